@@ -9,12 +9,12 @@ use crate::DomainError;
 
 /// 列约束
 ///
-/// 定义列的约束条件，包括 NOT NULL、UNIQUE、PRIMARY KEY 和 AUTOINCREMENT。
+/// 定义列的约束条件，包括 NOT NULL、UNIQUE、PRIMARY KEY、AUTOINCREMENT 和最大长度。
 /// 注意：AUTOINCREMENT 仅适用于 INTEGER PRIMARY KEY。
 ///
 /// 生命周期: 'static
 /// 线程安全: Send + Sync
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub struct ColumnConstraints {
   /// NOT NULL 约束
   pub not_null: bool,
@@ -24,12 +24,8 @@ pub struct ColumnConstraints {
   pub primary_key: bool,
   /// AUTOINCREMENT (仅 INTEGER PRIMARY KEY)
   pub autoincrement: bool,
-}
-
-impl Default for ColumnConstraints {
-  fn default() -> Self {
-    Self { not_null: false, unique: false, primary_key: false, autoincrement: false }
-  }
+  /// TEXT/BLOB 值的最大长度（按 `Value::len`：TEXT 为字符数，BLOB 为字节数），`None` 为不限制
+  pub max_length: Option<usize>,
 }
 
 /// 列实体
@@ -43,7 +39,9 @@ impl Default for ColumnConstraints {
 ///
 /// 声明周期: 'static
 /// 线程安全: Send + Sync
-#[derive(Debug, Clone)]
+///
+/// 只实现 `PartialEq`：默认值可能是 `Value::Real`，浮点数不满足 `Eq`/`Hash`。
+#[derive(Debug, Clone, PartialEq)]
 pub struct Column {
   pub id: ColumnId,
   pub name: String,
@@ -88,6 +86,26 @@ impl Column {
     Self { id, name, data_type, constraints, default_value: None }
   }
 
+  /// 比较两列的逻辑结构（忽略列 ID）
+  ///
+  /// 名称、数据类型、约束和默认值都相同时返回 `true`，用于 schema 对比。
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::{Column, ColumnId, DataType};
+  ///
+  /// let a = Column::new(ColumnId::new(1), "id".to_string(), DataType::Integer);
+  /// let b = Column::new(ColumnId::new(2), "id".to_string(), DataType::Integer);
+  /// assert_ne!(a, b);
+  /// assert!(a.same_shape(&b));
+  ///
+  pub fn same_shape(&self, other: &Column) -> bool {
+    self.name == other.name
+      && self.data_type == other.data_type
+      && self.constraints == other.constraints
+      && self.default_value == other.default_value
+  }
+
   /// 验证值是否符合列定义
   ///
   /// 检查值是否:
@@ -111,17 +129,65 @@ impl Column {
       return Err(DomainError::NotNullViolation { name: self.name.clone() });
     }
 
-    // 检查类型匹配
-    let value_type = value.data_type();
-    if value_type != self.data_type && !matches!(value, Value::Null) {
+    // 检查类型匹配（NULL 匹配任意类型）
+    if !self.data_type.matches(value) {
       return Err(DomainError::TypeMismatch {
         name: self.name.clone(),
         expected: self.data_type.to_sql_type().to_string(),
-        got: value_type.to_sql_type().to_string(),
+        got: value.data_type().to_sql_type().to_string(),
       });
     }
 
-    Ok(())
+    self.check_length(value)
+  }
+
+  /// 检查 TEXT/BLOB 值是否超过 `max_length`；NULL 和数字不受限制
+  fn check_length(&self, value: &Value) -> Result<(), DomainError> {
+    match (self.constraints.max_length, value.len()) {
+      (Some(max), Some(got)) if got > max => {
+        Err(DomainError::LengthExceeded { name: self.name.clone(), max, got })
+      }
+      _ => Ok(()),
+    }
+  }
+
+  /// 按列亲和性（affinity）验证并转换值
+  ///
+  /// 与 `validate_value` 不同，兼容的值会被无损转换后返回：
+  /// - INTEGER 列：整数值的 `Real`（如 `3.0`）、可解析为整数的 `Text` -> `Integer`
+  /// - REAL 列：`Integer`（|i| <= 2^53，可精确表示）、可解析为数字的 `Text` -> `Real`
+  /// - TEXT 列：`Integer`/`Real` -> 十进制文本
+  /// - BLOB 列：不做转换
+  ///
+  /// 无法无损转换或违反 NOT NULL 时返回错误；`validate_value` 保持严格语义不变。
+  /// 转换规则与 `Value::coerce_to` 相同。
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::{Column, ColumnId, DataType, Value};
+  ///
+  /// let column = Column::new(ColumnId::new(1), "n".to_string(), DataType::Integer);
+  ///
+  /// assert_eq!(column.validate_value_coercing(&Value::Real(3.0)), Ok(Value::Integer(3)));
+  /// assert!(column.validate_value_coercing(&Value::Real(3.5)).is_err());
+  ///
+  pub fn validate_value_coercing(&self, value: &Value) -> Result<Value<'static>, DomainError> {
+    if matches!(value, Value::Null) {
+      self.validate_value(value)?;
+      return Ok(Value::Null);
+    }
+
+    match value.coerce_to(self.data_type) {
+      // 转换可能改变长度（如数字转为文本），按转换后的值检查
+      Some(v) => {
+        self.check_length(&v)?;
+        Ok(v)
+      }
+      None => {
+        self.validate_value(value)?;
+        Ok(value.clone().into_owned())
+      }
+    }
   }
 }
 
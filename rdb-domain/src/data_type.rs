@@ -12,6 +12,9 @@ use crate::Value;
 /// - `Text`: UTF-8 字符串
 /// - `Blob`: 二进制数据
 ///
+/// 以及派生类型:
+/// - `Json`: JSON 文本（物理上按 `Text` 存储）
+///
 /// 生命周期: 'static
 /// 线程安全: Send + Sync
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -24,6 +27,8 @@ pub enum DataType {
   Text,
   /// BLOB 类型（二进制数据）
   Blob,
+  /// JSON 类型（物理存储为 TEXT）
+  Json,
 }
 
 impl DataType {
@@ -34,6 +39,7 @@ impl DataType {
   /// - `REAL`, `FLOAT`, `DOUBLE`, `DOUBLE PRECISION` -> `Real`
   /// - `TEXT`, `VARCHAR`, `CHAR`, `STRING` -> `Text`
   /// - `BLOB`, `BINARY` -> `Blob`
+  /// - `JSON` -> `Json`
   ///
   /// # Examples
   ///
@@ -50,6 +56,7 @@ impl DataType {
       "REAL" | "FLOAT" | "DOUBLE" | "DOUBLE PRECISION" => Some(Self::Real),
       "TEXT" | "VARCHAR" | "CHAR" | "STRING" => Some(Self::Text),
       "BLOB" | "BINARY" => Some(Self::Blob),
+      "JSON" => Some(Self::Json),
       _ => None,
     }
   }
@@ -73,6 +80,7 @@ impl DataType {
       DataType::Real => "REAL",
       DataType::Text => "TEXT",
       DataType::Blob => "BLOB",
+      DataType::Json => "JSON",
     }
   }
 
@@ -81,6 +89,9 @@ impl DataType {
   /// 注意：此方法需要 `Value` 类型已实现。当前为占位。
   /// 等 `Value` 类型实现后（T017），需要根据 `Value` 的实际类型进行匹配。
   ///
+  /// `Json` 接受任意 `Text`，不在这里解析 JSON：解析依赖可选的 `json` feature，
+  /// 且代价与文本长度成正比。内容校验推迟到读取时（`Value::as_json`）。
+  ///
   /// # Examples
   ///
   /// use rdb_domain::{DataType, Value};
@@ -93,8 +104,47 @@ impl DataType {
       Value::Null => true,
       Value::Integer(_) => matches!(self, DataType::Integer),
       Value::Real(_) => matches!(self, DataType::Real),
-      Value::Text(_) => matches!(self, DataType::Text),
+      Value::Text(_) | Value::SharedText(_) => matches!(self, DataType::Text | DataType::Json),
       Value::Blob(_) => matches!(self, DataType::Blob),
     }
   }
+
+  /// 从一组样本值推断列类型（如根据 CSV 建表）
+  ///
+  /// 选择能覆盖所有非 NULL 值的最具体的类型，按 `Integer < Real < Text < Blob` 取最宽者：
+  /// - 全是 `Integer` -> `Integer`
+  /// - `Integer` 与 `Real` 混合 -> `Real`
+  /// - 出现 `Text` -> `Text`
+  /// - 出现 `Blob` -> `Blob`
+  /// - 空序列或全为 NULL -> `Text`（最安全的默认值）
+  ///
+  /// 不会推断出 `Json`。
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::{DataType, Value};
+  ///
+  /// let values = [Value::Integer(1), Value::Null, Value::Real(2.5)];
+  /// assert_eq!(DataType::infer_from_values(&values), DataType::Real);
+  ///
+  pub fn infer_from_values(values: &[Value<'_>]) -> DataType {
+    let rank = |data_type: DataType| match data_type {
+      DataType::Integer => 0,
+      DataType::Real => 1,
+      DataType::Text | DataType::Json => 2,
+      DataType::Blob => 3,
+    };
+
+    values
+      .iter()
+      .filter_map(|value| match value {
+        Value::Null => None,
+        Value::Integer(_) => Some(DataType::Integer),
+        Value::Real(_) => Some(DataType::Real),
+        Value::Text(_) | Value::SharedText(_) => Some(DataType::Text),
+        Value::Blob(_) => Some(DataType::Blob),
+      })
+      .max_by_key(|&data_type| rank(data_type))
+      .unwrap_or(DataType::Text)
+  }
 }
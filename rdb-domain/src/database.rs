@@ -4,11 +4,39 @@
 
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
 
 use crate::ids::{IndexId, TableId};
-use crate::table::Table;
+use crate::row::Row;
+use crate::table::{is_system_table_name, Table};
 use crate::DomainError;
 
+/// 模式版本号：可跨线程共享的原子计数器
+///
+/// `clone` 得到指向同一计数器的句柄。`Database` 通常放在 `Arc<Mutex<Database>>` 中，
+/// 缓存层持有一个句柄即可无锁地检查版本是否过期，不必锁住整个 `Database`。
+/// 递增只发生在持有 `&mut Database` 的 DDL 操作中，因此写者始终只有一个。
+#[derive(Debug, Clone, Default)]
+pub struct SchemaVersion(Arc<AtomicU32>);
+
+impl SchemaVersion {
+  /// 当前版本号
+  pub fn get(&self) -> u32 {
+    self.0.load(Ordering::Acquire)
+  }
+
+  /// 递增版本号，返回递增后的值
+  pub fn increment(&self) -> u32 {
+    self.0.fetch_add(1, Ordering::AcqRel) + 1
+  }
+
+  /// 直接设置版本号（恢复/测试用，调用方负责保证单调递增）
+  pub fn set(&self, version: u32) {
+    self.0.store(version, Ordering::Release);
+  }
+}
+
 /// 数据库聚合根
 ///
 /// 管理数据库的表、索引和全局状态。
@@ -28,13 +56,12 @@ pub struct Database {
   /// 表集合（表 ID -> 表定义）
   pub tables: HashMap<TableId, Table>,
 
-  /// 索引集合（索引 ID -> 表定义）
-  /// 注意: Index 类型尚未实现，暂时使用占位符
+  /// 索引集合（索引 ID -> 索引所属的表 ID）
+  /// 注意: Index 类型尚未实现，暂时只记录索引引用的表
   /// TODO: 实现 Index 类型后替换为 HashMap<IndexId, Index>
-  #[allow(dead_code)]
-  pub indexes: HashMap<IndexId, ()>,
-  /// 模式版本号（每次 DDL 操作递增）
-  pub schema_version: u32,
+  pub indexes: HashMap<IndexId, TableId>,
+  /// 模式版本号（每次 DDL 操作递增），见 `SchemaVersion`
+  pub schema_version: SchemaVersion,
 }
 
 impl Database {
@@ -58,17 +85,24 @@ impl Database {
       path: path.as_ref().to_path_buf(),
       tables: HashMap::new(),
       indexes: HashMap::new(),
-      schema_version: 0,
+      schema_version: SchemaVersion::default(),
     }
   }
 
+  /// 当前模式版本号
+  ///
+  /// 需要在不持有 `Database` 的情况下读取时，`clone` 一份 `schema_version` 句柄。
+  pub fn schema_version(&self) -> u32 {
+    self.schema_version.get()
+  }
+
   /// 添加表（DDL 操作）
   ///
   /// 将表添加到数据库中，并递增 schema_version。
   ///
   /// 不变量检查：
   /// - 表名必须唯一
-  /// - 表必须至少有一列
+  /// - 表定义本身有效（见 `Table::validate`）
   ///
   /// # Arguments
   ///
@@ -91,23 +125,28 @@ impl Database {
   ///
   /// let table_id = db.add_table(table)?;
   pub fn add_table(&mut self, table: Table) -> Result<TableId, DomainError> {
-    // 检查表明是否唯一
-    if self.tables.values().any(|t| t.name == table.name) {
-      return Err(DomainError::TableAlreadyExists { name: table.name });
-    }
-
-    // 检查表是否有列
-    if table.columns.is_empty() {
-      return Err(DomainError::TableMusthHaveColumns);
-    }
+    self.can_add_table(&table)?;
 
     let table_id = table.id;
     self.tables.insert(table_id, table);
-    self.schema_version += 1;
+    self.schema_version.increment();
 
     Ok(table_id)
   }
 
+  /// 预检 `add_table`：执行相同的检查但不修改数据库（不插入表、不递增 schema 版本）
+  ///
+  /// 返回 `Ok(())` 表示对当前数据库调用 `add_table` 会成功，供迁移工具预览失败原因。
+  pub fn can_add_table(&self, table: &Table) -> Result<(), DomainError> {
+    // 检查表明是否唯一
+    if self.tables.values().any(|t| t.name == table.name) {
+      return Err(DomainError::TableAlreadyExists { name: table.name.clone() });
+    }
+
+    // 检查表定义的不变量
+    table.validate()
+  }
+
   /// 删除表（级联删除关联索引）
   ///
   /// 从数据库中删除表，并删除所有关联的索引
@@ -118,7 +157,7 @@ impl Database {
   ///
   /// # Returns
   ///
-  /// 如果表不存在则返回错误
+  /// 如果表不存在则返回错误；系统表（见 `Table::is_system`）返回 `CannotDropSystemTable`
   ///
   /// # Examples
   ///
@@ -129,22 +168,45 @@ impl Database {
   ///
   /// db.drop_table(TableId::new(1)).unwrap();
   pub fn drop_table(&mut self, table_id: TableId) -> Result<(), DomainError> {
-    if !self.tables.contains_key(&table_id) {
-      return Err(DomainError::TableNotFound { table_id });
-    }
+    self.can_drop_table(table_id)?;
 
     // 删除表
     self.tables.remove(&table_id);
 
-    // TODO: 级联删除关联的索引
-    // 当 Index 类型实现后，需要删除所有引用此表的索引
-    // self.indexes.retain(|_, index| index.table_id != table_id);
+    // 级联删除引用此表的索引
+    self.indexes.retain(|_, t| *t != table_id);
 
-    self.schema_version += 1;
+    self.schema_version.increment();
 
     Ok(())
   }
 
+  /// 预检 `drop_table`：执行相同的检查但不修改数据库
+  pub fn can_drop_table(&self, table_id: TableId) -> Result<(), DomainError> {
+    let Some(table) = self.tables.get(&table_id) else {
+      return Err(DomainError::TableNotFound { table_id });
+    };
+    if table.is_system() {
+      return Err(DomainError::CannotDropSystemTable { name: table.name.clone() });
+    }
+    Ok(())
+  }
+
+  /// 按表名删除表
+  ///
+  /// 系统表名（`rdb_` 前缀，包括不在 `tables` 中的 `rdb_master`）总是返回
+  /// `CannotDropSystemTable`；表不存在返回 `TableNameNotFound`。
+  pub fn drop_table_by_name(&mut self, name: &str) -> Result<(), DomainError> {
+    if is_system_table_name(name) {
+      return Err(DomainError::CannotDropSystemTable { name: name.to_string() });
+    }
+    let table_id = self
+      .get_table_by_name(name)
+      .map(|t| t.id)
+      .ok_or_else(|| DomainError::TableNameNotFound { name: name.to_string() })?;
+    self.drop_table(table_id)
+  }
+
   /// 获取表定义（不可变引用）
   ///
   /// # Arguments
@@ -172,7 +234,9 @@ impl Database {
   ///
   /// # Returns
   ///
-  /// 如果找到则返回表的引用，否则返回 None
+  /// 如果找到则返回表的引用，否则返回 None。
+  /// 正常情况下表名唯一；若数据库已损坏出现同名表，固定返回表 ID 最小的那张
+  /// （可用 `validate_invariants` 检出这种情况）。
   ///
   /// # Examples
   ///
@@ -184,7 +248,11 @@ impl Database {
   /// let table = db.get_table_by_name("users");
   ///
   pub fn get_table_by_name(&self, name: &str) -> Option<&Table> {
-    self.tables.values().find(|t| t.name == name)
+    self
+      .tables
+      .values()
+      .filter(|t| t.name == name)
+      .min_by_key(|t| t.id.into_inner())
   }
 
   /// 添加索引（DDL 操作）
@@ -219,8 +287,8 @@ impl Database {
     // TODO: 实现 Index 类型后完善此方法
     // 检查索引列是否在表中存在
     // 添加索引并递增 schema_version
-    self.indexes.insert(index_id, ());
-    self.schema_version += 1;
+    self.indexes.insert(index_id, table_id);
+    self.schema_version.increment();
 
     Ok(index_id)
   }
@@ -242,6 +310,78 @@ impl Database {
   pub fn table_count(&self) -> usize {
     self.tables.len()
   }
+
+  /// 自检聚合根的不变量
+  ///
+  /// `add_table` 等方法会维护这些不变量，但直接修改字段或反序列化得到的
+  /// `Database` 可能不一致。按表 ID 升序检查，返回描述第一个问题的 `InvariantViolation`：
+  /// - 表集合的 key 与表自身的 ID 一致
+  /// - 表名唯一
+  /// - 索引引用的表存在（按索引 ID 升序检查）
+  /// - schema_version 不小于表和索引的总数（每次添加都会递增版本号）
+  pub fn validate_invariants(&self) -> Result<(), DomainError> {
+    let violation = |message: String| Err(DomainError::InvariantViolation { message });
+
+    let mut tables: Vec<(&TableId, &Table)> = self.tables.iter().collect();
+    tables.sort_by_key(|(id, _)| id.into_inner());
+
+    for (i, &(id, table)) in tables.iter().enumerate() {
+      if table.id != *id {
+        return violation(format!("table {:?} is stored under id {:?}", table.id, id));
+      }
+      if let Some((_, first)) = tables[..i].iter().find(|(_, t)| t.name == table.name) {
+        return violation(format!(
+          "duplicate table name '{}' ({:?} and {:?})",
+          table.name, first.id, table.id
+        ));
+      }
+    }
+
+    let mut indexes: Vec<(&IndexId, &TableId)> = self.indexes.iter().collect();
+    indexes.sort_by_key(|(id, _)| id.into_inner());
+
+    for (index_id, table_id) in indexes {
+      if !self.tables.contains_key(table_id) {
+        return violation(format!(
+          "index {index_id:?} references missing table {table_id:?}"
+        ));
+      }
+    }
+
+    let objects = self.tables.len() + self.indexes.len();
+    let version = self.schema_version();
+    if (version as usize) < objects {
+      return violation(format!(
+        "schema version {version} is lower than the number of schema objects {objects}"
+      ));
+    }
+
+    Ok(())
+  }
+
+  /// 系统目录 `rdb_master` 的内容（见 `Table::master`）
+  ///
+  /// 每张表一行，按表 ID 升序，rowid 为表 ID。
+  /// TODO: 实现 Index 类型后为每个索引输出 `type = 'index'` 的行
+  pub fn master_rows(&self) -> Vec<Row<'static>> {
+    let mut tables: Vec<&Table> = self.tables.values().collect();
+    tables.sort_by_key(|t| t.id.into_inner());
+    tables.iter().map(|t| t.to_master_row()).collect()
+  }
+
+  /// 导出整个 schema 的建表语句
+  ///
+  /// 按表 ID 升序输出每张表的 `Table::to_create_sql`，每条语句以 `;\n` 结尾，
+  /// 因此相同的 schema 总是得到相同的文本。引用其他表的外键按本数据库中的表解析。
+  pub fn to_schema_sql(&self) -> String {
+    let mut tables: Vec<&Table> = self.tables.values().collect();
+    tables.sort_by_key(|t| t.id.into_inner());
+
+    tables
+      .iter()
+      .map(|t| format!("{};\n", t.to_create_sql_with(|id| self.tables.get(&id))))
+      .collect()
+  }
 }
 
 // 保证 Database 是 Send + Sync
@@ -24,6 +24,10 @@ pub enum DomainError {
   #[error("Table '{name} does not exist")]
   TableNameNotFound { name: String },
 
+  /// 表名不能为空
+  #[error("Table name must not be empty")]
+  EmptyTableName,
+
   /// 表必须至少有一列
   #[error("Table must have at least one column")]
   TableMusthHaveColumns,
@@ -32,22 +36,62 @@ pub enum DomainError {
   #[error("Column '{name}' does not exist in table")]
   ColumnNotFound { name: String },
 
+  /// 列名重复
+  #[error("Duplicate column name '{name}'")]
+  DuplicateColumnName { name: String },
+
   /// 列 ID 不存在
   #[error("Column with ID {column_id:?} does not exist in table")]
   ColumnIdNotFound { column_id: ColumnId },
 
+  /// 列下标越界
+  #[error("Column index {index} out of range (row has {len} values)")]
+  ColumnIndexOutOfRange { index: usize, len: usize },
+
   /// 约束违反：NOT NULL
   #[error("Column '{name}' does not allow NULL values")]
   NotNullViolation { name: String },
 
+  /// 约束违反：值长度超过列的最大长度
+  #[error("Value for column '{name}' is too long (max {max}, got {got})")]
+  LengthExceeded { name: String, max: usize, got: usize },
+
   /// 约束违反：类型不匹配
   #[error("Value type does not match column '{name}' type (expected: {expected:?}, got: {got:?})")]
   TypeMismatch { name: String, expected: String, got: String },
 
+  /// 行的值数量与表的列数不一致
+  #[error("Row has {got} values but table has {expected} columns")]
+  RowLengthMismatch { expected: usize, got: usize },
+
   /// 主键引用无效
   #[error("Primary key column {column_id:?} does not exist in table")]
   InvalidPrimaryKeyReference { column_id: ColumnId },
 
+  /// 外键引用了不存在的列
+  #[error("Foreign key column {column_id:?} does not exist in table")]
+  InvalidForeignKeyReference { column_id: ColumnId },
+
+  /// 外键的引用列与被引用列数量不一致（或为空）
+  #[error("Foreign key has {columns} columns but references {ref_columns}")]
+  ForeignKeyColumnCountMismatch { columns: usize, ref_columns: usize },
+
+  /// 多列唯一约束没有列
+  #[error("Unique constraint must have at least one column")]
+  EmptyUniqueConstraint,
+
+  /// 约束违反：多列唯一约束上的值组合与已有行重复
+  #[error("UNIQUE constraint failed on ({})", columns.join(", "))]
+  UniqueViolation { columns: Vec<String> },
+
+  /// 表声明了多个主键
+  #[error("Table has more than one primary key")]
+  MultiplePrimaryKeys,
+
+  /// AUTOINCREMENT 只能用于 INTEGER PRIMARY KEY
+  #[error("AUTOINCREMENT is only allowed on an INTEGER PRIMARY KEY (column '{name}')")]
+  InvalidAutoincrement { name: String },
+
   /// 索引相关错误：表不存在
   #[error("Table with ID {table_id:?} does not exist for index")]
   IndexTableNotFound { table_id: TableId },
@@ -76,6 +120,10 @@ pub enum DomainError {
   #[error("System table '{name}' cannot be dropped")]
   CannotDropSystemTable { name: String },
 
+  /// 存储层错误（由存储层错误转换而来，只保留错误信息文本）
+  #[error("Storage error: {message}")]
+  Storage { message: String },
+
   /// 不变量违反（通用）
   #[error("Invariant violation: {message}")]
   InvariantViolation { message: String },
@@ -2,6 +2,8 @@
 //!
 //! 使用 newtype 模式提供类型安全的 ID，防止不同类型的 ID 混淆。
 
+use serde::{Deserialize, Serialize};
+
 /// 表 ID（newtype 模式）
 ///
 /// 用于唯一标识数据库中的表。
@@ -104,7 +106,7 @@ impl From<IndexId> for u32 {
 ///
 /// 用于唯一标识表中的行
 /// 底层类型：`i64` (支持负数，SQLite 兼容)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct RowId(i64);
 
 impl RowId {
@@ -175,7 +177,7 @@ pub struct PageId(u32);
 
 impl PageId {
   #[inline]
-  pub fn new(id: u32) -> Self {
+  pub const fn new(id: u32) -> Self {
     Self(id)
   }
 
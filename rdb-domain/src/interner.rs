@@ -0,0 +1,64 @@
+//! 字符串驻留
+//!
+//! 宽表中低基数的文本列（状态、国家代码等）每行都会解码出一份相同的 `String`。
+//! `StringInterner` 为每个不同的字符串只保存一份 `Arc<str>`，`Value::intern` 把文本换成
+//! 共享它的 `Value::SharedText`。
+//!
+//! 与 `Cow`/生命周期模型的关系：
+//! - `Cow::Borrowed` 只能借用比值活得久的存储，驻留后的行就会被驻留器的生命周期绑住；
+//!   因此驻留使用单独的 `SharedText(Arc<str>)` 变体，得到的是 `Value<'static>`，
+//!   可以放进 `Row<'static>`，驻留器先于这些值 drop 也没有问题
+//! - `SharedText` 与 `Text` 语义相同（比较、哈希、编码、序列化一致），按文本读取请用 `as_text`
+//! - 字符串在驻留器和最后一个引用它的值都 drop 后释放，不会泄漏；`into_owned` 只克隆 `Arc`
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+/// 字符串驻留器（可选，按需创建）
+///
+/// 只增不减：同一个驻留器存活期间，已驻留的字符串不会被移除。
+/// 内部用 `Mutex` 保护，可在多个线程间共享。
+#[derive(Debug, Default)]
+pub struct StringInterner {
+  strings: Mutex<HashSet<Arc<str>>>,
+}
+
+impl StringInterner {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// 驻留 `s`，返回驻留器中唯一的那一份
+  ///
+  /// 相同内容的字符串多次驻留返回同一个 `Arc`。
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::StringInterner;
+  /// use std::sync::Arc;
+  ///
+  /// let interner = StringInterner::new();
+  /// let a = interner.intern("active");
+  /// let b = interner.intern(&String::from("active"));
+  /// assert!(Arc::ptr_eq(&a, &b));
+  ///
+  pub fn intern(&self, s: &str) -> Arc<str> {
+    let mut strings = self.strings.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(existing) = strings.get(s) {
+      return Arc::clone(existing);
+    }
+    let shared: Arc<str> = Arc::from(s);
+    strings.insert(Arc::clone(&shared));
+    shared
+  }
+
+  /// 已驻留的不同字符串个数
+  pub fn len(&self) -> usize {
+    self.strings.lock().unwrap_or_else(|e| e.into_inner()).len()
+  }
+
+  /// 是否尚未驻留任何字符串
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+}
@@ -7,15 +7,19 @@ pub mod data_type;
 pub mod database;
 pub mod error;
 pub mod ids;
+pub mod interner;
 pub mod row;
+pub mod sort;
 pub mod table;
 pub mod value;
 
 pub use column::{Column, ColumnConstraints};
 pub use data_type::DataType;
-pub use database::Database;
+pub use database::{Database, SchemaVersion};
 pub use error::DomainError;
 pub use ids::{ColumnId, IndexId, LockId, PageId, RowId, TableId, TransactionId};
-pub use row::Row;
-pub use table::Table;
+pub use interner::StringInterner;
+pub use row::{Row, RowBuilder};
+pub use sort::{NullsOrder, RowComparator, SortDirection};
+pub use table::{ForeignKey, ForeignKeyAction, Table, MASTER_TABLE_NAME, SYSTEM_TABLE_PREFIX};
 pub use value::Value;
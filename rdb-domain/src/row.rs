@@ -2,9 +2,14 @@
 //!
 //! 定义数据库表中的行数据，包含行 ID 和列值
 
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
 use crate::ids::RowId;
 use crate::table::Table;
-use crate::value::Value;
+use crate::value::{real_to_integer, Value};
+use crate::DomainError;
 
 /// 行实体
 ///
@@ -16,11 +21,12 @@ use crate::value::Value;
 ///
 /// 生命周期: 'r (可能引用外部数据)
 /// 线程安全: Send + Sync
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Row<'r> {
   /// 行 ID （等同于 INTEGER PRIMARY KEY)
   pub row_id: RowId,
   /// 列值
+  #[serde(borrow)]
   pub values: Vec<Value<'r>>,
 }
 
@@ -62,6 +68,27 @@ impl<'r> Row<'r> {
     self.values.get(index)
   }
 
+  /// 替换列值（按索引），返回原来的值
+  ///
+  /// 原地交换，不克隆整行；索引越界时返回 `ColumnIndexOutOfRange`。
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::{Row, RowId, Value};
+  ///
+  /// let mut row = Row::new(RowId::new(1), vec![Value::Integer(1)]);
+  /// let old = row.replace(0, Value::Integer(2))?;
+  /// assert_eq!(old, Value::Integer(1));
+  /// assert_eq!(row.get(0), Some(&Value::Integer(2)));
+  pub fn replace(&mut self, index: usize, value: Value<'r>) -> Result<Value<'r>, DomainError> {
+    let len = self.values.len();
+    let slot = self
+      .values
+      .get_mut(index)
+      .ok_or(DomainError::ColumnIndexOutOfRange { index, len })?;
+    Ok(std::mem::replace(slot, value))
+  }
+
   /// 获取列值（按列名）
   ///
   /// 根据列名从表中查找对应的列，然后返回改列的值。
@@ -110,8 +137,161 @@ impl<'r> Row<'r> {
   pub fn into_owned(self) -> Row<'static> {
     Row { row_id: self.row_id, values: self.values.into_iter().map(|v| v.into_owned()).collect() }
   }
+
+  /// 换一个 row_id（移动值，不克隆）
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::{Row, RowId, Value};
+  ///
+  /// let row = Row::new(RowId::new(1), vec![Value::Integer(1)]);
+  /// let copy = row.with_row_id(RowId::new(2));
+  /// assert_eq!(copy.row_id, RowId::new(2));
+  pub fn with_row_id(self, row_id: RowId) -> Row<'r> {
+    Row { row_id, values: self.values }
+  }
+
+  /// 以新的 row_id 克隆为拥有数据的行
+  ///
+  /// 与 `into_owned` 相同会克隆借用数据，但保留原行不动。
+  pub fn clone_with_row_id(&self, row_id: RowId) -> Row<'static> {
+    Row { row_id, values: self.values.iter().map(|v| v.clone().into_owned()).collect() }
+  }
+
+  /// 集合运算（`UNION`/`INTERSECT`/`EXCEPT`）语义的行相等
+  ///
+  /// 按位置比较值，忽略 `row_id`：
+  /// - 同一位置上两个 NULL 视为相等（与 `Value::sql_compare` 中 NULL 不可比较不同）
+  /// - `Integer` 与 `Real` 按数值相等判断，如 `1` 与 `1.0` 相等
+  ///   （与 `Value` 的 `==` 不同，后者要求类型也相同）
+  /// - 其他情况要求类型和值都相同
+  ///
+  /// 值数量不同的两行不相等。
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::{Row, RowId, Value};
+  ///
+  /// let a = Row::new(RowId::new(1), vec![Value::Null, Value::Integer(1)]);
+  /// let b = Row::new(RowId::new(2), vec![Value::Null, Value::Real(1.0)]);
+  /// assert!(a.sql_eq(&b));
+  pub fn sql_eq(&self, other: &Row<'_>) -> bool {
+    self.values.len() == other.values.len()
+      && self
+        .values
+        .iter()
+        .zip(&other.values)
+        .all(|(a, b)| match (a, b) {
+          (Value::Integer(i), Value::Real(r)) | (Value::Real(r), Value::Integer(i)) => {
+            real_to_integer(*r) == Some(*i)
+          }
+          _ => a == b,
+        })
+  }
+
+  /// 把行在 `indices` 位置上的值与搜索键按字典序比较（索引 seek 用）
+  ///
+  /// 逐列用 `Value::sql_compare` 比较，第一个不相等的列决定结果：
+  /// - 任一列比较结果不确定（NULL 或类型不可比较）时返回 `None`
+  /// - `indices` 越界时返回 `None`
+  /// - `key` 比 `indices` 短时只比较前缀，前缀全部相等即返回 `Equal`（前缀 seek）
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::{Row, RowId, Value};
+  /// use std::cmp::Ordering;
+  ///
+  /// let row = Row::new(RowId::new(1), vec![Value::Integer(1), Value::Integer(5)]);
+  /// let key = [Value::Integer(5), Value::Integer(1)];
+  /// assert_eq!(row.compare_key(&[1, 0], &key), Some(Ordering::Equal));
+  pub fn compare_key(&self, indices: &[usize], key: &[Value<'_>]) -> Option<Ordering> {
+    for (&index, k) in indices.iter().zip(key) {
+      match self.get(index)?.sql_compare(k)? {
+        Ordering::Equal => {}
+        other => return Some(other),
+      }
+    }
+    Some(Ordering::Equal)
+  }
+}
+
+impl Row<'static> {
+  /// 从 bincode 字节反序列化出拥有所有权的行（需要 `bincode` feature）
+  ///
+  /// 与 `Value::deserialize_owned` 相同：先借用 `bytes` 反序列化，再 `into_owned`。
+  #[cfg(feature = "bincode")]
+  pub fn deserialize_owned(bytes: &[u8]) -> Result<Row<'static>, bincode::Error> {
+    let row: Row<'_> = bincode::deserialize(bytes)?;
+    Ok(row.into_owned())
+  }
 }
 
 // 保证 Row 是 Send + Sync
 unsafe impl<'r> Send for Row<'r> {}
 unsafe impl<'r> Sync for Row<'r> {}
+
+/// 按列名构造行
+///
+/// 按列名设置值，`build` 时按表的列顺序放到正确位置，避免列顺序变化导致的错位。
+/// 未设置的列使用列默认值，没有默认值则为 NULL。
+///
+/// 生命周期: 't (借用表定义)
+#[derive(Debug)]
+pub struct RowBuilder<'t> {
+  table: &'t Table,
+  row_id: RowId,
+  values: Vec<Option<Value<'static>>>,
+  /// 第一个未知列名（延迟到 build 时报错，保证链式调用）
+  unknown_column: Option<String>,
+}
+
+impl<'t> RowBuilder<'t> {
+  /// 创建行构造器
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::{RowBuilder, RowId, Value};
+  ///
+  /// let row = RowBuilder::new(&table, RowId::new(1))
+  ///   .set("id", Value::Integer(1))
+  ///   .build()?;
+  ///
+  pub fn new(table: &'t Table, row_id: RowId) -> Self {
+    Self { table, row_id, values: vec![None; table.columns.len()], unknown_column: None }
+  }
+
+  /// 按列名设置值（重复设置时后者覆盖前者）
+  pub fn set(mut self, name: &str, value: Value<'_>) -> Self {
+    match self.table.columns.iter().position(|col| col.name == name) {
+      Some(index) => self.values[index] = Some(value.into_owned()),
+      None => {
+        if self.unknown_column.is_none() {
+          self.unknown_column = Some(name.to_string());
+        }
+      }
+    }
+    self
+  }
+
+  /// 构造行
+  ///
+  /// 错误：
+  /// - 设置过不存在的列名：`ColumnNotFound`
+  /// - 值不符合列定义（NOT NULL、类型）：见 `Column::validate_value`
+  pub fn build(self) -> Result<Row<'static>, DomainError> {
+    if let Some(name) = self.unknown_column {
+      return Err(DomainError::ColumnNotFound { name });
+    }
+
+    let mut values = Vec::with_capacity(self.values.len());
+    for (column, value) in self.table.columns.iter().zip(self.values) {
+      let value = value
+        .or_else(|| column.default_value.clone())
+        .unwrap_or(Value::Null);
+      column.validate_value(&value)?;
+      values.push(value);
+    }
+
+    Ok(Row::new(self.row_id, values))
+  }
+}
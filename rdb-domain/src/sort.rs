@@ -0,0 +1,181 @@
+//! 行排序
+//!
+//! 多列 ORDER BY 比较器：按给定的列顺序、方向和 NULL 位置比较两行。
+
+use std::cmp::Ordering;
+
+use crate::row::Row;
+use crate::value::Value;
+
+/// 排序方向
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortDirection {
+  #[default]
+  Ascending,
+  Descending,
+}
+
+/// NULL 的位置（与排序方向无关）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NullsOrder {
+  /// NULL 排在最前
+  First,
+  /// NULL 排在最后
+  Last,
+}
+
+/// 多列行比较器
+///
+/// 依次比较每个排序键，前一个键相等时才比较下一个。
+/// 非 NULL 值先用 `Value::sql_compare` 比较；`Integer` 与 `Real` 按数值精确比较（不经 `f64` 舍入）；
+/// NaN 排在所有其他数字之前，NaN 之间相等；
+/// 其他不同类型按 SQLite 的类型顺序：数字 < `Text` < `Blob`。列下标越界时该值按 NULL 处理。
+///
+/// # Examples
+///
+/// use rdb_domain::sort::{NullsOrder, RowComparator, SortDirection};
+/// use rdb_domain::{Row, RowId, Value};
+///
+/// let mut rows = vec![
+///   Row::new(RowId::new(1), vec![Value::Integer(2), Value::Real(1.5)]),
+///   Row::new(RowId::new(2), vec![Value::Null, Value::Real(0.5)]),
+///   Row::new(RowId::new(3), vec![Value::Integer(2), Value::Real(f64::NAN)]),
+/// ];
+///
+/// let cmp = RowComparator::new([
+///   (0, SortDirection::Ascending, NullsOrder::First),
+///   (1, SortDirection::Descending, NullsOrder::Last),
+/// ]);
+/// rows.sort_by(|a, b| cmp.compare(a, b));
+///
+/// let ids: Vec<i64> = rows.iter().map(|r| r.row_id.into_inner()).collect();
+/// assert_eq!(ids, vec![2, 1, 3]);
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowComparator {
+  keys: Vec<(usize, SortDirection, NullsOrder)>,
+}
+
+impl RowComparator {
+  /// 由 `(列下标, 方向, NULL 位置)` 列表创建比较器
+  pub fn new(keys: impl IntoIterator<Item = (usize, SortDirection, NullsOrder)>) -> Self {
+    Self { keys: keys.into_iter().collect() }
+  }
+
+  /// 比较两行
+  pub fn compare(&self, a: &Row<'_>, b: &Row<'_>) -> Ordering {
+    for &(column, direction, nulls) in &self.keys {
+      let ordering = compare_values(a.get(column), b.get(column), direction, nulls);
+      if ordering != Ordering::Equal {
+        return ordering;
+      }
+    }
+    Ordering::Equal
+  }
+}
+
+/// 按 `RowComparator` 的规则比较两个单列键（升序，NULL 在前）
+///
+/// 给一组值排序时使用，如索引统计。
+///
+/// # Examples
+///
+/// use rdb_domain::sort::compare_keys;
+/// use rdb_domain::Value;
+/// use std::cmp::Ordering;
+///
+/// assert_eq!(compare_keys(&Value::Integer(3), &Value::Real(2.5)), Ordering::Greater);
+/// assert_eq!(compare_keys(&Value::Null, &Value::Integer(0)), Ordering::Less);
+pub fn compare_keys(a: &Value<'_>, b: &Value<'_>) -> Ordering {
+  compare_values(
+    Some(a),
+    Some(b),
+    SortDirection::Ascending,
+    NullsOrder::First,
+  )
+}
+
+fn compare_values(
+  a: Option<&Value<'_>>,
+  b: Option<&Value<'_>>,
+  direction: SortDirection,
+  nulls: NullsOrder,
+) -> Ordering {
+  let a = a.filter(|v| !matches!(v, Value::Null));
+  let b = b.filter(|v| !matches!(v, Value::Null));
+
+  match (a, b) {
+    (None, None) => Ordering::Equal,
+    (None, Some(_)) => match nulls {
+      NullsOrder::First => Ordering::Less,
+      NullsOrder::Last => Ordering::Greater,
+    },
+    (Some(_), None) => match nulls {
+      NullsOrder::First => Ordering::Greater,
+      NullsOrder::Last => Ordering::Less,
+    },
+    (Some(a), Some(b)) => {
+      let ordering = compare_non_null(a, b);
+      match direction {
+        SortDirection::Ascending => ordering,
+        SortDirection::Descending => ordering.reverse(),
+      }
+    }
+  }
+}
+
+fn compare_non_null(a: &Value<'_>, b: &Value<'_>) -> Ordering {
+  match (a, b) {
+    (Value::Real(x), Value::Real(y)) => compare_reals(*x, *y),
+    (Value::Integer(i), Value::Real(r)) => compare_integer_real(*i, *r),
+    (Value::Real(r), Value::Integer(i)) => compare_integer_real(*i, *r).reverse(),
+    _ => a
+      .sql_compare(b)
+      .unwrap_or_else(|| type_rank(a).cmp(&type_rank(b))),
+  }
+}
+
+/// NaN 小于其他所有实数，NaN 之间相等
+fn compare_reals(x: f64, y: f64) -> Ordering {
+  match (x.is_nan(), y.is_nan()) {
+    (true, true) => Ordering::Equal,
+    (true, false) => Ordering::Less,
+    (false, true) => Ordering::Greater,
+    (false, false) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
+  }
+}
+
+/// 精确比较整数与实数
+///
+/// `i as f64` 在 |i| > 2^53 时会舍入（如 `2^53 + 1` 与 `2^53 as f64` 相等），
+/// 因此先比较实数的整数部分，再看小数部分。NaN 小于任何整数。
+fn compare_integer_real(i: i64, r: f64) -> Ordering {
+  // 2^63：i64 的范围是 [-2^63, 2^63)
+  const TWO_POW_63: f64 = 9_223_372_036_854_775_808.0;
+
+  if r.is_nan() {
+    return Ordering::Greater;
+  }
+  if r >= TWO_POW_63 {
+    return Ordering::Less;
+  }
+  if r < -TWO_POW_63 {
+    return Ordering::Greater;
+  }
+
+  // 范围内的 trunc 可以精确转换为 i64
+  let whole = r.trunc();
+  match i.cmp(&(whole as i64)) {
+    Ordering::Equal => 0.0.partial_cmp(&(r - whole)).unwrap_or(Ordering::Equal),
+    ordering => ordering,
+  }
+}
+
+/// SQLite 的跨类型排序：NULL < 数字 < Text < Blob
+fn type_rank(value: &Value<'_>) -> u8 {
+  match value {
+    Value::Null => 0,
+    Value::Integer(_) | Value::Real(_) => 1,
+    Value::Text(_) | Value::SharedText(_) => 2,
+    Value::Blob(_) => 3,
+  }
+}
@@ -2,8 +2,64 @@
 //!
 //! 定义数据库表结构，包含表 ID、名称、列定义、主键和根页信息
 
-use crate::column::Column;
-use crate::ids::{ColumnId, PageId, TableId};
+use std::borrow::Cow;
+
+use crate::column::{Column, ColumnConstraints};
+use crate::data_type::DataType;
+use crate::ids::{ColumnId, PageId, RowId, TableId};
+use crate::row::Row;
+use crate::sort::SortDirection;
+use crate::value::Value;
+use crate::DomainError;
+
+/// 系统表名前缀：以此开头的表由数据库自身维护，不能被删除
+pub const SYSTEM_TABLE_PREFIX: &str = "rdb_";
+
+/// 系统目录表（相当于 SQLite 的 `sqlite_master`）的表名
+pub const MASTER_TABLE_NAME: &str = "rdb_master";
+
+/// 表名是否属于系统表（以 `rdb_` 开头）
+pub fn is_system_table_name(name: &str) -> bool {
+  name.starts_with(SYSTEM_TABLE_PREFIX)
+}
+
+/// 外键删除动作：被引用的行删除时如何处理引用它的行
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ForeignKeyAction {
+  /// 不做处理（由语句结束时的检查报错）
+  #[default]
+  NoAction,
+  /// 立即拒绝删除
+  Restrict,
+  /// 级联删除引用行
+  Cascade,
+  /// 把引用列置为 NULL
+  SetNull,
+}
+
+impl ForeignKeyAction {
+  /// 对应的 SQL 关键字（`ON DELETE` 之后的部分）
+  pub fn to_sql(self) -> &'static str {
+    match self {
+      ForeignKeyAction::NoAction => "NO ACTION",
+      ForeignKeyAction::Restrict => "RESTRICT",
+      ForeignKeyAction::Cascade => "CASCADE",
+      ForeignKeyAction::SetNull => "SET NULL",
+    }
+  }
+}
+
+/// 外键约束
+///
+/// `columns` 是本表中的引用列，`ref_columns` 是 `ref_table` 中被引用的列，两者按位置一一对应。
+/// 目前只是 schema 描述，还没有执行约束检查。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForeignKey {
+  pub columns: Vec<ColumnId>,
+  pub ref_table: TableId,
+  pub ref_columns: Vec<ColumnId>,
+  pub on_delete: ForeignKeyAction,
+}
 
 /// 表实体
 ///
@@ -13,6 +69,8 @@ use crate::ids::{ColumnId, PageId, TableId};
 /// - name 非空
 /// - columns 非空
 /// - primary_key 如果存在，必须引用 columns 中的列
+/// - foreign_keys 的引用列必须是 columns 中的列
+/// - unique_constraints 的每一项非空，且只包含 columns 中的列
 /// - root_page 必须有效
 ///
 /// 生命周期: 'static
@@ -25,6 +83,12 @@ pub struct Table {
   pub primary_key: Option<ColumnId>,
   /// B+Tree 根页 ID
   pub root_page: PageId,
+  /// 外键约束（`Table::new` 创建时为空）
+  pub foreign_keys: Vec<ForeignKey>,
+  /// 多列唯一约束，每项是一组列 ID（`Table::new` 创建时为空）
+  pub unique_constraints: Vec<Vec<ColumnId>>,
+  /// 按 rowid 扫描的默认方向（B+Tree 本身总是按 rowid 升序存储）
+  pub rowid_order: SortDirection,
 }
 
 impl Table {
@@ -56,7 +120,133 @@ impl Table {
     primary_key: Option<ColumnId>,
     root_page: PageId,
   ) -> Self {
-    Self { id, name, columns, primary_key, root_page }
+    Self {
+      id,
+      name,
+      columns,
+      primary_key,
+      root_page,
+      foreign_keys: Vec::new(),
+      unique_constraints: Vec::new(),
+      rowid_order: SortDirection::Ascending,
+    }
+  }
+
+  /// 由 `(列名, 类型, 约束)` 列表创建表
+  ///
+  /// 列 ID 按顺序从 1 开始分配；主键取第一个声明了 `primary_key` 约束的列，没有则为 `None`。
+  /// 与 `new` 一样不做校验（多个主键等问题交给 `validate`）。
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::{ColumnConstraints, ColumnId, DataType, PageId, Table, TableId};
+  ///
+  /// let pk = ColumnConstraints { primary_key: true, ..Default::default() };
+  /// let table = Table::from_specs(
+  ///   TableId::new(1),
+  ///   "users".to_string(),
+  ///   &[("id", DataType::Integer, pk), ("name", DataType::Text, ColumnConstraints::default())],
+  ///   PageId::new(2),
+  /// );
+  /// assert_eq!(table.primary_key, Some(ColumnId::new(1)));
+  pub fn from_specs(
+    id: TableId,
+    name: String,
+    specs: &[(&str, DataType, ColumnConstraints)],
+    root_page: PageId,
+  ) -> Self {
+    let columns: Vec<Column> = (1..)
+      .zip(specs)
+      .map(|(column_id, (name, data_type, constraints))| {
+        Column::with_constraints(
+          ColumnId::new(column_id),
+          name.to_string(),
+          *data_type,
+          *constraints,
+        )
+      })
+      .collect();
+    let primary_key = columns
+      .iter()
+      .find(|c| c.constraints.primary_key)
+      .map(|c| c.id);
+    Self::new(id, name, columns, primary_key, root_page)
+  }
+
+  /// 附加外键约束（构建时使用），校验交给 `validate`
+  pub fn with_foreign_keys(mut self, foreign_keys: Vec<ForeignKey>) -> Self {
+    self.foreign_keys = foreign_keys;
+    self
+  }
+
+  /// 附加多列唯一约束（构建时使用），校验交给 `validate`
+  pub fn with_unique_constraints(mut self, unique_constraints: Vec<Vec<ColumnId>>) -> Self {
+    self.unique_constraints = unique_constraints;
+    self
+  }
+
+  /// 设置按 rowid 扫描的默认方向（如需要“最新的行在前”时用 `Descending`）
+  pub fn with_rowid_order(mut self, order: SortDirection) -> Self {
+    self.rowid_order = order;
+    self
+  }
+
+  /// 本表声明的外键约束
+  pub fn foreign_keys(&self) -> &[ForeignKey] {
+    &self.foreign_keys
+  }
+
+  /// 本表声明的多列唯一约束
+  pub fn unique_constraints(&self) -> &[Vec<ColumnId>] {
+    &self.unique_constraints
+  }
+
+  /// 是否为系统表（见 `SYSTEM_TABLE_PREFIX`）
+  pub fn is_system(&self) -> bool {
+    is_system_table_name(&self.name)
+  }
+
+  /// 系统目录表 `rdb_master`
+  ///
+  /// 列布局与 SQLite 的 `sqlite_master` 一致：
+  /// `(type TEXT, name TEXT, tbl_name TEXT, rootpage INTEGER, sql TEXT)`，
+  /// 每行描述一个 schema 对象（见 `to_master_row`）。表 ID 固定为 0，
+  /// `root_page` 是目录 B+Tree 的根页，由存储层决定。
+  pub fn master(root_page: PageId) -> Self {
+    let column =
+      |id, name: &str, data_type| Column::new(ColumnId::new(id), name.to_string(), data_type);
+    Self::new(
+      TableId::new(0),
+      MASTER_TABLE_NAME.to_string(),
+      vec![
+        column(1, "type", DataType::Text),
+        column(2, "name", DataType::Text),
+        column(3, "tbl_name", DataType::Text),
+        column(4, "rootpage", DataType::Integer),
+        column(5, "sql", DataType::Text),
+      ],
+      None,
+      root_page,
+    )
+  }
+
+  /// 描述本表的 `rdb_master` 行，rowid 为表 ID
+  ///
+  /// # Examples
+  ///
+  /// let row = table.to_master_row();
+  /// assert_eq!(row.get(0), Some(&Value::Text(Cow::Borrowed("table"))));
+  pub fn to_master_row(&self) -> Row<'static> {
+    Row::new(
+      RowId::new(i64::from(self.id.into_inner())),
+      vec![
+        Value::Text(Cow::Borrowed("table")),
+        Value::Text(Cow::Owned(self.name.clone())),
+        Value::Text(Cow::Owned(self.name.clone())),
+        Value::Integer(i64::from(self.root_page.into_inner())),
+        Value::Text(Cow::Owned(self.to_create_sql())),
+      ],
+    )
   }
 
   /// 查找列（按名称）
@@ -111,6 +301,399 @@ impl Table {
       .primary_key
       .and_then(|pk_id| self.columns.iter().find(|col| col.id == pk_id))
   }
+
+  /// 验证表定义的全部不变量（持久化前调用）
+  ///
+  /// 依次检查，遇到第一个错误即返回：
+  /// 1. 表名非空
+  /// 2. 至少有一列
+  /// 3. 列名不重复
+  /// 4. `primary_key` 引用存在的列
+  /// 5. 最多一个主键：列约束中的主键最多一个，且与 `primary_key` 一致
+  /// 6. `AUTOINCREMENT` 只出现在 INTEGER 主键上
+  /// 7. 外键的引用列非空、与被引用列数量一致且都是本表的列；
+  ///    自引用外键（`ref_table` 是本表）的被引用列也必须是本表的列
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::{Table, TableId, PageId, Column, ColumnId, DataType};
+  ///
+  /// let columns = vec![Column::new(ColumnId::new(1), "id".to_string(), DataType::Integer)];
+  /// let table = Table::new(TableId::new(1), "users".to_string(), columns, Some(ColumnId::new(1)), PageId::new(2));
+  /// assert!(table.validate().is_ok());
+  pub fn validate(&self) -> Result<(), DomainError> {
+    if self.name.is_empty() {
+      return Err(DomainError::EmptyTableName);
+    }
+    if self.columns.is_empty() {
+      return Err(DomainError::TableMusthHaveColumns);
+    }
+
+    for (i, column) in self.columns.iter().enumerate() {
+      if self.columns[..i].iter().any(|c| c.name == column.name) {
+        return Err(DomainError::DuplicateColumnName { name: column.name.clone() });
+      }
+    }
+
+    if let Some(column_id) = self.primary_key {
+      if !self.columns.iter().any(|c| c.id == column_id) {
+        return Err(DomainError::InvalidPrimaryKeyReference { column_id });
+      }
+    }
+
+    let mut declared = self.columns.iter().filter(|c| c.constraints.primary_key);
+    if let Some(first) = declared.next() {
+      if declared.next().is_some() || self.primary_key.is_some_and(|pk| pk != first.id) {
+        return Err(DomainError::MultiplePrimaryKeys);
+      }
+    }
+
+    for column in &self.columns {
+      let is_pk = column.constraints.primary_key || self.primary_key == Some(column.id);
+      if column.constraints.autoincrement && !(is_pk && column.data_type == DataType::Integer) {
+        return Err(DomainError::InvalidAutoincrement { name: column.name.clone() });
+      }
+    }
+
+    for fk in &self.foreign_keys {
+      if fk.columns.is_empty() || fk.columns.len() != fk.ref_columns.len() {
+        return Err(DomainError::ForeignKeyColumnCountMismatch {
+          columns: fk.columns.len(),
+          ref_columns: fk.ref_columns.len(),
+        });
+      }
+      let self_ref = fk.ref_table == self.id;
+      let referenced = fk.ref_columns.iter().filter(|_| self_ref);
+      if let Some(&column_id) = fk
+        .columns
+        .iter()
+        .chain(referenced)
+        .find(|&&id| !self.columns.iter().any(|c| c.id == id))
+      {
+        return Err(DomainError::InvalidForeignKeyReference { column_id });
+      }
+    }
+
+    for unique in &self.unique_constraints {
+      if unique.is_empty() {
+        return Err(DomainError::EmptyUniqueConstraint);
+      }
+      if let Some(&column_id) = unique
+        .iter()
+        .find(|&&id| !self.columns.iter().any(|c| c.id == id))
+      {
+        return Err(DomainError::ColumnIdNotFound { column_id });
+      }
+    }
+
+    Ok(())
+  }
+
+  /// 检查 `new_row` 是否违反多列唯一约束
+  ///
+  /// 对每个约束，取 `new_row` 在约束列上的值组成元组，与 `existing` 中每行的元组比较；
+  /// 数值按 `Value::loose_eq` 比较（`1` 与 `1.0` 视为相同）。与 SQL 一致，
+  /// 元组中只要有 NULL 就不与任何行冲突。`existing` 中与 `new_row` rowid 相同的行被跳过，
+  /// 因此更新一行时可以直接传入包含旧版本的整表数据。
+  ///
+  /// 约束引用了不存在的列时返回 `ColumnIdNotFound`，冲突时返回 `UniqueViolation`。
+  ///
+  /// # Examples
+  ///
+  /// let table = table.with_unique_constraints(vec![vec![ColumnId::new(1), ColumnId::new(2)]]);
+  /// let a = Row::new(RowId::new(1), vec![Value::Integer(1), Value::Integer(2)]);
+  /// let b = Row::new(RowId::new(2), vec![Value::Integer(1), Value::Integer(2)]);
+  /// assert!(table.check_composite_unique(&b, &[a]).is_err());
+  pub fn check_composite_unique(&self, new_row: &Row, existing: &[Row]) -> Result<(), DomainError> {
+    for unique in &self.unique_constraints {
+      let positions = unique
+        .iter()
+        .map(|&column_id| {
+          self
+            .columns
+            .iter()
+            .position(|c| c.id == column_id)
+            .ok_or(DomainError::ColumnIdNotFound { column_id })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+      let key = positions
+        .iter()
+        .map(|&index| {
+          new_row
+            .get(index)
+            .ok_or(DomainError::ColumnIndexOutOfRange { index, len: new_row.values.len() })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+      if key.iter().any(|v| matches!(v, Value::Null)) {
+        continue;
+      }
+
+      let conflict = existing
+        .iter()
+        .filter(|row| row.row_id != new_row.row_id)
+        .any(|row| {
+          positions
+            .iter()
+            .zip(&key)
+            .all(|(&index, value)| row.get(index).is_some_and(|v| v.loose_eq(value)))
+        });
+      if conflict {
+        return Err(DomainError::UniqueViolation {
+          columns: positions
+            .iter()
+            .map(|&index| self.columns[index].name.clone())
+            .collect(),
+        });
+      }
+    }
+
+    Ok(())
+  }
+
+  /// 验证行是否符合表定义
+  ///
+  /// 依次检查值数量与列数一致、每列的值满足列定义（见 `Column::validate_value`），
+  /// 遇到第一个错误即返回。
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::{Row, RowId, Value};
+  ///
+  /// let row = Row::new(RowId::new(1), vec![Value::Integer(1)]);
+  /// assert!(table.validate_row(&row).is_ok());
+  pub fn validate_row(&self, row: &Row) -> Result<(), DomainError> {
+    if row.values.len() != self.columns.len() {
+      return Err(DomainError::RowLengthMismatch {
+        expected: self.columns.len(),
+        got: row.values.len(),
+      });
+    }
+
+    for (column, value) in self.columns.iter().zip(&row.values) {
+      column.validate_value(value)?;
+    }
+
+    Ok(())
+  }
+
+  /// 验证行是否符合表定义，收集全部错误
+  ///
+  /// 与 `validate_row` 的检查相同，但不在第一个错误处停止：
+  /// 值数量不一致时仍按位置检查两边都存在的列。
+  pub fn validate_row_collect(&self, row: &Row) -> Result<(), Vec<DomainError>> {
+    let mut errors = Vec::new();
+
+    if row.values.len() != self.columns.len() {
+      errors.push(DomainError::RowLengthMismatch {
+        expected: self.columns.len(),
+        got: row.values.len(),
+      });
+    }
+
+    for (column, value) in self.columns.iter().zip(&row.values) {
+      if let Err(e) = column.validate_value(value) {
+        errors.push(e);
+      }
+    }
+
+    if errors.is_empty() {
+      Ok(())
+    } else {
+      Err(errors)
+    }
+  }
+
+  /// 估算一行编码为 record 后的字节数（供查询规划和批量导入估算页数）
+  ///
+  /// - INTEGER/REAL 按 8 字节计（整数实际可能更短，这里取上界）
+  /// - TEXT/JSON/BLOB 按 `avg_var_len` 字节计
+  /// - 加上 record 头：头长度 1 字节 + 每列一个 serial type varint
+  ///
+  /// 不含 cell 中的 rowid、payload 长度和 cell 指针。
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::{Table, TableId, PageId, Column, ColumnId, DataType};
+  ///
+  /// let columns = vec![Column::new(ColumnId::new(1), "id".to_string(), DataType::Integer)];
+  /// let table = Table::new(TableId::new(1), "t".to_string(), columns, None, PageId::new(1));
+  ///
+  /// assert_eq!(table.estimated_row_size(0), 1 + 1 + 8);
+  pub fn estimated_row_size(&self, avg_var_len: usize) -> usize {
+    // SQLite 风格 varint 的字节数（每字节 7 bit，最多 9 字节）
+    let varint_len = |v: u64| ((64 - v.leading_zeros()).div_ceil(7)).clamp(1, 9) as usize;
+
+    let (header, body) =
+      self
+        .columns
+        .iter()
+        .fold((1, 0), |(header, body), column| match column.data_type {
+          DataType::Integer | DataType::Real => (header + 1, body + 8),
+          DataType::Text | DataType::Json | DataType::Blob => {
+            let serial_type = 13 + 2 * avg_var_len as u64;
+            (header + varint_len(serial_type), body + avg_var_len)
+          }
+        });
+    header + body
+  }
+
+  /// 生成建表语句
+  ///
+  /// 输出形如 `CREATE TABLE name (col TYPE constraints, ...)` 的 SQL（不含结尾分号），
+  /// 列约束按 `PRIMARY KEY`、`AUTOINCREMENT`、`NOT NULL`、`UNIQUE`、`DEFAULT`、
+  /// `CHECK (length(col) <= n)`（`max_length`）的顺序输出；
+  /// 列定义之后依次是多列唯一约束 `UNIQUE (a, b)` 和外键
+  /// `FOREIGN KEY (a) REFERENCES t (b) [ON DELETE ..]`。
+  /// 列的 `constraints.primary_key` 或表的 `primary_key` 任一指向该列都视为主键。
+  /// 需要时标识符会用双引号包裹（见 `quote_identifier`）。
+  ///
+  /// 单独一张表只能解析引用自身的外键；引用其他表的外键需要通过
+  /// `to_create_sql_with`（或 `Database::to_schema_sql`）提供被引用的表，否则不输出。
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::{Table, TableId, PageId, Column, ColumnId, DataType};
+  ///
+  /// let columns = vec![Column::new(ColumnId::new(1), "id".to_string(), DataType::Integer)];
+  /// let table = Table::new(TableId::new(1), "users".to_string(), columns, Some(ColumnId::new(1)), PageId::new(1));
+  ///
+  /// assert_eq!(table.to_create_sql(), "CREATE TABLE users (id INTEGER PRIMARY KEY)");
+  pub fn to_create_sql(&self) -> String {
+    self.to_create_sql_with(|id| (id == self.id).then_some(self))
+  }
+
+  /// 生成建表语句，用 `lookup` 查找外键引用的表（见 `to_create_sql`）
+  ///
+  /// 被引用的表或列找不到时跳过该外键。
+  pub fn to_create_sql_with<'a>(&'a self, lookup: impl Fn(TableId) -> Option<&'a Table>) -> String {
+    let mut columns: Vec<String> = self
+      .columns
+      .iter()
+      .map(|col| {
+        let mut def = format!(
+          "{} {}",
+          quote_identifier(&col.name),
+          col.data_type.to_sql_type()
+        );
+        if col.constraints.primary_key || self.primary_key == Some(col.id) {
+          def.push_str(" PRIMARY KEY");
+        }
+        if col.constraints.autoincrement {
+          def.push_str(" AUTOINCREMENT");
+        }
+        if col.constraints.not_null {
+          def.push_str(" NOT NULL");
+        }
+        if col.constraints.unique {
+          def.push_str(" UNIQUE");
+        }
+        if let Some(default) = &col.default_value {
+          def.push_str(" DEFAULT ");
+          def.push_str(&sql_literal(default));
+        }
+        if let Some(max) = col.constraints.max_length {
+          def.push_str(&format!(
+            " CHECK (length({}) <= {max})",
+            quote_identifier(&col.name)
+          ));
+        }
+        def
+      })
+      .collect();
+
+    for unique in &self.unique_constraints {
+      let names: Vec<String> = unique
+        .iter()
+        .filter_map(|&id| self.columns.iter().find(|c| c.id == id))
+        .map(|c| quote_identifier(&c.name))
+        .collect();
+      columns.push(format!("UNIQUE ({})", names.join(", ")));
+    }
+
+    for fk in &self.foreign_keys {
+      let Some(ref_table) = lookup(fk.ref_table) else {
+        continue;
+      };
+      let names = |table: &Table, ids: &[ColumnId]| -> Option<Vec<String>> {
+        ids
+          .iter()
+          .map(|&id| table.columns.iter().find(|c| c.id == id))
+          .map(|c| c.map(|c| quote_identifier(&c.name)))
+          .collect()
+      };
+      let (Some(cols), Some(ref_cols)) =
+        (names(self, &fk.columns), names(ref_table, &fk.ref_columns))
+      else {
+        continue;
+      };
+      let mut clause = format!(
+        "FOREIGN KEY ({}) REFERENCES {} ({})",
+        cols.join(", "),
+        quote_identifier(&ref_table.name),
+        ref_cols.join(", ")
+      );
+      if fk.on_delete != ForeignKeyAction::NoAction {
+        clause.push_str(" ON DELETE ");
+        clause.push_str(fk.on_delete.to_sql());
+      }
+      columns.push(clause);
+    }
+
+    format!(
+      "CREATE TABLE {} ({})",
+      quote_identifier(&self.name),
+      columns.join(", ")
+    )
+  }
+}
+
+/// 必须加引号才能作为标识符使用的关键字（只列出常见的保留字）
+const RESERVED_WORDS: &[&str] = &[
+  "ALL", "AND", "AS", "BY", "CHECK", "CREATE", "DEFAULT", "DELETE", "DISTINCT", "DROP", "FROM",
+  "GROUP", "IN", "INDEX", "INSERT", "INTO", "IS", "KEY", "LIMIT", "NOT", "NULL", "ON", "OR",
+  "ORDER", "PRIMARY", "SELECT", "SET", "TABLE", "UNIQUE", "UPDATE", "VALUES", "WHERE",
+];
+
+/// 把值写成 SQL 字面量（用于 `DEFAULT`）
+///
+/// 文本用单引号包裹（内部 `'` 转义为 `''`），BLOB 写成 `X'..'`；
+/// 实数使用能精确还原的最短写法，无穷大写成 SQLite 的 `9e999`，NaN 没有字面量，写成 `NULL`。
+fn sql_literal(value: &Value<'_>) -> String {
+  match value {
+    Value::Null => "NULL".to_string(),
+    Value::Integer(i) => i.to_string(),
+    Value::Real(r) if r.is_nan() => "NULL".to_string(),
+    Value::Real(r) if r.is_infinite() => if *r > 0.0 { "9e999" } else { "-9e999" }.to_string(),
+    Value::Real(r) => format!("{r:?}"),
+    Value::Blob(b) => {
+      let hex: String = b.iter().map(|byte| format!("{byte:02X}")).collect();
+      format!("X'{hex}'")
+    }
+    text => format!(
+      "'{}'",
+      text.as_text().unwrap_or_default().replace('\'', "''")
+    ),
+  }
+}
+
+/// 按需给 SQL 标识符加双引号
+///
+/// 满足 `[A-Za-z_][A-Za-z0-9_]*` 且不是保留字的名称原样返回，
+/// 否则用双引号包裹，内部的 `"` 转义为 `""`。
+pub(crate) fn quote_identifier(name: &str) -> String {
+  let mut chars = name.chars();
+  let plain = chars
+    .next()
+    .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+    && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+    && !RESERVED_WORDS.iter().any(|w| w.eq_ignore_ascii_case(name));
+
+  if plain {
+    name.to_string()
+  } else {
+    format!("\"{}\"", name.replace('"', "\"\""))
+  }
 }
 
 // 取保 Table 是 Send + Sync
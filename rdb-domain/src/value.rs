@@ -6,12 +6,17 @@
 //! - `Real`: 64-bit 浮点数
 //! - `Text`: UTF-8 字符串(使用 Cow 避免拷贝)
 //! - `Blob`: 二进制数据(使用 Cow 避免拷贝)
+//!
+//! 另有 `SharedText`：驻留后共享存储的文本（见 `Value::intern`），语义上与 `Text` 完全相同。
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, Serializer};
 use std::borrow::Cow;
 use std::cmp::Ordering;
+use std::fmt;
+use std::sync::Arc;
 
 use crate::data_type::DataType;
+use crate::interner::StringInterner;
 
 /// 值对象：数据库值
 ///
@@ -20,7 +25,7 @@ use crate::data_type::DataType;
 ///
 /// 声明周期: 'v (可能引用外部数据，避免拷贝)
 /// 线程安全: Send + Sync
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub enum Value<'v> {
   /// NULL 值
   Null,
@@ -32,6 +37,39 @@ pub enum Value<'v> {
   Text(#[serde(borrow)] Cow<'v, str>),
   /// 二进制数据(使用 Cow 避免拷贝)
   Blob(#[serde(borrow)] Cow<'v, [u8]>),
+  /// 驻留的 UTF-8 字符串（见 `Value::intern`）
+  ///
+  /// 与 `Text` 等价：比较、哈希、编码和序列化的结果都相同，只是存储由 `Arc` 共享，
+  /// 不借用任何外部数据。
+  #[serde(skip_deserializing)]
+  SharedText(Arc<str>),
+}
+
+/// 按内容比较：`SharedText` 与内容相同的 `Text` 相等
+impl PartialEq for Value<'_> {
+  fn eq(&self, other: &Self) -> bool {
+    match (self, other) {
+      (Value::Null, Value::Null) => true,
+      (Value::Integer(a), Value::Integer(b)) => a == b,
+      (Value::Real(a), Value::Real(b)) => a == b,
+      (Value::Blob(a), Value::Blob(b)) => a == b,
+      _ => matches!((self.as_text(), other.as_text()), (Some(a), Some(b)) if a == b),
+    }
+  }
+}
+
+/// 与派生实现的格式一致；`SharedText` 按 `Text` 序列化，反序列化得到普通的 `Text`
+impl Serialize for Value<'_> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    match self {
+      Value::Null => serializer.serialize_unit_variant("Value", 0, "Null"),
+      Value::Integer(i) => serializer.serialize_newtype_variant("Value", 1, "Integer", i),
+      Value::Real(r) => serializer.serialize_newtype_variant("Value", 2, "Real", r),
+      Value::Text(s) => serializer.serialize_newtype_variant("Value", 3, "Text", s),
+      Value::SharedText(s) => serializer.serialize_newtype_variant("Value", 3, "Text", &**s),
+      Value::Blob(b) => serializer.serialize_newtype_variant("Value", 4, "Blob", b),
+    }
+  }
 }
 
 // 确保 Value 是 Send + Sync
@@ -58,9 +96,68 @@ impl<'v> Value<'v> {
       Value::Real(r) => Value::Real(r),
       Value::Text(cow) => Value::Text(Cow::Owned(cow.into_owned())),
       Value::Blob(cow) => Value::Blob(Cow::Owned(cow.into_owned())),
+      Value::SharedText(s) => Value::SharedText(s),
+    }
+  }
+
+  /// 把文本换成驻留器中共享的那一份（`SharedText`），其他类型转为 owned 返回
+  ///
+  /// 返回 `Value<'static>`：相同内容的文本共享同一个 `Arc<str>`，不借用驻留器，
+  /// 可以放进 `Row<'static>` 并比驻留器活得久。见 `StringInterner` 中关于生命周期的说明。
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::{StringInterner, Value};
+  ///
+  /// let interner = StringInterner::new();
+  /// let v = Value::Text("active".to_string().into()).intern(&interner);
+  /// drop(interner);
+  /// assert_eq!(v.as_text(), Some("active"));
+  ///
+  pub fn intern(self, interner: &StringInterner) -> Value<'static> {
+    match self.as_text() {
+      Some(text) => Value::SharedText(interner.intern(text)),
+      None => self.into_owned(),
+    }
+  }
+
+  /// 是否借用外部数据
+  ///
+  /// 仅当 `Text`/`Blob` 的 `Cow` 为 `Borrowed` 时返回 `true`。
+  /// 可用于判断 `into_owned` 是否会发生分配。
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::Value;
+  /// use std::borrow::Cow;
+  ///
+  /// assert!(Value::Text(Cow::Borrowed("hello")).is_borrowed());
+  /// assert!(!Value::Integer(1).is_borrowed());
+  ///
+  pub fn is_borrowed(&self) -> bool {
+    match self {
+      Value::Text(cow) => matches!(cow, Cow::Borrowed(_)),
+      Value::Blob(cow) => matches!(cow, Cow::Borrowed(_)),
+      _ => false,
     }
   }
 
+  /// 是否自包含（不借用外部数据）
+  ///
+  /// 标量（`Null`/`Integer`/`Real`）视为拥有所有权，与 `is_borrowed` 互斥。
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::Value;
+  /// use std::borrow::Cow;
+  ///
+  /// assert!(Value::Text(Cow::Owned("hello".to_string())).is_owned());
+  /// assert!(Value::Integer(1).is_owned());
+  ///
+  pub fn is_owned(&self) -> bool {
+    !self.is_borrowed()
+  }
+
   /// 获取值的类型
   ///
   /// 返回对应的 `DataType`。
@@ -78,7 +175,7 @@ impl<'v> Value<'v> {
       Value::Null => DataType::Integer, // NULL 在 SQLite 中通常关联到 Integer
       Value::Integer(_) => DataType::Integer,
       Value::Real(_) => DataType::Real,
-      Value::Text(_) => DataType::Text,
+      Value::Text(_) | Value::SharedText(_) => DataType::Text,
       Value::Blob(_) => DataType::Blob,
     }
   }
@@ -119,6 +216,47 @@ impl<'v> Value<'v> {
     }
   }
 
+  /// 按数值读取为 f64
+  ///
+  /// `Integer` 转换为 f64（超过 2^53 时可能损失精度），`Real` 原样返回，其他类型返回 `None`。
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::Value;
+  ///
+  /// assert_eq!(Value::Integer(2).as_f64_lossy(), Some(2.0));
+  /// assert_eq!(Value::Real(2.5).as_f64_lossy(), Some(2.5));
+  /// assert_eq!(Value::Null.as_f64_lossy(), None);
+  ///
+  pub fn as_f64_lossy(&self) -> Option<f64> {
+    match self {
+      Value::Integer(i) => Some(*i as f64),
+      Value::Real(r) => Some(*r),
+      _ => None,
+    }
+  }
+
+  /// 按数值读取为 i64，不做任何有损转换
+  ///
+  /// `Integer` 原样返回；`Real` 只有在没有小数部分且落在 i64 范围内时返回，
+  /// 其他情况（含 NaN、无穷）以及非数值类型都返回 `None`。
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::Value;
+  ///
+  /// assert_eq!(Value::Real(3.0).as_i64_checked(), Some(3));
+  /// assert_eq!(Value::Real(3.5).as_i64_checked(), None);
+  /// assert_eq!(Value::Real(1e19).as_i64_checked(), None);
+  ///
+  pub fn as_i64_checked(&self) -> Option<i64> {
+    match self {
+      Value::Integer(i) => Some(*i),
+      Value::Real(r) => real_to_integer(*r),
+      _ => None,
+    }
+  }
+
   /// 尝试转换为 &str
   ///
   /// 如果值是 `Text`, 返回 `Some(&str)`, 否则返回 `None`。
@@ -135,6 +273,7 @@ impl<'v> Value<'v> {
   pub fn as_text(&self) -> Option<&str> {
     match self {
       Value::Text(s) => Some(s.as_ref()),
+      Value::SharedText(s) => Some(s),
       _ => None,
     }
   }
@@ -158,6 +297,429 @@ impl<'v> Value<'v> {
     }
   }
 
+  /// 统一的字节视图（零拷贝）
+  ///
+  /// `Blob` 返回其字节，`Text` 返回 UTF-8 编码的字节；
+  /// NULL 和数字没有现成的字节表示，返回 `None`（不会临时渲染为文本）。
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::Value;
+  /// use std::borrow::Cow;
+  ///
+  /// assert_eq!(Value::Text(Cow::Borrowed("hi")).as_bytes(), Some(b"hi" as &[u8]));
+  /// assert_eq!(Value::Integer(1).as_bytes(), None);
+  ///
+  pub fn as_bytes(&self) -> Option<&[u8]> {
+    match self {
+      Value::Text(cow) => Some(cow.as_bytes()),
+      Value::SharedText(s) => Some(s.as_bytes()),
+      Value::Blob(cow) => Some(cow.as_ref()),
+      _ => None,
+    }
+  }
+
+  /// 把 `Text` 值解析为 JSON（需要 `json` feature）
+  ///
+  /// 非 `Text` 或不是合法 JSON 时返回 `None`。
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::Value;
+  /// use std::borrow::Cow;
+  ///
+  /// let value = Value::Text(Cow::Borrowed(r#"{"a": 1}"#));
+  /// assert_eq!(value.as_json().unwrap()["a"], 1);
+  ///
+  #[cfg(feature = "json")]
+  pub fn as_json(&self) -> Option<serde_json::Value> {
+    serde_json::from_str(self.as_text()?).ok()
+  }
+
+  /// 按 Unix 时间戳（秒）读取日期时间
+  ///
+  /// 日期时间按 SQLite 的常见做法存为 `Integer`，本方法只是 `as_integer` 的别名，
+  /// 不会解析 `Text`（ISO-8601 文本见 `from_iso8601`）。
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::Value;
+  ///
+  /// assert_eq!(Value::from_unix_secs(1_700_000_000).as_unix_secs(), Some(1_700_000_000));
+  /// assert_eq!(Value::Real(1.5).as_unix_secs(), None);
+  ///
+  pub fn as_unix_secs(&self) -> Option<i64> {
+    self.as_integer()
+  }
+
+  /// 把 Unix 时间戳格式化为 UTC 的 ISO-8601（RFC 3339）文本（需要 `time` feature）
+  ///
+  /// 非 `Integer` 或超出可表示的年份范围（0000~9999）时返回 `None`。
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::Value;
+  ///
+  /// let value = Value::from_unix_secs(0);
+  /// assert_eq!(value.to_iso8601().as_deref(), Some("1970-01-01T00:00:00Z"));
+  ///
+  #[cfg(feature = "time")]
+  pub fn to_iso8601(&self) -> Option<String> {
+    use time::format_description::well_known::Rfc3339;
+
+    let at = time::OffsetDateTime::from_unix_timestamp(self.as_unix_secs()?).ok()?;
+    at.format(&Rfc3339).ok()
+  }
+
+  /// SQL `length()`：`Text` 返回字符数，`Blob` 返回字节数
+  ///
+  /// `Text` 按 Unicode 标量值（`char`）计数而不是字节，如 `"héllo"` 为 5；
+  /// NULL 和数字返回 `None`（需要数字的长度时先 `cast` 为 TEXT）。
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::Value;
+  /// use std::borrow::Cow;
+  ///
+  /// assert_eq!(Value::Text(Cow::Borrowed("héllo")).len(), Some(5));
+  /// assert_eq!(Value::Null.len(), None);
+  ///
+  // 返回 Option，`is_empty` 没有对应的自然语义
+  #[allow(clippy::len_without_is_empty)]
+  pub fn len(&self) -> Option<usize> {
+    match self {
+      Value::Text(s) => Some(s.chars().count()),
+      Value::SharedText(s) => Some(s.chars().count()),
+      Value::Blob(b) => Some(b.len()),
+      _ => None,
+    }
+  }
+
+  /// `Text`/`Blob` 的字节数（`Text` 为 UTF-8 编码后的字节数），NULL 和数字返回 `None`
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::Value;
+  /// use std::borrow::Cow;
+  ///
+  /// assert_eq!(Value::Text(Cow::Borrowed("héllo")).byte_len(), Some(6));
+  ///
+  pub fn byte_len(&self) -> Option<usize> {
+    match self {
+      Value::Text(s) => Some(s.len()),
+      Value::SharedText(s) => Some(s.len()),
+      Value::Blob(b) => Some(b.len()),
+      _ => None,
+    }
+  }
+
+  /// 数值规范化：整数值的 `Real` 转为 `Integer`
+  ///
+  /// 只有值恰好是整数且在 i64 范围内的 `Real` 会被转换（如 `3.0` -> `3`）；
+  /// 非整数、超出 i64 范围、NaN/Inf 保持为 `Real`，其他类型原样返回（转为 owned）。
+  /// 规范化后 record 编码更紧凑（小整数只占 0~2 字节），且与 `Integer` 比较时类型一致。
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::Value;
+  ///
+  /// assert_eq!(Value::Real(3.0).normalize_numeric(), Value::Integer(3));
+  /// assert_eq!(Value::Real(3.5).normalize_numeric(), Value::Real(3.5));
+  ///
+  pub fn normalize_numeric(self) -> Value<'static> {
+    match self {
+      Value::Real(r) => real_to_integer(r).map_or(Value::Real(r), Value::Integer),
+      other => other.into_owned(),
+    }
+  }
+
+  /// SQL `ROUND(x, digits)`：把 `Real` 四舍五入到 `digits` 位小数
+  ///
+  /// 与 SQLite 一致采用“远离零”的四舍五入（round half away from zero），不是银行家舍入：
+  /// `2.5 -> 3.0`、`-2.5 -> -3.0`。`digits` 为负时按 0 处理；结果总是 `Real`。
+  /// 位数足够表示全部精度（`digits >= 15` 或 |x| >= 2^52）时原样返回。
+  /// 注意二进制浮点无法精确表示多数小数，例如 `2.675` 实际略小于 2.675，舍入得 `2.67`。
+  /// `Integer` 和 NULL 原样返回，其他类型返回 NULL。
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::Value;
+  ///
+  /// assert_eq!(Value::Real(3.14159).round(2), Value::Real(3.14));
+  /// assert_eq!(Value::Real(2.5).round(0), Value::Real(3.0));
+  /// assert_eq!(Value::Integer(7).round(2), Value::Integer(7));
+  ///
+  pub fn round(&self, digits: i32) -> Value<'static> {
+    match self {
+      Value::Integer(i) => Value::Integer(*i),
+      Value::Real(r) => {
+        let digits = digits.max(0);
+        if digits >= 15 || r.abs() >= MAX_EXACT_F64_INT as f64 / 2.0 {
+          return Value::Real(*r);
+        }
+        let scale = 10f64.powi(digits);
+        Value::Real((r * scale).round() / scale)
+      }
+      _ => Value::Null,
+    }
+  }
+
+  /// SQL `ABS(x)`：数值的绝对值
+  ///
+  /// `Integer` 返回 `Integer`；`i64::MIN` 的绝对值超出 i64 范围，
+  /// 此时返回 `Real(9.223372036854776e18)`（SQLite 会报整数溢出错误）。
+  /// `Real` 返回 `Real`，NULL 及其他类型返回 NULL。
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::Value;
+  ///
+  /// assert_eq!(Value::Real(-1.5).abs(), Value::Real(1.5));
+  /// assert_eq!(Value::Integer(-3).abs(), Value::Integer(3));
+  ///
+  pub fn abs(&self) -> Value<'static> {
+    match self {
+      Value::Integer(i) => i
+        .checked_abs()
+        .map_or(Value::Real(-(*i as f64)), Value::Integer),
+      Value::Real(r) => Value::Real(r.abs()),
+      _ => Value::Null,
+    }
+  }
+
+  /// 稳定哈希（用于一致性哈希/分片）
+  ///
+  /// 使用 64 位 FNV-1a，依次哈希 `seed`、类型标记字节和值的字节（整数/浮点数取小端序），
+  /// 结果在不同进程、不同平台间保持一致。与 `Hash` trait 不同，它不依赖运行时的
+  /// `RandomState`。注意 `Integer(1)` 与 `Real(1.0)` 的哈希不同；浮点数按位哈希，
+  /// 因此 `0.0` 与 `-0.0` 也不同。
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::Value;
+  ///
+  /// assert_eq!(Value::Integer(42).stable_hash(7), Value::Integer(42).stable_hash(7));
+  ///
+  pub fn stable_hash(&self, seed: u64) -> u64 {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let fnv = |hash: u64, bytes: &[u8]| {
+      bytes
+        .iter()
+        .fold(hash, |h, &b| (h ^ u64::from(b)).wrapping_mul(FNV_PRIME))
+    };
+
+    let hash = fnv(FNV_OFFSET_BASIS, &seed.to_le_bytes());
+    match self {
+      Value::Null => fnv(hash, &[0]),
+      Value::Integer(i) => fnv(fnv(hash, &[1]), &i.to_le_bytes()),
+      Value::Real(r) => fnv(fnv(hash, &[2]), &r.to_bits().to_le_bytes()),
+      Value::Text(s) => fnv(fnv(hash, &[3]), s.as_bytes()),
+      Value::SharedText(s) => fnv(fnv(hash, &[3]), s.as_bytes()),
+      Value::Blob(b) => fnv(fnv(hash, &[4]), b),
+    }
+  }
+
+  /// 以带标签的线格式追加到 `out`（RPC 帧用，与磁盘 record 格式无关）
+  ///
+  /// 格式：1 字节类型标签 + payload
+  /// - `0` NULL：无 payload
+  /// - `1` Integer / `2` Real：8 字节大端（Real 按 IEEE 754 位模式）
+  /// - `3` Text / `4` Blob：LEB128 变长长度 + 原始字节
+  ///
+  /// 标签与 `stable_hash` 使用的类型编号一致。
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::Value;
+  ///
+  /// let mut out = Vec::new();
+  /// Value::Integer(1).encode_tagged(&mut out);
+  /// assert_eq!(out, [1, 0, 0, 0, 0, 0, 0, 0, 1]);
+  ///
+  pub fn encode_tagged(&self, out: &mut Vec<u8>) {
+    match self {
+      Value::Null => out.push(TAG_NULL),
+      Value::Integer(i) => {
+        out.push(TAG_INTEGER);
+        out.extend_from_slice(&i.to_be_bytes());
+      }
+      Value::Real(r) => {
+        out.push(TAG_REAL);
+        out.extend_from_slice(&r.to_bits().to_be_bytes());
+      }
+      Value::Text(_) | Value::SharedText(_) => {
+        let s = self.as_text().unwrap_or_default();
+        out.push(TAG_TEXT);
+        encode_len(s.len(), out);
+        out.extend_from_slice(s.as_bytes());
+      }
+      Value::Blob(b) => {
+        out.push(TAG_BLOB);
+        encode_len(b.len(), out);
+        out.extend_from_slice(b);
+      }
+    }
+  }
+
+  /// SQL 字符串拼接（`||`）
+  ///
+  /// 遵循 SQLite 语义：
+  /// - 任一操作数为 NULL，结果为 NULL
+  /// - 否则两边都转为文本后拼接，返回 `Text`（整数按十进制，实数按 `format_real`，如 `3.0 || 'x'` 为 `'3.0x'`）
+  /// - 例外：`Blob || Blob` 直接拼接字节并返回 `Blob`，避免二进制数据被当作文本损坏；
+  ///   `Blob` 与其他类型拼接时按 UTF-8（非法字节替换为 U+FFFD）转为文本
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::Value;
+  /// use std::borrow::Cow;
+  ///
+  /// let v = Value::Integer(1).concat(&Value::Text(Cow::Borrowed("a")));
+  /// assert_eq!(v, Value::Text(Cow::Borrowed("1a")));
+  /// assert_eq!(Value::Null.concat(&v), Value::Null);
+  ///
+  pub fn concat(&self, other: &Self) -> Value<'static> {
+    match (self, other) {
+      (Value::Null, _) | (_, Value::Null) => Value::Null,
+      (Value::Blob(a), Value::Blob(b)) => {
+        Value::Blob(Cow::Owned([a.as_ref(), b.as_ref()].concat()))
+      }
+      _ => {
+        let mut text = self.concat_text().into_owned();
+        text.push_str(&other.concat_text());
+        Value::Text(Cow::Owned(text))
+      }
+    }
+  }
+
+  /// SQL `IFNULL(self, fallback)`：`self` 不是 NULL 时返回它的副本，否则返回 `fallback`
+  ///
+  /// 多参数版本见 `coalesce`。
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::Value;
+  ///
+  /// assert_eq!(Value::Null.if_null(Value::Integer(0)), Value::Integer(0));
+  /// assert_eq!(Value::Integer(5).if_null(Value::Integer(0)), Value::Integer(5));
+  ///
+  pub fn if_null(&self, fallback: Value<'v>) -> Value<'v> {
+    match self {
+      Value::Null => fallback,
+      other => other.clone(),
+    }
+  }
+
+  /// `concat` 使用的文本形式（NULL 由调用方提前处理）
+  fn concat_text(&self) -> Cow<'_, str> {
+    match self {
+      Value::Null => Cow::Borrowed(""),
+      Value::Integer(i) => Cow::Owned(i.to_string()),
+      Value::Real(r) => Cow::Owned(format_real(*r)),
+      Value::Text(s) => Cow::Borrowed(s.as_ref()),
+      Value::SharedText(s) => Cow::Borrowed(s),
+      Value::Blob(b) => String::from_utf8_lossy(b),
+    }
+  }
+
+  /// 按类型亲和性（affinity）无损转换
+  ///
+  /// 已符合 `to` 的值（包括 NULL）原样返回（转为 owned）；兼容的值无损转换：
+  /// - INTEGER：整数值的 `Real`（如 `3.0`）、可解析为整数的 `Text` -> `Integer`
+  /// - REAL：`Integer`（|i| <= 2^53，可精确表示）、可解析为有限数字的 `Text` -> `Real`
+  /// - TEXT/JSON：`Integer` -> 十进制文本，`Real` -> 与 `cast` 相同的 SQLite 文本形式（见 `format_real`）
+  /// - BLOB：不做转换
+  ///
+  /// 无法无损转换时返回 `None`。需要“总是得到一个值”的语义时使用 `cast`。
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::{DataType, Value};
+  ///
+  /// assert_eq!(Value::Real(3.0).coerce_to(DataType::Integer), Some(Value::Integer(3)));
+  /// assert_eq!(Value::Real(3.5).coerce_to(DataType::Integer), None);
+  ///
+  pub fn coerce_to(&self, to: DataType) -> Option<Value<'static>> {
+    if to.matches(self) {
+      return Some(self.clone().into_owned());
+    }
+
+    match (to, self) {
+      (DataType::Integer, Value::Real(r)) => real_to_integer(*r).map(Value::Integer),
+      (DataType::Integer, Value::Text(_) | Value::SharedText(_)) => {
+        let s = self.as_text()?.trim();
+        s.parse::<i64>()
+          .ok()
+          .or_else(|| s.parse::<f64>().ok().and_then(real_to_integer))
+          .map(Value::Integer)
+      }
+      (DataType::Real, Value::Integer(i)) => {
+        (i.unsigned_abs() <= MAX_EXACT_F64_INT).then_some(Value::Real(*i as f64))
+      }
+      (DataType::Real, Value::Text(_) | Value::SharedText(_)) => self
+        .as_text()?
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|r| r.is_finite())
+        .map(Value::Real),
+      (DataType::Text | DataType::Json, Value::Integer(i)) => {
+        Some(Value::Text(Cow::Owned(i.to_string())))
+      }
+      (DataType::Text | DataType::Json, Value::Real(r)) => {
+        Some(Value::Text(Cow::Owned(format_real(*r))))
+      }
+      _ => None,
+    }
+  }
+
+  /// SQL 显式类型转换（`CAST(value AS type)`）
+  ///
+  /// 按 SQLite 的 CAST 规则，总是返回一个值（无法解析时取 0 或空值，而不是报错）：
+  /// - NULL 转为任何类型仍是 NULL
+  /// - INTEGER：`Real` 向零截断（超出 i64 范围时饱和，NaN 为 0）；`Text`/`Blob` 取最长的整数前缀
+  ///   （忽略前导空白，如 `'12abc'` -> 12），没有前缀时为 0
+  /// - REAL：`Integer` 直接转换；`Text`/`Blob` 取最长的实数前缀，没有前缀时为 0.0
+  /// - TEXT/JSON：数字渲染为文本（实数见 `format_real`，`3.0` -> `'3.0'`），
+  ///   `Blob` 按 UTF-8 解释（非法字节替换为 U+FFFD）
+  /// - BLOB：`Text` 直接取字节，数字先渲染为文本再取字节
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::{DataType, Value};
+  /// use std::borrow::Cow;
+  ///
+  /// assert_eq!(Value::Text(Cow::Borrowed("12abc")).cast(DataType::Integer), Value::Integer(12));
+  /// assert_eq!(Value::Real(3.9).cast(DataType::Integer), Value::Integer(3));
+  /// assert_eq!(Value::Null.cast(DataType::Text), Value::Null);
+  ///
+  pub fn cast(&self, to: DataType) -> Value<'static> {
+    if matches!(self, Value::Null) {
+      return Value::Null;
+    }
+
+    match to {
+      DataType::Integer => Value::Integer(match self {
+        Value::Integer(i) => *i,
+        // `as` 向零截断，越界时饱和，NaN 为 0
+        Value::Real(r) => *r as i64,
+        _ => integer_prefix(&self.concat_text()),
+      }),
+      DataType::Real => Value::Real(match self {
+        Value::Integer(i) => *i as f64,
+        Value::Real(r) => *r,
+        _ => real_prefix(&self.concat_text()),
+      }),
+      DataType::Text | DataType::Json => Value::Text(Cow::Owned(self.concat_text().into_owned())),
+      DataType::Blob => match self {
+        Value::Blob(b) => Value::Blob(Cow::Owned(b.to_vec())),
+        _ => Value::Blob(Cow::Owned(self.concat_text().as_bytes().to_vec())),
+      },
+    }
+  }
+
   /// SQL 语义比较（NULL != NULL)
   ///
   /// 按照 SQL 的语义进行比较
@@ -180,10 +742,349 @@ impl<'v> Value<'v> {
       // 同类型比较
       (Value::Integer(a), Value::Integer(b)) => Some(a.cmp(b)),
       (Value::Real(a), Value::Real(b)) => a.partial_cmp(b),
-      (Value::Text(a), Value::Text(b)) => Some(a.cmp(b)),
+      (Value::Text(_) | Value::SharedText(_), Value::Text(_) | Value::SharedText(_)) => {
+        Some(self.as_text()?.cmp(other.as_text()?))
+      }
       (Value::Blob(a), Value::Blob(b)) => Some(a.cmp(b)),
       // 其他类型无法比较
       _ => None,
     }
   }
+
+  /// 宽松相等：数值跨类型相等，NULL 不等于任何值
+  ///
+  /// `Value` 上有三种相等：
+  /// - `PartialEq`（`==`）：结构相等，类型和值都相同；`NULL == NULL`，`Integer(3) != Real(3.0)`
+  /// - `sql_compare(..) == Some(Equal)`：SQL 语义，只比较同类型；NULL 与任何值不可比较
+  /// - `loose_eq`：在 SQL 语义基础上，`Integer` 与 `Real` 按数值精确比较
+  ///   （`Real` 必须恰好是该整数，不做舍入），适合 `value == expected` 式的断言
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::Value;
+  ///
+  /// assert!(Value::Integer(3).loose_eq(&Value::Real(3.0)));
+  /// assert!(!Value::Null.loose_eq(&Value::Null));
+  ///
+  pub fn loose_eq(&self, other: &Self) -> bool {
+    match (self, other) {
+      (Value::Integer(i), Value::Real(r)) | (Value::Real(r), Value::Integer(i)) => {
+        real_to_integer(*r) == Some(*i)
+      }
+      _ => self.sql_compare(other) == Some(Ordering::Equal),
+    }
+  }
+
+  /// 只比较两个 BLOB 的前 `len` 个字节（前缀索引扫描用）
+  ///
+  /// 不足 `len` 字节的 BLOB 按整体参与比较，因此较短的前缀小于较长的值。
+  /// 任一操作数不是 `Blob`（包括 NULL）时返回 `None`。
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::Value;
+  /// use std::borrow::Cow;
+  /// use std::cmp::Ordering;
+  ///
+  /// let a = Value::Blob(Cow::Borrowed(b"abcX"));
+  /// let b = Value::Blob(Cow::Borrowed(b"abcY"));
+  /// assert_eq!(a.compare_blob_prefix(&b, 3), Some(Ordering::Equal));
+  /// assert_eq!(a.compare_blob_prefix(&b, 4), Some(Ordering::Less));
+  ///
+  pub fn compare_blob_prefix(&self, other: &Self, len: usize) -> Option<Ordering> {
+    match (self, other) {
+      (Value::Blob(a), Value::Blob(b)) => Some(a[..len.min(a.len())].cmp(&b[..len.min(b.len())])),
+      _ => None,
+    }
+  }
+}
+
+impl Value<'static> {
+  /// 用 Unix 时间戳（秒）表示日期时间，存为 `Integer`
+  pub fn from_unix_secs(secs: i64) -> Value<'static> {
+    Value::Integer(secs)
+  }
+
+  /// 从 bincode 字节反序列化出拥有所有权的值（需要 `bincode` feature）
+  ///
+  /// `Text`/`Blob` 带 `#[serde(borrow)]`，不能直接反序列化为 `Value<'static>`
+  /// （要求输入字节也是 'static）；这里先借用 `bytes` 反序列化，再 `into_owned`。
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::Value;
+  /// use std::borrow::Cow;
+  ///
+  /// let bytes = bincode::serialize(&Value::Text(Cow::Borrowed("hi"))).unwrap();
+  /// let value: Value<'static> = Value::deserialize_owned(&bytes).unwrap();
+  ///
+  #[cfg(feature = "bincode")]
+  pub fn deserialize_owned(bytes: &[u8]) -> Result<Value<'static>, bincode::Error> {
+    let value: Value<'_> = bincode::deserialize(bytes)?;
+    Ok(value.into_owned())
+  }
+
+  /// 解析 ISO-8601（RFC 3339）文本为 Unix 时间戳（需要 `time` feature）
+  ///
+  /// 文本必须带时区（`Z` 或 `+08:00`），按时区换算到 UTC；小数秒被截断。
+  /// 格式不合法时返回 `None`。
+  ///
+  /// # Examples
+  ///
+  /// use rdb_domain::Value;
+  ///
+  /// let value = Value::from_iso8601("1970-01-01T08:00:01+08:00").unwrap();
+  /// assert_eq!(value.as_unix_secs(), Some(1));
+  ///
+  #[cfg(feature = "time")]
+  pub fn from_iso8601(text: &str) -> Option<Value<'static>> {
+    use time::format_description::well_known::Rfc3339;
+
+    let at = time::OffsetDateTime::parse(text, &Rfc3339).ok()?;
+    Some(Value::from_unix_secs(at.unix_timestamp()))
+  }
+
+  /// 从 `bytes` 开头解码一个 `encode_tagged` 编码的值，返回 `(值, 消耗的字节数)`
+  ///
+  /// 未知标签、数据不完整、长度溢出或 TEXT 不是合法 UTF-8 时返回 `None`。
+  /// `bytes` 中多余的字节不会被读取，可以连续解码多个值。
+  pub fn decode_tagged(bytes: &[u8]) -> Option<(Value<'static>, usize)> {
+    let (&tag, rest) = bytes.split_first()?;
+    let (value, len) = match tag {
+      TAG_NULL => (Value::Null, 0),
+      TAG_INTEGER => (
+        Value::Integer(i64::from_be_bytes(rest.get(..8)?.try_into().ok()?)),
+        8,
+      ),
+      TAG_REAL => (
+        Value::Real(f64::from_bits(u64::from_be_bytes(
+          rest.get(..8)?.try_into().ok()?,
+        ))),
+        8,
+      ),
+      TAG_TEXT | TAG_BLOB => {
+        let (n, prefix) = decode_len(rest)?;
+        let data = rest.get(prefix..prefix.checked_add(n)?)?;
+        let value = if tag == TAG_TEXT {
+          Value::Text(Cow::Owned(String::from_utf8(data.to_vec()).ok()?))
+        } else {
+          Value::Blob(Cow::Owned(data.to_vec()))
+        };
+        (value, prefix + n)
+      }
+      _ => return None,
+    };
+    Some((value, 1 + len))
+  }
+}
+
+/// SQL `COALESCE(...)`：返回第一个非 NULL 值的副本，全部为 NULL（或为空）时返回 NULL
+///
+/// 只克隆被选中的值；`Cow::Borrowed` 的 TEXT/BLOB 克隆后仍然借用原数据。
+///
+/// # Examples
+///
+/// use rdb_domain::value::coalesce;
+/// use rdb_domain::Value;
+///
+/// assert_eq!(coalesce(&[Value::Null, Value::Integer(2), Value::Integer(3)]), Value::Integer(2));
+/// assert_eq!(coalesce(&[Value::Null, Value::Null]), Value::Null);
+///
+pub fn coalesce<'v>(values: &[Value<'v>]) -> Value<'v> {
+  values
+    .iter()
+    .find(|v| !matches!(v, Value::Null))
+    .cloned()
+    .unwrap_or(Value::Null)
+}
+
+// `encode_tagged` 的类型标签
+const TAG_NULL: u8 = 0;
+const TAG_INTEGER: u8 = 1;
+const TAG_REAL: u8 = 2;
+const TAG_TEXT: u8 = 3;
+const TAG_BLOB: u8 = 4;
+
+/// LEB128 编码长度：每字节 7 bit，低位在前，最高位为续位标志
+fn encode_len(len: usize, out: &mut Vec<u8>) {
+  let mut rest = len as u64;
+  loop {
+    let byte = (rest & 0x7F) as u8;
+    rest >>= 7;
+    if rest == 0 {
+      out.push(byte);
+      return;
+    }
+    out.push(byte | 0x80);
+  }
+}
+
+/// 解码 LEB128 长度，返回 `(长度, 消耗的字节数)`；超出 `usize` 或不完整时返回 `None`
+fn decode_len(bytes: &[u8]) -> Option<(usize, usize)> {
+  let mut len: u64 = 0;
+  for (i, &byte) in bytes.iter().enumerate().take(10) {
+    let bits = u64::from(byte & 0x7F);
+    // 第 10 个字节只剩 1 bit 可用
+    if i == 9 && bits > 1 {
+      return None;
+    }
+    len |= bits << (7 * i);
+    if byte & 0x80 == 0 {
+      return Some((usize::try_from(len).ok()?, i + 1));
+    }
+  }
+  None
+}
+
+/// 文本形式：NULL 渲染为 `NULL`，整数按十进制，实数按 `format_real`（`3.0`），`Text` 原样输出，
+/// `Blob` 渲染为大写十六进制（与 SQLite 的 `hex()` 一致）
+///
+/// # Examples
+///
+/// use rdb_domain::Value;
+/// use std::borrow::Cow;
+///
+/// assert_eq!(Value::Integer(7).to_string(), "7");
+/// assert_eq!(Value::Blob(Cow::Borrowed(&[0xAB, 0x01])).to_string(), "AB01");
+///
+impl fmt::Display for Value<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Value::Null => f.write_str("NULL"),
+      Value::Integer(i) => write!(f, "{i}"),
+      Value::Real(r) => f.write_str(&format_real(*r)),
+      Value::Text(s) => f.write_str(s),
+      Value::SharedText(s) => f.write_str(s),
+      Value::Blob(b) => b.iter().try_for_each(|byte| write!(f, "{byte:02X}")),
+    }
+  }
+}
+
+/// 解析文本开头最长的整数前缀（`CAST AS INTEGER`），越界时饱和，没有前缀时为 0
+fn integer_prefix(s: &str) -> i64 {
+  let s = s.trim_start();
+  let (negative, digits) = match s.as_bytes().first() {
+    Some(b'-') => (true, &s[1..]),
+    Some(b'+') => (false, &s[1..]),
+    _ => (false, s),
+  };
+
+  let mut value: i64 = 0;
+  for b in digits.bytes().take_while(u8::is_ascii_digit) {
+    let d = i64::from(b - b'0');
+    // 按负数累加，i64::MIN 也能精确表示
+    value = match value.checked_mul(10).and_then(|v| v.checked_sub(d)) {
+      Some(v) => v,
+      None => return if negative { i64::MIN } else { i64::MAX },
+    };
+  }
+
+  if negative {
+    value
+  } else {
+    value.checked_neg().unwrap_or(i64::MAX)
+  }
+}
+
+/// 解析文本开头最长的实数前缀（`CAST AS REAL`），没有前缀时为 0.0
+fn real_prefix(s: &str) -> f64 {
+  let s = s.trim_start();
+  let bytes = s.as_bytes();
+  let digits_from = |mut i: usize| {
+    while bytes.get(i).is_some_and(u8::is_ascii_digit) {
+      i += 1;
+    }
+    i
+  };
+
+  let mut end = usize::from(matches!(bytes.first(), Some(b'+' | b'-')));
+  let int_end = digits_from(end);
+  let mut mantissa = int_end > end;
+  end = int_end;
+  if bytes.get(end) == Some(&b'.') {
+    let frac_end = digits_from(end + 1);
+    mantissa |= frac_end > end + 1;
+    end = frac_end;
+  }
+  if !mantissa {
+    return 0.0;
+  }
+
+  // 指数部分必须至少有一位数字，否则不计入前缀
+  if matches!(bytes.get(end), Some(b'e' | b'E')) {
+    let sign = usize::from(matches!(bytes.get(end + 1), Some(b'+' | b'-')));
+    let exp_end = digits_from(end + 1 + sign);
+    if exp_end > end + 1 + sign {
+      end = exp_end;
+    }
+  }
+
+  s[..end].parse().unwrap_or(0.0)
+}
+
+/// 按 SQLite 的规则把实数渲染为文本（`printf("%!.15g")`）
+///
+/// 保留 15 位有效数字并去掉末尾的 0，但总是带小数点（`3.0` 而不是 `3`）；
+/// 十进制指数小于 -4 或不小于 15 时用科学计数法，指数至少两位（`1.0e+30`、`1.5e-05`）。
+/// 非有限值渲染为 `NaN`、`Inf`、`-Inf`。`||` 拼接、`CAST(... AS TEXT)` 和 `Display` 都使用它。
+///
+/// # Examples
+///
+/// use rdb_domain::value::format_real;
+///
+/// assert_eq!(format_real(3.0), "3.0");
+/// assert_eq!(format_real(0.1 + 0.2), "0.3");
+/// assert_eq!(format_real(1e30), "1.0e+30");
+/// assert_eq!(format_real(-1.5e-5), "-1.5e-05");
+///
+pub fn format_real(r: f64) -> String {
+  if r.is_nan() {
+    return "NaN".to_string();
+  }
+  if r.is_infinite() {
+    return if r > 0.0 { "Inf" } else { "-Inf" }.to_string();
+  }
+
+  let sign = if r.is_sign_negative() { "-" } else { "" };
+  if r == 0.0 {
+    return format!("{sign}0.0");
+  }
+
+  // `{:.14e}` 给出四舍五入到 15 位有效数字的 `d.dddddddddddddde<exp>`
+  let sci = format!("{:.14e}", r.abs());
+  let (mantissa, exp) = sci.split_once('e').unwrap_or((&sci, "0"));
+  let exp: i32 = exp.parse().unwrap_or(0);
+  let digits: String = mantissa.chars().filter(char::is_ascii_digit).collect();
+  let digits = digits.trim_end_matches('0');
+
+  if !(-4..15).contains(&exp) {
+    let (first, rest) = digits.split_at(1);
+    let rest = if rest.is_empty() { "0" } else { rest };
+    let exp_sign = if exp < 0 { '-' } else { '+' };
+    return format!("{sign}{first}.{rest}e{exp_sign}{:02}", exp.unsigned_abs());
+  }
+
+  if exp < 0 {
+    let zeros = "0".repeat(exp.unsigned_abs() as usize - 1);
+    return format!("{sign}0.{zeros}{digits}");
+  }
+
+  let int_len = exp as usize + 1;
+  let int_part = format!("{digits:0<int_len$}");
+  let (int_part, frac) = int_part.split_at(int_len);
+  let frac = if frac.is_empty() { "0" } else { frac };
+  format!("{sign}{int_part}.{frac}")
+}
+
+/// f64 能精确表示的最大整数（2^53）
+const MAX_EXACT_F64_INT: u64 = 1 << 53;
+
+/// 把整数值的 f64 无损转换为 i64（非整数、越界、NaN 返回 None）
+pub(crate) fn real_to_integer(r: f64) -> Option<i64> {
+  // i64::MAX as f64 会舍入到 2^63，因此上界用开区间
+  if r.fract() == 0.0 && r >= i64::MIN as f64 && r < i64::MAX as f64 {
+    Some(r as i64)
+  } else {
+    None
+  }
 }
@@ -2,10 +2,13 @@
 //!
 //! 测试所有领域类型的构造和验证功能
 
+#![allow(clippy::approx_constant, clippy::bool_assert_comparison)]
+
 use rdb_domain::*;
 use std::borrow::Cow;
 use std::cmp::Ordering;
 use std::path::Path;
+use std::sync::Arc;
 
 // ===============================================
 // ID 类型测试
@@ -117,6 +120,123 @@ fn test_data_type_to_sql_type() {
   assert_eq!(DataType::Blob.to_sql_type(), "BLOB");
 }
 
+#[test]
+fn test_data_type_json() {
+  assert_eq!(DataType::from_sql_type("json"), Some(DataType::Json));
+  assert_eq!(DataType::Json.to_sql_type(), "JSON");
+
+  // JSON 列接受任意文本，内容校验推迟到读取时
+  assert!(DataType::Json.matches(&Value::Text(Cow::Borrowed("{not json"))));
+  assert!(DataType::Json.matches(&Value::Null));
+  assert!(!DataType::Json.matches(&Value::Integer(1)));
+
+  let column = Column::new(ColumnId::new(1), "doc".to_string(), DataType::Json);
+  assert!(column
+    .validate_value(&Value::Text(Cow::Borrowed(r#"{"a":1}"#)))
+    .is_ok());
+  assert!(column.validate_value(&Value::Integer(1)).is_err());
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_value_as_json() {
+  let valid = Value::Text(Cow::Borrowed(r#"{"name": "Alice", "tags": [1, 2]}"#));
+  let json = valid.as_json().unwrap();
+  assert_eq!(json["name"], "Alice");
+  assert_eq!(json["tags"][1], 2);
+
+  assert_eq!(Value::Text(Cow::Borrowed("{not json")).as_json(), None);
+  assert_eq!(Value::Integer(1).as_json(), None);
+}
+
+#[test]
+fn test_value_coalesce_and_if_null() {
+  let text = Value::Text(Cow::Borrowed("x"));
+  assert_eq!(
+    value::coalesce(&[Value::Null, Value::Null, text.clone(), Value::Integer(1)]),
+    text
+  );
+  assert!(value::coalesce(&[Value::Null, text.clone()]).is_borrowed());
+  assert_eq!(value::coalesce(&[Value::Null, Value::Null]), Value::Null);
+  assert_eq!(value::coalesce(&[]), Value::Null);
+
+  assert_eq!(Value::Null.if_null(Value::Real(0.5)), Value::Real(0.5));
+  assert_eq!(
+    Value::Integer(0).if_null(Value::Real(0.5)),
+    Value::Integer(0)
+  );
+  assert_eq!(Value::Null.if_null(Value::Null), Value::Null);
+}
+
+#[test]
+fn test_value_unix_secs() {
+  let value = Value::from_unix_secs(1_700_000_000);
+  assert_eq!(value, Value::Integer(1_700_000_000));
+  assert_eq!(value.as_unix_secs(), Some(1_700_000_000));
+  assert_eq!(Value::from_unix_secs(-86_400).as_unix_secs(), Some(-86_400));
+
+  assert_eq!(Value::Real(1.0).as_unix_secs(), None);
+  assert_eq!(
+    Value::Text(Cow::Borrowed("1970-01-01T00:00:00Z")).as_unix_secs(),
+    None
+  );
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn test_value_iso8601_roundtrip() {
+  let value = Value::from_unix_secs(1_700_000_000);
+  let text = value.to_iso8601().unwrap();
+  assert_eq!(text, "2023-11-14T22:13:20Z");
+  assert_eq!(Value::from_iso8601(&text), Some(value));
+
+  // 时区换算到 UTC，小数秒截断
+  assert_eq!(
+    Value::from_iso8601("2023-11-15T06:13:20.75+08:00"),
+    Some(Value::Integer(1_700_000_000))
+  );
+
+  assert_eq!(Value::from_iso8601("2023-11-14"), None);
+  assert_eq!(Value::from_iso8601("not a date"), None);
+  assert_eq!(Value::Text(Cow::Borrowed("2023")).to_iso8601(), None);
+  assert_eq!(Value::from_unix_secs(i64::MAX).to_iso8601(), None);
+}
+
+#[cfg(feature = "bincode")]
+#[test]
+fn test_deserialize_owned_roundtrip() {
+  let values = vec![
+    Value::Null,
+    Value::Integer(-42),
+    Value::Real(2.5),
+    Value::Text(Cow::Borrowed("héllo")),
+    Value::Blob(Cow::Borrowed(&[0x00, 0xFF, 0x10])),
+  ];
+
+  for value in &values {
+    let bytes = bincode::serialize(value).unwrap();
+    let owned: Value<'static> = Value::deserialize_owned(&bytes).unwrap();
+    drop(bytes);
+    assert_eq!(&owned, value);
+  }
+
+  let row = Row::new(RowId::new(7), values.clone());
+  let bytes = bincode::serialize(&row).unwrap();
+  let owned: Row<'static> = Row::deserialize_owned(&bytes).unwrap();
+  drop(bytes);
+  assert_eq!(owned.row_id, RowId::new(7));
+  assert_eq!(owned.values, values);
+
+  assert!(Value::deserialize_owned(&[0xFF]).is_err());
+
+  // 驻留文本与普通 Text 编码相同
+  let interned = Value::Text(Cow::Borrowed("héllo")).intern(&StringInterner::new());
+  assert_eq!(
+    bincode::serialize(&interned).unwrap(),
+    bincode::serialize(&values[3]).unwrap()
+  );
+}
+
 // ===============================================
 // Value 测试
 // ===============================================
@@ -151,6 +271,100 @@ fn test_value_real() {
   assert_eq!(value.as_blob(), None);
 }
 
+#[test]
+fn test_value_numeric_accessors() {
+  assert_eq!(Value::Integer(-7).as_f64_lossy(), Some(-7.0));
+  assert_eq!(Value::Integer(-7).as_i64_checked(), Some(-7));
+
+  assert_eq!(Value::Real(42.0).as_f64_lossy(), Some(42.0));
+  assert_eq!(Value::Real(42.0).as_i64_checked(), Some(42));
+  assert_eq!(
+    Value::Real(i64::MIN as f64).as_i64_checked(),
+    Some(i64::MIN)
+  );
+  assert_eq!(Value::Real(9.3e18).as_i64_checked(), None);
+
+  assert_eq!(Value::Real(2.5).as_f64_lossy(), Some(2.5));
+  assert_eq!(Value::Real(2.5).as_i64_checked(), None);
+  assert_eq!(Value::Real(f64::NAN).as_i64_checked(), None);
+  assert_eq!(Value::Real(f64::INFINITY).as_i64_checked(), None);
+
+  let text = Value::Text(Cow::Borrowed("42"));
+  assert_eq!(text.as_f64_lossy(), None);
+  assert_eq!(text.as_i64_checked(), None);
+  assert_eq!(Value::Null.as_f64_lossy(), None);
+}
+
+#[test]
+fn test_value_round_and_abs() {
+  assert_eq!(Value::Real(3.14159).round(2), Value::Real(3.14));
+  assert_eq!(Value::Real(-1.005).round(2), Value::Real(-1.0));
+  assert_eq!(Value::Real(2.345).round(-1), Value::Real(2.0));
+  // 远离零舍入，而不是银行家舍入
+  assert_eq!(Value::Real(2.5).round(0), Value::Real(3.0));
+  assert_eq!(Value::Real(-2.5).round(0), Value::Real(-3.0));
+  assert_eq!(Value::Real(0.125).round(2), Value::Real(0.13));
+
+  assert_eq!(Value::Integer(42).round(2), Value::Integer(42));
+  assert_eq!(Value::Null.round(2), Value::Null);
+  assert_eq!(Value::Text(Cow::Borrowed("1.5")).round(0), Value::Null);
+
+  assert_eq!(Value::Real(-2.75).abs(), Value::Real(2.75));
+  assert_eq!(Value::Integer(-5).abs(), Value::Integer(5));
+  assert_eq!(
+    Value::Integer(i64::MIN).abs(),
+    Value::Real(9.223372036854776e18)
+  );
+  assert_eq!(Value::Null.abs(), Value::Null);
+}
+
+#[test]
+fn test_value_compare_blob_prefix() {
+  let blob = |b: &'static [u8]| Value::Blob(Cow::Borrowed(b));
+  let a = blob(b"prefix-1");
+  let b = blob(b"prefix-2");
+
+  // 只在前缀之后不同
+  assert_eq!(a.compare_blob_prefix(&b, 7), Some(Ordering::Equal));
+  assert_eq!(a.compare_blob_prefix(&b, 0), Some(Ordering::Equal));
+  // 前缀之内不同
+  assert_eq!(a.compare_blob_prefix(&b, 8), Some(Ordering::Less));
+  assert_eq!(
+    blob(b"q").compare_blob_prefix(&a, 3),
+    Some(Ordering::Greater)
+  );
+  // 长度不足前缀时整体比较
+  assert_eq!(
+    blob(b"pre").compare_blob_prefix(&a, 7),
+    Some(Ordering::Less)
+  );
+  assert_eq!(a.compare_blob_prefix(&b, 100), Some(Ordering::Less));
+
+  assert_eq!(
+    a.compare_blob_prefix(&Value::Text(Cow::Borrowed("prefix-1")), 3),
+    None
+  );
+  assert_eq!(Value::Null.compare_blob_prefix(&a, 3), None);
+}
+
+#[test]
+fn test_value_loose_eq() {
+  assert_ne!(Value::Integer(3), Value::Real(3.0));
+  assert!(Value::Integer(3).loose_eq(&Value::Real(3.0)));
+  assert!(Value::Real(3.0).loose_eq(&Value::Integer(3)));
+  assert!(!Value::Integer(3).loose_eq(&Value::Real(3.5)));
+  // i64::MAX 不能精确表示为 f64，不会因舍入而相等
+  assert!(!Value::Integer(i64::MAX).loose_eq(&Value::Real(i64::MAX as f64)));
+
+  assert_eq!(Value::Null, Value::Null);
+  assert!(!Value::Null.loose_eq(&Value::Null));
+  assert!(!Value::Null.loose_eq(&Value::Integer(0)));
+
+  let text = Value::Text(Cow::Borrowed("3"));
+  assert!(text.loose_eq(&Value::Text(Cow::Owned("3".to_string()))));
+  assert!(!text.loose_eq(&Value::Integer(3)));
+}
+
 #[test]
 fn test_value_text_borrowed() {
   let s = "hello";
@@ -196,6 +410,21 @@ fn test_value_into_owned() {
   assert_eq!(owned.as_blob(), Some(b"world" as &[u8]));
 }
 
+#[test]
+fn test_value_is_borrowed_or_owned() {
+  let borrowed = Value::Text(Cow::Borrowed("hello"));
+  assert!(borrowed.is_borrowed());
+  assert!(!borrowed.is_owned());
+
+  let owned = Value::Text(Cow::Owned("hello".to_string()));
+  assert!(!owned.is_borrowed());
+  assert!(owned.is_owned());
+
+  let integer = Value::Integer(1);
+  assert!(!integer.is_borrowed());
+  assert!(integer.is_owned());
+}
+
 #[test]
 fn test_value_sql_compare() {
   // 同类型比较
@@ -233,6 +462,213 @@ fn test_value_sql_compare() {
   assert_eq!(Value::Integer(1).sql_compare(&Value::Real(1.0)), None);
 }
 
+#[test]
+fn test_value_normalize_numeric() {
+  assert_eq!(Value::Real(3.0).normalize_numeric(), Value::Integer(3));
+  assert_eq!(Value::Real(-42.0).normalize_numeric(), Value::Integer(-42));
+  assert_eq!(Value::Real(3.5).normalize_numeric(), Value::Real(3.5));
+  assert_eq!(Value::Real(1e30).normalize_numeric(), Value::Real(1e30));
+  assert!(matches!(Value::Real(f64::NAN).normalize_numeric(), Value::Real(r) if r.is_nan()));
+
+  // 非 Real 原样返回
+  assert_eq!(Value::Integer(7).normalize_numeric(), Value::Integer(7));
+  assert_eq!(
+    Value::Text(Cow::Borrowed("3.0")).normalize_numeric(),
+    Value::Text(Cow::Borrowed("3.0"))
+  );
+}
+
+#[test]
+fn test_value_stable_hash() {
+  let values = [
+    Value::Null,
+    Value::Integer(42),
+    Value::Real(42.0),
+    Value::Text(Cow::Borrowed("42")),
+    Value::Blob(Cow::Borrowed(b"42")),
+  ];
+
+  // 同一个值多次计算结果相同，且与借用/拥有无关
+  for v in &values {
+    assert_eq!(v.stable_hash(1), v.stable_hash(1));
+    assert_eq!(v.stable_hash(1), v.clone().into_owned().stable_hash(1));
+  }
+  assert_eq!(
+    Value::Text(Cow::Borrowed("42")).stable_hash(9),
+    Value::Text(Cow::Owned("42".to_string())).stable_hash(9)
+  );
+
+  // 固定算法：结果与运行环境无关
+  assert_eq!(Value::Null.stable_hash(0), 0xe604_823a_2490_29bf);
+
+  // 不同的值、不同的种子得到不同的哈希
+  for (i, a) in values.iter().enumerate() {
+    for b in &values[i + 1..] {
+      assert_ne!(a.stable_hash(1), b.stable_hash(1), "{a:?} vs {b:?}");
+    }
+  }
+  assert_ne!(
+    Value::Integer(42).stable_hash(1),
+    Value::Integer(42).stable_hash(2)
+  );
+}
+
+#[test]
+fn test_value_cast() {
+  let text = |s: &'static str| Value::Text(Cow::Borrowed(s));
+
+  // CAST AS INTEGER
+  assert_eq!(text("12abc").cast(DataType::Integer), Value::Integer(12));
+  assert_eq!(text("  -7.9").cast(DataType::Integer), Value::Integer(-7));
+  assert_eq!(text("abc").cast(DataType::Integer), Value::Integer(0));
+  assert_eq!(
+    text("99999999999999999999").cast(DataType::Integer),
+    Value::Integer(i64::MAX)
+  );
+  assert_eq!(Value::Real(3.9).cast(DataType::Integer), Value::Integer(3));
+  assert_eq!(
+    Value::Real(-3.9).cast(DataType::Integer),
+    Value::Integer(-3)
+  );
+  assert_eq!(
+    Value::Blob(Cow::Borrowed(b"42")).cast(DataType::Integer),
+    Value::Integer(42)
+  );
+
+  // CAST AS REAL
+  assert_eq!(text("1.5e2xyz").cast(DataType::Real), Value::Real(150.0));
+  assert_eq!(text("2e").cast(DataType::Real), Value::Real(2.0));
+  assert_eq!(text("x").cast(DataType::Real), Value::Real(0.0));
+  assert_eq!(Value::Integer(5).cast(DataType::Real), Value::Real(5.0));
+
+  // CAST AS TEXT / BLOB
+  assert_eq!(Value::Integer(5).cast(DataType::Text), text("5"));
+  assert_eq!(Value::Real(3.0).cast(DataType::Text), text("3.0"));
+  assert_eq!(Value::Real(1e30).cast(DataType::Text), text("1.0e+30"));
+  assert_eq!(
+    Value::Real(3.0).cast(DataType::Blob),
+    Value::Blob(Cow::Borrowed(b"3.0"))
+  );
+  assert_eq!(
+    Value::Blob(Cow::Borrowed(b"hi")).cast(DataType::Text),
+    text("hi")
+  );
+  assert_eq!(
+    text("hi").cast(DataType::Blob),
+    Value::Blob(Cow::Borrowed(b"hi"))
+  );
+
+  // NULL 始终是 NULL
+  for to in [DataType::Integer, DataType::Real, DataType::Text, DataType::Blob] {
+    assert_eq!(Value::Null.cast(to), Value::Null);
+  }
+}
+
+#[test]
+fn test_value_display() {
+  assert_eq!(Value::Null.to_string(), "NULL");
+  assert_eq!(Value::Integer(-3).to_string(), "-3");
+  assert_eq!(Value::Real(2.5).to_string(), "2.5");
+  assert_eq!(Value::Real(3.0).to_string(), "3.0");
+  assert_eq!(Value::Real(1e30).to_string(), "1.0e+30");
+  assert_eq!(Value::Text(Cow::Borrowed("a,b")).to_string(), "a,b");
+  assert_eq!(
+    Value::Blob(Cow::Borrowed(&[0x00, 0xFF, 0x1A])).to_string(),
+    "00FF1A"
+  );
+}
+
+#[test]
+fn test_value_len() {
+  let text = Value::Text(Cow::Borrowed("héllo"));
+  assert_eq!(text.len(), Some(5));
+  assert_eq!(text.byte_len(), Some(6));
+
+  let blob = Value::Blob(Cow::Borrowed(&[1, 2, 3]));
+  assert_eq!(blob.len(), Some(3));
+  assert_eq!(blob.byte_len(), Some(3));
+
+  assert_eq!(Value::Null.len(), None);
+  assert_eq!(Value::Null.byte_len(), None);
+  assert_eq!(Value::Integer(123).len(), None);
+}
+
+#[test]
+fn test_value_as_bytes() {
+  assert_eq!(
+    Value::Text(Cow::Borrowed("é")).as_bytes(),
+    Some(&[0xC3, 0xA9][..])
+  );
+  assert_eq!(
+    Value::Blob(Cow::Borrowed(&[1, 2])).as_bytes(),
+    Some(&[1, 2][..])
+  );
+  assert_eq!(Value::Integer(42).as_bytes(), None);
+  assert_eq!(Value::Null.as_bytes(), None);
+}
+
+#[test]
+fn test_value_concat() {
+  let hello = Value::Text(Cow::Borrowed("hello "));
+  let world = Value::Text(Cow::Borrowed("world"));
+
+  // Text || Text
+  assert_eq!(
+    hello.concat(&world),
+    Value::Text(Cow::Borrowed("hello world"))
+  );
+
+  // Integer || Text：数字转为文本
+  assert_eq!(
+    Value::Integer(42).concat(&world),
+    Value::Text(Cow::Borrowed("42world"))
+  );
+
+  // Real || Text：实数按 SQLite 的 %!.15g 渲染
+  let x = Value::Text(Cow::Borrowed("x"));
+  assert_eq!(
+    Value::Real(3.0).concat(&x),
+    Value::Text(Cow::Borrowed("3.0x"))
+  );
+  assert_eq!(
+    Value::Real(0.5).concat(&x),
+    Value::Text(Cow::Borrowed("0.5x"))
+  );
+  assert_eq!(
+    Value::Real(1e30).concat(&x),
+    Value::Text(Cow::Borrowed("1.0e+30x"))
+  );
+
+  // NULL || Text
+  assert_eq!(Value::Null.concat(&world), Value::Null);
+  assert_eq!(world.concat(&Value::Null), Value::Null);
+
+  // Blob || Blob 保持为 Blob
+  let a = Value::Blob(Cow::Borrowed(&[1u8, 2][..]));
+  let b = Value::Blob(Cow::Borrowed(&[3u8][..]));
+  assert_eq!(a.concat(&b), Value::Blob(Cow::Owned(vec![1, 2, 3])));
+}
+
+#[test]
+fn test_format_real_matches_sqlite() {
+  assert_eq!(value::format_real(3.0), "3.0");
+  assert_eq!(value::format_real(-2.0), "-2.0");
+  assert_eq!(value::format_real(0.0), "0.0");
+  assert_eq!(value::format_real(100.0), "100.0");
+  assert_eq!(value::format_real(2.5), "2.5");
+  assert_eq!(value::format_real(0.1 + 0.2), "0.3");
+  assert_eq!(value::format_real(0.0001), "0.0001");
+  assert_eq!(value::format_real(0.00001), "1.0e-05");
+  assert_eq!(value::format_real(123456789012345.0), "123456789012345.0");
+  assert_eq!(value::format_real(1e15), "1.0e+15");
+  assert_eq!(value::format_real(1e30), "1.0e+30");
+  assert_eq!(value::format_real(1.5e300), "1.5e+300");
+  assert_eq!(value::format_real(123456789.1234567), "123456789.123457");
+  assert_eq!(value::format_real(f64::INFINITY), "Inf");
+  assert_eq!(value::format_real(f64::NEG_INFINITY), "-Inf");
+  assert_eq!(value::format_real(f64::NAN), "NaN");
+}
+
 // ===============================================
 // ColumnConstraints 测试
 // ===============================================
@@ -248,8 +684,13 @@ fn test_column_constraints_defaults() {
 
 #[test]
 fn test_column_constraints_construction() {
-  let constraints =
-    ColumnConstraints { not_null: true, unique: true, primary_key: true, autoincrement: true };
+  let constraints = ColumnConstraints {
+    not_null: true,
+    unique: true,
+    primary_key: true,
+    autoincrement: true,
+    max_length: None,
+  };
   assert_eq!(constraints.not_null, true);
   assert_eq!(constraints.unique, true);
   assert_eq!(constraints.primary_key, true);
@@ -321,6 +762,86 @@ fn test_column_validate_value_type_mismatch() {
   }
 }
 
+#[test]
+fn test_column_validate_value_coercing() {
+  let int_col = Column::new(ColumnId::new(1), "n".to_string(), DataType::Integer);
+
+  assert_eq!(
+    int_col.validate_value_coercing(&Value::Real(3.0)),
+    Ok(Value::Integer(3))
+  );
+  assert_eq!(
+    int_col.validate_value_coercing(&Value::Integer(7)),
+    Ok(Value::Integer(7))
+  );
+  assert_eq!(
+    int_col.validate_value_coercing(&Value::Text(Cow::Borrowed(" 42 "))),
+    Ok(Value::Integer(42))
+  );
+  assert!(matches!(
+    int_col.validate_value_coercing(&Value::Real(3.5)),
+    Err(DomainError::TypeMismatch { .. })
+  ));
+  assert!(int_col
+    .validate_value_coercing(&Value::Text(Cow::Borrowed("abc")))
+    .is_err());
+
+  let real_col = Column::new(ColumnId::new(2), "r".to_string(), DataType::Real);
+  assert_eq!(
+    real_col.validate_value_coercing(&Value::Integer(2)),
+    Ok(Value::Real(2.0))
+  );
+
+  let mut not_null = Column::new(ColumnId::new(3), "x".to_string(), DataType::Integer);
+  not_null.constraints.not_null = true;
+  assert!(matches!(
+    not_null.validate_value_coercing(&Value::Null),
+    Err(DomainError::NotNullViolation { .. })
+  ));
+}
+
+#[test]
+fn test_column_validate_value_coercing_real_into_text_matches_cast() {
+  let text_col = Column::new(ColumnId::new(1), "t".to_string(), DataType::Text);
+
+  // 与 CAST(3.0 AS TEXT) 一样得到 '3.0'，而不是 '3'
+  assert_eq!(
+    text_col.validate_value_coercing(&Value::Real(3.0)),
+    Ok(Value::Text(Cow::Borrowed("3.0")))
+  );
+  assert_eq!(
+    text_col.validate_value_coercing(&Value::Real(1e30)),
+    Ok(Value::Text(Cow::Borrowed("1.0e+30")))
+  );
+  assert_eq!(
+    Value::Real(3.0).coerce_to(DataType::Text),
+    Some(Value::Real(3.0).cast(DataType::Text))
+  );
+}
+
+#[test]
+fn test_column_equality_and_same_shape() {
+  let a = Column::new(ColumnId::new(1), "price".to_string(), DataType::Real);
+  let mut same = a.clone();
+  same.default_value = None;
+  assert_eq!(a, same);
+  assert!(a.same_shape(&same));
+
+  // 只有 id 不同
+  let renumbered = Column { id: ColumnId::new(7), ..a.clone() };
+  assert_ne!(a, renumbered);
+  assert!(a.same_shape(&renumbered));
+
+  // 类型不同
+  let retyped = Column::new(ColumnId::new(1), "price".to_string(), DataType::Integer);
+  assert_ne!(a, retyped);
+  assert!(!a.same_shape(&retyped));
+
+  // 默认值参与比较
+  let defaulted = Column { default_value: Some(Value::Real(1.5)), ..a.clone() };
+  assert!(!a.same_shape(&defaulted));
+}
+
 // ===============================================
 // Table 测试
 // ===============================================
@@ -396,105 +917,783 @@ fn test_table_primary_key_column() {
   assert!(table_no_pk.primary_key_column().is_none());
 }
 
-// ===============================================
-// Row<'r> 测试
-// ===============================================
-
 #[test]
-fn test_row_new() {
-  let values = vec![Value::Integer(1), Value::Text(Cow::Borrowed("Alice"))];
-  let row = Row::new(RowId::new(1), values);
-  assert_eq!(row.row_id, RowId::new(1));
-  assert_eq!(row.values.len(), 2);
+fn test_table_validate_row() {
+  let table = users_table_for_builder();
+
+  let ok = Row::new(
+    RowId::new(1),
+    vec![Value::Integer(1), Value::Text(Cow::Borrowed("Alice")), Value::Integer(30)],
+  );
+  assert!(table.validate_row(&ok).is_ok());
+  assert!(table.validate_row_collect(&ok).is_ok());
+
+  let short = Row::new(RowId::new(1), vec![Value::Integer(1)]);
+  assert_eq!(
+    table.validate_row(&short),
+    Err(DomainError::RowLengthMismatch { expected: 3, got: 1 })
+  );
 }
 
 #[test]
-fn test_row_get() {
-  let values = vec![Value::Integer(1), Value::Text(Cow::Borrowed("Alice"))];
-  let row = Row::new(RowId::new(1), values);
+fn test_table_validate_row_collect_reports_all_errors() {
+  let table = users_table_for_builder();
 
-  assert_eq!(row.get(0), Some(&Value::Integer(1)));
-  assert_eq!(row.get(1), Some(&Value::Text(Cow::Borrowed("Alice"))));
-  assert_eq!(row.get(2), None);
+  // id 违反 NOT NULL，age 类型不匹配
+  let row = Row::new(
+    RowId::new(1),
+    vec![Value::Null, Value::Text(Cow::Borrowed("Alice")), Value::Real(1.5)],
+  );
+
+  // validate_row 只报告第一个错误
+  assert!(matches!(
+    table.validate_row(&row),
+    Err(DomainError::NotNullViolation { .. })
+  ));
+
+  let errors = table.validate_row_collect(&row).unwrap_err();
+  assert_eq!(errors.len(), 2);
+  assert_eq!(
+    errors[0],
+    DomainError::NotNullViolation { name: "id".to_string() }
+  );
+  assert!(matches!(&errors[1], DomainError::TypeMismatch { name, .. } if name == "age"));
 }
 
 #[test]
-fn test_row_get_by_name() {
-  let columns = vec![
-    Column::new(ColumnId::new(1), "id".to_string(), DataType::Integer),
-    Column::new(ColumnId::new(2), "name".to_string(), DataType::Text),
-  ];
+fn test_table_to_create_sql() {
+  let id = Column::with_constraints(
+    ColumnId::new(1),
+    "id".to_string(),
+    DataType::Integer,
+    ColumnConstraints { primary_key: true, autoincrement: true, ..Default::default() },
+  );
+  let name = Column::with_constraints(
+    ColumnId::new(2),
+    "name".to_string(),
+    DataType::Text,
+    ColumnConstraints { not_null: true, unique: true, ..Default::default() },
+  );
   let table = Table::new(
     TableId::new(1),
     "users".to_string(),
-    columns,
+    vec![id, name],
     None,
     PageId::new(1),
   );
 
-  let values = vec![Value::Integer(1), Value::Text(Cow::Borrowed("Alice"))];
-  let row = Row::new(RowId::new(1), values);
-
-  assert_eq!(row.get_by_name("id", &table), Some(&Value::Integer(1)));
   assert_eq!(
-    row.get_by_name("name", &table),
-    Some(&Value::Text(Cow::Borrowed("Alice")))
+    table.to_create_sql(),
+    "CREATE TABLE users (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT NOT NULL UNIQUE)"
   );
-  assert_eq!(row.get_by_name("nonexistent", &table), None);
-}
-
-#[test]
-fn test_row_into_owned() {
-  let s = "hello";
-  let values = vec![Value::Text(Cow::Borrowed(s))];
-  let row = Row::new(RowId::new(1), values);
-  let owned: Row<'static> = row.into_owned();
-  assert_eq!(owned.get(0).unwrap().as_text(), Some("hello"));
-}
-
-// ===============================================
-// Database 测试
-// ===============================================
-
-#[test]
-fn test_database_new() {
-  let db = Database::new(Path::new("/tmp/test.db"));
-  assert_eq!(db.path, Path::new("/tmp/test.db"));
-  assert_eq!(db.tables.len(), 0);
-  assert_eq!(db.indexes.len(), 0);
-  assert_eq!(db.schema_version, 0);
 }
 
 #[test]
-fn test_database_add_table() {
-  let mut db = Database::new(Path::new("/tmp/test.db"));
-
-  let columns = vec![Column::new(ColumnId::new(1), "id".to_string(), DataType::Integer)];
+fn test_table_to_create_sql_quotes_identifiers() {
+  let columns = vec![
+    Column::new(ColumnId::new(1), "order".to_string(), DataType::Integer),
+    Column::new(ColumnId::new(2), "first name".to_string(), DataType::Text),
+    Column::new(ColumnId::new(3), "a\"b".to_string(), DataType::Blob),
+  ];
   let table = Table::new(
     TableId::new(1),
-    "users".to_string(),
+    "my table".to_string(),
     columns,
-    None,
+    Some(ColumnId::new(1)),
     PageId::new(1),
   );
 
-  let table_id = db.add_table(table).unwrap();
-  assert_eq!(table_id, TableId::new(1));
-  assert_eq!(db.tables.len(), 1);
-  assert_eq!(db.schema_version, 1);
+  assert_eq!(
+    table.to_create_sql(),
+    r#"CREATE TABLE "my table" ("order" INTEGER PRIMARY KEY, "first name" TEXT, "a""b" BLOB)"#
+  );
 }
 
 #[test]
-fn test_database_add_table_duplicate_name() {
-  let mut db = Database::new(Path::new("/tmp/test.db"));
-
-  let columns = vec![Column::new(ColumnId::new(1), "id".to_string(), DataType::Integer)];
-  let table1 = Table::new(
+fn test_table_to_create_sql_emits_defaults_checks_and_foreign_keys() {
+  let users = Table::new(
     TableId::new(1),
     "users".to_string(),
-    columns.clone(),
-    None,
-    PageId::new(1),
+    vec![Column::new(ColumnId::new(1), "id".to_string(), DataType::Integer)],
+    Some(ColumnId::new(1)),
+    PageId::new(2),
+  );
+
+  let mut status = Column::with_constraints(
+    ColumnId::new(3),
+    "status".to_string(),
+    DataType::Text,
+    ColumnConstraints { not_null: true, max_length: Some(16), ..Default::default() },
+  );
+  status.default_value = Some(Value::Text(Cow::Borrowed("it's new")));
+  let mut score = Column::new(ColumnId::new(4), "score".to_string(), DataType::Real);
+  score.default_value = Some(Value::Real(0.1));
+  let mut tag = Column::new(ColumnId::new(5), "tag".to_string(), DataType::Blob);
+  tag.default_value = Some(Value::Blob(Cow::Borrowed(&[0xCA, 0xFE])));
+  let mut qty = Column::new(ColumnId::new(6), "qty".to_string(), DataType::Integer);
+  qty.default_value = Some(Value::Integer(-1));
+
+  let orders = Table::new(
+    TableId::new(2),
+    "orders".to_string(),
+    vec![
+      Column::new(ColumnId::new(1), "id".to_string(), DataType::Integer),
+      Column::new(ColumnId::new(2), "user_id".to_string(), DataType::Integer),
+      status,
+      score,
+      tag,
+      qty,
+      Column::new(ColumnId::new(7), "parent_id".to_string(), DataType::Integer),
+    ],
+    Some(ColumnId::new(1)),
+    PageId::new(3),
+  )
+  .with_foreign_keys(vec![
+    ForeignKey {
+      columns: vec![ColumnId::new(2)],
+      ref_table: TableId::new(1),
+      ref_columns: vec![ColumnId::new(1)],
+      on_delete: ForeignKeyAction::Cascade,
+    },
+    ForeignKey {
+      columns: vec![ColumnId::new(7)],
+      ref_table: TableId::new(2),
+      ref_columns: vec![ColumnId::new(1)],
+      on_delete: ForeignKeyAction::NoAction,
+    },
+  ]);
+  assert_eq!(orders.validate(), Ok(()));
+
+  let columns = "id INTEGER PRIMARY KEY, user_id INTEGER, \
+    status TEXT NOT NULL DEFAULT 'it''s new' CHECK (length(status) <= 16), \
+    score REAL DEFAULT 0.1, tag BLOB DEFAULT X'CAFE', qty INTEGER DEFAULT -1, parent_id INTEGER";
+  let self_fk = "FOREIGN KEY (parent_id) REFERENCES orders (id)";
+  let users_fk = "FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE";
+
+  // 单独一张表只能解析自引用的外键
+  assert_eq!(
+    orders.to_create_sql(),
+    format!("CREATE TABLE orders ({columns}, {self_fk})")
+  );
+
+  // 通过数据库解析引用其他表的外键
+  let mut db = Database::new(Path::new("/tmp/test.db"));
+  db.add_table(users).unwrap();
+  db.add_table(orders).unwrap();
+  assert_eq!(
+    db.to_schema_sql(),
+    format!(
+      "CREATE TABLE users (id INTEGER PRIMARY KEY);\n\
+       CREATE TABLE orders ({columns}, {users_fk}, {self_fk});\n"
+    )
+  );
+}
+
+#[test]
+fn test_table_validate() {
+  let pk = ColumnConstraints { primary_key: true, autoincrement: true, ..Default::default() };
+  let valid = Table::new(
+    TableId::new(1),
+    "users".to_string(),
+    vec![
+      Column::with_constraints(ColumnId::new(1), "id".to_string(), DataType::Integer, pk),
+      Column::new(ColumnId::new(2), "name".to_string(), DataType::Text),
+    ],
+    Some(ColumnId::new(1)),
+    PageId::new(2),
+  );
+  assert_eq!(valid.validate(), Ok(()));
+
+  let mut t = valid.clone();
+  t.name.clear();
+  assert_eq!(t.validate(), Err(DomainError::EmptyTableName));
+
+  let mut t = valid.clone();
+  t.columns.clear();
+  t.primary_key = None;
+  assert_eq!(t.validate(), Err(DomainError::TableMusthHaveColumns));
+
+  let mut t = valid.clone();
+  t.columns[1].name = "id".to_string();
+  assert_eq!(
+    t.validate(),
+    Err(DomainError::DuplicateColumnName { name: "id".to_string() })
+  );
+
+  let mut t = valid.clone();
+  t.primary_key = Some(ColumnId::new(9));
+  assert_eq!(
+    t.validate(),
+    Err(DomainError::InvalidPrimaryKeyReference { column_id: ColumnId::new(9) })
+  );
+
+  // 列约束声明了两个主键
+  let mut t = valid.clone();
+  t.primary_key = None;
+  t.columns[1].constraints.primary_key = true;
+  assert_eq!(t.validate(), Err(DomainError::MultiplePrimaryKeys));
+
+  // 表的主键与列约束中的主键不一致
+  let mut t = valid.clone();
+  t.primary_key = Some(ColumnId::new(2));
+  assert_eq!(t.validate(), Err(DomainError::MultiplePrimaryKeys));
+
+  // AUTOINCREMENT 用在非 INTEGER 主键上
+  let mut t = valid.clone();
+  t.columns[0].data_type = DataType::Text;
+  assert_eq!(
+    t.validate(),
+    Err(DomainError::InvalidAutoincrement { name: "id".to_string() })
+  );
+
+  // AUTOINCREMENT 用在非主键列上
+  let mut t = valid.clone();
+  t.columns[1].constraints.autoincrement = true;
+  assert_eq!(
+    t.validate(),
+    Err(DomainError::InvalidAutoincrement { name: "name".to_string() })
+  );
+}
+
+#[test]
+fn test_table_from_specs() {
+  let pk = ColumnConstraints { primary_key: true, ..Default::default() };
+  let not_null = ColumnConstraints { not_null: true, ..Default::default() };
+  let table = Table::from_specs(
+    TableId::new(5),
+    "users".to_string(),
+    &[("name", DataType::Text, not_null), ("id", DataType::Integer, pk)],
+    PageId::new(3),
+  );
+
+  assert_eq!(table.id, TableId::new(5));
+  assert_eq!(table.root_page, PageId::new(3));
+  let ids: Vec<_> = table
+    .columns
+    .iter()
+    .map(|c| (c.id, c.name.as_str()))
+    .collect();
+  assert_eq!(ids, [(ColumnId::new(1), "name"), (ColumnId::new(2), "id")]);
+  assert!(table.columns[0].constraints.not_null);
+  assert_eq!(table.primary_key, Some(ColumnId::new(2)));
+  assert_eq!(
+    table.primary_key_column().map(|c| c.name.as_str()),
+    Some("id")
+  );
+  assert_eq!(table.validate(), Ok(()));
+
+  let no_pk = Table::from_specs(
+    TableId::new(6),
+    "t".to_string(),
+    &[("a", DataType::Blob, ColumnConstraints::default())],
+    PageId::new(4),
+  );
+  assert_eq!(no_pk.primary_key, None);
+}
+
+#[test]
+fn test_table_foreign_keys() {
+  let columns = vec![
+    Column::new(ColumnId::new(1), "id".to_string(), DataType::Integer),
+    Column::new(ColumnId::new(2), "user_id".to_string(), DataType::Integer),
+    Column::new(ColumnId::new(3), "parent_id".to_string(), DataType::Integer),
+  ];
+  let to_users = ForeignKey {
+    columns: vec![ColumnId::new(2)],
+    ref_table: TableId::new(1),
+    ref_columns: vec![ColumnId::new(1)],
+    on_delete: ForeignKeyAction::Cascade,
+  };
+  // 自引用：parent_id -> orders.id
+  let to_self = ForeignKey {
+    columns: vec![ColumnId::new(3)],
+    ref_table: TableId::new(2),
+    ref_columns: vec![ColumnId::new(1)],
+    on_delete: ForeignKeyAction::default(),
+  };
+  let orders = Table::new(
+    TableId::new(2),
+    "orders".to_string(),
+    columns,
+    None,
+    PageId::new(3),
+  )
+  .with_foreign_keys(vec![to_users.clone(), to_self.clone()]);
+
+  assert_eq!(orders.validate(), Ok(()));
+  assert_eq!(orders.foreign_keys(), &[to_users.clone(), to_self.clone()]);
+  assert_eq!(
+    orders.foreign_keys()[1].on_delete,
+    ForeignKeyAction::NoAction
+  );
+  assert!(Table::new(
+    TableId::new(3),
+    "t".to_string(),
+    vec![],
+    None,
+    PageId::new(4)
+  )
+  .foreign_keys()
+  .is_empty());
+
+  // 引用列不在本表
+  let mut t = orders.clone();
+  t.foreign_keys[0].columns = vec![ColumnId::new(9)];
+  assert_eq!(
+    t.validate(),
+    Err(DomainError::InvalidForeignKeyReference { column_id: ColumnId::new(9) })
+  );
+
+  // 自引用外键的被引用列不在本表；引用其他表时不检查被引用列
+  let mut t = orders.clone();
+  t.foreign_keys[1].ref_columns = vec![ColumnId::new(8)];
+  assert_eq!(
+    t.validate(),
+    Err(DomainError::InvalidForeignKeyReference { column_id: ColumnId::new(8) })
+  );
+  let mut t = orders.clone();
+  t.foreign_keys[0].ref_columns = vec![ColumnId::new(8)];
+  assert_eq!(t.validate(), Ok(()));
+
+  // 列数不一致
+  let mut t = orders.clone();
+  t.foreign_keys[0].ref_columns.push(ColumnId::new(2));
+  assert_eq!(
+    t.validate(),
+    Err(DomainError::ForeignKeyColumnCountMismatch { columns: 1, ref_columns: 2 })
+  );
+}
+
+#[test]
+fn test_table_composite_unique() {
+  let table = Table::from_specs(
+    TableId::new(1),
+    "members".to_string(),
+    &[
+      ("id", DataType::Integer, ColumnConstraints::default()),
+      ("team", DataType::Text, ColumnConstraints::default()),
+      ("seat", DataType::Integer, ColumnConstraints::default()),
+    ],
+    PageId::new(2),
+  )
+  .with_unique_constraints(vec![vec![ColumnId::new(2), ColumnId::new(3)]]);
+  assert_eq!(table.validate(), Ok(()));
+  assert_eq!(
+    table.to_create_sql(),
+    "CREATE TABLE members (id INTEGER, team TEXT, seat INTEGER, UNIQUE (team, seat))"
+  );
+
+  let row = |id: i64, team: Option<&'static str>, seat: Value<'static>| {
+    let team = team.map_or(Value::Null, |t| Value::Text(Cow::Borrowed(t)));
+    Row::new(RowId::new(id), vec![Value::Integer(id), team, seat])
+  };
+  let existing =
+    vec![row(1, Some("red"), Value::Integer(1)), row(2, Some("red"), Value::Integer(2))];
+
+  // 两列都相同（数值跨类型比较）
+  assert_eq!(
+    table.check_composite_unique(&row(3, Some("red"), Value::Real(2.0)), &existing),
+    Err(DomainError::UniqueViolation { columns: vec!["team".to_string(), "seat".to_string()] })
+  );
+
+  // 只有一列相同
+  assert_eq!(
+    table.check_composite_unique(&row(3, Some("red"), Value::Integer(3)), &existing),
+    Ok(())
+  );
+  assert_eq!(
+    table.check_composite_unique(&row(3, Some("blue"), Value::Integer(1)), &existing),
+    Ok(())
+  );
+
+  // 含 NULL 的元组不冲突；与自身（相同 rowid）不冲突
+  let with_null = vec![row(4, None, Value::Integer(1))];
+  assert_eq!(
+    table.check_composite_unique(&row(5, None, Value::Integer(1)), &with_null),
+    Ok(())
+  );
+  assert_eq!(
+    table.check_composite_unique(&existing[0], &existing),
+    Ok(())
+  );
+
+  // 约束列不在本表 / 空约束
+  let mut t = table.clone();
+  t.unique_constraints = vec![vec![ColumnId::new(9)]];
+  assert_eq!(
+    t.validate(),
+    Err(DomainError::ColumnIdNotFound { column_id: ColumnId::new(9) })
+  );
+  assert_eq!(
+    t.check_composite_unique(&existing[0], &existing),
+    Err(DomainError::ColumnIdNotFound { column_id: ColumnId::new(9) })
+  );
+  t.unique_constraints = vec![vec![]];
+  assert_eq!(t.validate(), Err(DomainError::EmptyUniqueConstraint));
+}
+
+#[test]
+fn test_database_add_table_validates_table() {
+  let mut db = Database::new(Path::new("/tmp/test.db"));
+  let columns = vec![
+    Column::new(ColumnId::new(1), "a".to_string(), DataType::Integer),
+    Column::new(ColumnId::new(2), "a".to_string(), DataType::Text),
+  ];
+  let table = Table::new(
+    TableId::new(1),
+    "t".to_string(),
+    columns,
+    None,
+    PageId::new(2),
+  );
+
+  assert_eq!(
+    db.add_table(table),
+    Err(DomainError::DuplicateColumnName { name: "a".to_string() })
+  );
+  assert!(db.tables.is_empty());
+}
+
+#[test]
+fn test_database_dry_run_schema_checks() {
+  let mut db = Database::new(Path::new("/tmp/test.db"));
+  let users = || {
+    let columns = vec![Column::new(ColumnId::new(1), "id".to_string(), DataType::Integer)];
+    Table::new(
+      TableId::new(1),
+      "users".to_string(),
+      columns,
+      None,
+      PageId::new(2),
+    )
+  };
+  assert_eq!(db.can_add_table(&users()), Ok(()));
+  assert!(db.tables.is_empty());
+  assert_eq!(db.schema_version(), 0);
+
+  db.add_table(users()).unwrap();
+  let version = db.schema_version();
+
+  // 同名表：预检报告与 add_table 相同的错误，且不插入任何东西
+  let mut dup = users();
+  dup.id = TableId::new(2);
+  let expected = Err(DomainError::TableAlreadyExists { name: "users".to_string() });
+  assert_eq!(db.can_add_table(&dup), expected);
+  assert_eq!(db.tables.len(), 1);
+  assert_eq!(db.schema_version(), version);
+  assert_eq!(db.add_table(dup).map(|_| ()), expected);
+
+  // 删除预检不删除表
+  assert_eq!(db.can_drop_table(TableId::new(1)), Ok(()));
+  assert!(db.get_table(TableId::new(1)).is_some());
+  assert_eq!(db.schema_version(), version);
+  assert_eq!(
+    db.can_drop_table(TableId::new(9)),
+    Err(DomainError::TableNotFound { table_id: TableId::new(9) })
+  );
+}
+
+// ===============================================
+// Row<'r> 测试
+// ===============================================
+
+#[test]
+fn test_row_new() {
+  let values = vec![Value::Integer(1), Value::Text(Cow::Borrowed("Alice"))];
+  let row = Row::new(RowId::new(1), values);
+  assert_eq!(row.row_id, RowId::new(1));
+  assert_eq!(row.values.len(), 2);
+}
+
+#[test]
+fn test_row_get() {
+  let values = vec![Value::Integer(1), Value::Text(Cow::Borrowed("Alice"))];
+  let row = Row::new(RowId::new(1), values);
+
+  assert_eq!(row.get(0), Some(&Value::Integer(1)));
+  assert_eq!(row.get(1), Some(&Value::Text(Cow::Borrowed("Alice"))));
+  assert_eq!(row.get(2), None);
+}
+
+#[test]
+fn test_row_replace() {
+  let name = String::from("Alice");
+  let mut row = Row::new(
+    RowId::new(1),
+    vec![Value::Integer(1), Value::Text(Cow::Borrowed(&name))],
+  );
+
+  let old = row.replace(1, Value::Text(Cow::Borrowed("Bob")));
+  assert_eq!(old, Ok(Value::Text(Cow::Borrowed("Alice"))));
+  assert_eq!(row.get(1), Some(&Value::Text(Cow::Borrowed("Bob"))));
+  assert_eq!(row.get(0), Some(&Value::Integer(1)));
+
+  assert_eq!(
+    row.replace(2, Value::Null),
+    Err(DomainError::ColumnIndexOutOfRange { index: 2, len: 2 })
+  );
+  assert_eq!(row.values.len(), 2);
+}
+
+#[test]
+fn test_row_with_row_id() {
+  let text = String::from("Alice");
+  let row = Row::new(
+    RowId::new(1),
+    vec![Value::Integer(7), Value::Text(Cow::Borrowed(&text))],
+  );
+
+  let owned = row.clone_with_row_id(RowId::new(3));
+  assert_eq!(owned.row_id, RowId::new(3));
+  assert_eq!(owned.values, row.values);
+  assert!(matches!(owned.values[1], Value::Text(Cow::Owned(_))));
+  assert_eq!(row.row_id, RowId::new(1));
+
+  let moved = row.with_row_id(RowId::new(2));
+  assert_eq!(moved.row_id, RowId::new(2));
+  assert_eq!(
+    moved.values,
+    vec![Value::Integer(7), Value::Text(Cow::Borrowed("Alice"))]
+  );
+}
+
+#[test]
+fn test_row_get_by_name() {
+  let columns = vec![
+    Column::new(ColumnId::new(1), "id".to_string(), DataType::Integer),
+    Column::new(ColumnId::new(2), "name".to_string(), DataType::Text),
+  ];
+  let table = Table::new(
+    TableId::new(1),
+    "users".to_string(),
+    columns,
+    None,
+    PageId::new(1),
+  );
+
+  let values = vec![Value::Integer(1), Value::Text(Cow::Borrowed("Alice"))];
+  let row = Row::new(RowId::new(1), values);
+
+  assert_eq!(row.get_by_name("id", &table), Some(&Value::Integer(1)));
+  assert_eq!(
+    row.get_by_name("name", &table),
+    Some(&Value::Text(Cow::Borrowed("Alice")))
+  );
+  assert_eq!(row.get_by_name("nonexistent", &table), None);
+}
+
+#[test]
+fn test_row_into_owned() {
+  let s = "hello";
+  let values = vec![Value::Text(Cow::Borrowed(s))];
+  let row = Row::new(RowId::new(1), values);
+  let owned: Row<'static> = row.into_owned();
+  assert_eq!(owned.get(0).unwrap().as_text(), Some("hello"));
+}
+
+#[test]
+fn test_row_sql_eq() {
+  let text = |s: &'static str| Value::Text(Cow::Borrowed(s));
+
+  // NULL 位置相同的两行相等，rowid 不参与比较
+  let a = Row::new(RowId::new(1), vec![Value::Null, text("x"), Value::Null]);
+  let b = Row::new(RowId::new(2), vec![Value::Null, text("x"), Value::Null]);
+  assert!(a.sql_eq(&b));
+
+  // NULL 与非 NULL 不相等
+  let c = Row::new(
+    RowId::new(3),
+    vec![Value::Integer(0), text("x"), Value::Null],
+  );
+  assert!(!a.sql_eq(&c));
+
+  // 跨数值类型按数值比较
+  let int_row = Row::new(RowId::new(1), vec![Value::Integer(3)]);
+  assert!(int_row.sql_eq(&Row::new(RowId::new(1), vec![Value::Real(3.0)])));
+  assert!(!int_row.sql_eq(&Row::new(RowId::new(1), vec![Value::Real(3.5)])));
+
+  // 值数量不同
+  assert!(!int_row.sql_eq(&Row::new(
+    RowId::new(1),
+    vec![Value::Integer(3), Value::Null]
+  )));
+}
+
+fn users_table_for_builder() -> Table {
+  let id = Column::with_constraints(
+    ColumnId::new(1),
+    "id".to_string(),
+    DataType::Integer,
+    ColumnConstraints { not_null: true, primary_key: true, ..Default::default() },
+  );
+  let name = Column::new(ColumnId::new(2), "name".to_string(), DataType::Text);
+  let mut age = Column::new(ColumnId::new(3), "age".to_string(), DataType::Integer);
+  age.default_value = Some(Value::Integer(18));
+
+  Table::new(
+    TableId::new(1),
+    "users".to_string(),
+    vec![id, name, age],
+    None,
+    PageId::new(1),
+  )
+}
+
+#[test]
+fn test_row_builder_by_name() {
+  let table = users_table_for_builder();
+
+  let row = RowBuilder::new(&table, RowId::new(7))
+    .set("age", Value::Integer(30))
+    .set("name", Value::Text(Cow::Borrowed("Alice")))
+    .set("id", Value::Integer(1))
+    .build()
+    .unwrap();
+
+  assert_eq!(row.row_id, RowId::new(7));
+  assert_eq!(
+    row.values,
+    vec![Value::Integer(1), Value::Text(Cow::Borrowed("Alice")), Value::Integer(30)]
+  );
+}
+
+#[test]
+fn test_row_builder_uses_default_and_null() {
+  let table = users_table_for_builder();
+
+  let row = RowBuilder::new(&table, RowId::new(1))
+    .set("id", Value::Integer(1))
+    .build()
+    .unwrap();
+
+  assert_eq!(
+    row.values,
+    vec![Value::Integer(1), Value::Null, Value::Integer(18)]
+  );
+}
+
+#[test]
+fn test_row_builder_errors() {
+  let table = users_table_for_builder();
+
+  let unknown = RowBuilder::new(&table, RowId::new(1))
+    .set("id", Value::Integer(1))
+    .set("email", Value::Text(Cow::Borrowed("a@b.c")))
+    .build();
+  assert_eq!(
+    unknown.unwrap_err(),
+    DomainError::ColumnNotFound { name: "email".to_string() }
+  );
+
+  let missing_not_null = RowBuilder::new(&table, RowId::new(1)).build();
+  assert_eq!(
+    missing_not_null.unwrap_err(),
+    DomainError::NotNullViolation { name: "id".to_string() }
+  );
+}
+
+#[test]
+fn test_row_comparator_multi_column() {
+  let row = |id: i64, a: Value<'static>, b: Value<'static>| Row::new(RowId::new(id), vec![a, b]);
+  let mut rows = [
+    row(1, Value::Integer(2), Value::Text(Cow::Borrowed("x"))),
+    row(2, Value::Null, Value::Text(Cow::Borrowed("y"))),
+    row(3, Value::Integer(1), Value::Text(Cow::Borrowed("a"))),
+    row(4, Value::Real(1.0), Value::Text(Cow::Borrowed("b"))),
+    row(5, Value::Integer(2), Value::Null),
+    row(6, Value::Integer(2), Value::Text(Cow::Borrowed("z"))),
+  ];
+
+  // 第 0 列升序（NULL 在前），第 1 列降序（NULL 在后）
+  let cmp = RowComparator::new([
+    (0, SortDirection::Ascending, NullsOrder::First),
+    (1, SortDirection::Descending, NullsOrder::Last),
+  ]);
+  rows.sort_by(|a, b| cmp.compare(a, b));
+
+  let ids: Vec<i64> = rows.iter().map(|r| r.row_id.into_inner()).collect();
+  // Integer(1) 与 Real(1.0) 按数值相等，再按第 1 列降序："b" > "a"
+  assert_eq!(ids, vec![2, 4, 3, 6, 1, 5]);
+}
+
+#[test]
+fn test_row_comparator_orders_nan_and_large_integers_exactly() {
+  let row = |id: i64, v: Value<'static>| Row::new(RowId::new(id), vec![v]);
+  // 2^53 + 1 无法用 f64 表示，`as f64` 后会与 2^53 相等
+  let big = (1_i64 << 53) + 1;
+  let mut rows = [
+    row(1, Value::Integer(big)),
+    row(2, Value::Real((1_i64 << 53) as f64)),
+    row(3, Value::Real(f64::NAN)),
+    row(4, Value::Integer(i64::MIN)),
+    row(5, Value::Real(f64::NEG_INFINITY)),
+    row(6, Value::Real(f64::NAN)),
+    row(7, Value::Real(9_223_372_036_854_775_808.0)),
+    row(8, Value::Integer(i64::MAX)),
+    row(9, Value::Real(-0.5)),
+    row(10, Value::Integer(0)),
+  ];
+
+  let cmp = RowComparator::new([(0, SortDirection::Ascending, NullsOrder::First)]);
+  rows.sort_by(|a, b| cmp.compare(a, b));
+
+  let ids: Vec<i64> = rows.iter().map(|r| r.row_id.into_inner()).collect();
+  // NaN 最小（稳定排序保持 3、6 的顺序）；2^63 大于 i64::MAX
+  assert_eq!(ids, vec![3, 6, 5, 4, 9, 10, 2, 1, 8, 7]);
+
+  let a = row(1, Value::Integer(big));
+  let b = row(2, Value::Real((1_i64 << 53) as f64));
+  assert_eq!(cmp.compare(&a, &b), Ordering::Greater);
+  assert_eq!(cmp.compare(&b, &a), Ordering::Less);
+  assert_eq!(
+    cmp.compare(&row(1, Value::Integer(3)), &row(2, Value::Real(3.0))),
+    Ordering::Equal
+  );
+}
+
+// ===============================================
+// Database 测试
+// ===============================================
+
+#[test]
+fn test_database_new() {
+  let db = Database::new(Path::new("/tmp/test.db"));
+  assert_eq!(db.path, Path::new("/tmp/test.db"));
+  assert_eq!(db.tables.len(), 0);
+  assert_eq!(db.indexes.len(), 0);
+  assert_eq!(db.schema_version(), 0);
+}
+
+#[test]
+fn test_database_add_table() {
+  let mut db = Database::new(Path::new("/tmp/test.db"));
+
+  let columns = vec![Column::new(ColumnId::new(1), "id".to_string(), DataType::Integer)];
+  let table = Table::new(
+    TableId::new(1),
+    "users".to_string(),
+    columns,
+    None,
+    PageId::new(1),
+  );
+
+  let table_id = db.add_table(table).unwrap();
+  assert_eq!(table_id, TableId::new(1));
+  assert_eq!(db.tables.len(), 1);
+  assert_eq!(db.schema_version(), 1);
+}
+
+#[test]
+fn test_database_add_table_duplicate_name() {
+  let mut db = Database::new(Path::new("/tmp/test.db"));
+
+  let columns = vec![Column::new(ColumnId::new(1), "id".to_string(), DataType::Integer)];
+  let table1 = Table::new(
+    TableId::new(1),
+    "users".to_string(),
+    columns.clone(),
+    None,
+    PageId::new(1),
   );
   let table2 = Table::new(
     TableId::new(2),
@@ -553,7 +1752,7 @@ fn test_database_drop_table() {
 
   db.drop_table(TableId::new(1)).unwrap();
   assert_eq!(db.tables.len(), 0);
-  assert_eq!(db.schema_version, 2);
+  assert_eq!(db.schema_version(), 2);
 }
 
 #[test]
@@ -630,7 +1829,193 @@ fn test_database_add_index() {
   let index_id = db.add_index(IndexId::new(1), TableId::new(1)).unwrap();
   assert_eq!(index_id, IndexId::new(1));
   assert_eq!(db.indexes.len(), 1);
-  assert_eq!(db.schema_version, 2);
+  assert_eq!(db.schema_version(), 2);
+}
+
+#[test]
+fn test_database_to_schema_sql_is_ordered_by_table_id() {
+  let mut db = Database::new(Path::new("/tmp/test.db"));
+
+  for (id, name) in [(3, "c"), (1, "a"), (2, "b")] {
+    let columns = vec![Column::new(ColumnId::new(1), "id".to_string(), DataType::Integer)];
+    let table = Table::new(
+      TableId::new(id),
+      name.to_string(),
+      columns,
+      None,
+      PageId::new(id),
+    );
+    db.add_table(table).unwrap();
+  }
+
+  assert_eq!(
+    db.to_schema_sql(),
+    "CREATE TABLE a (id INTEGER);\nCREATE TABLE b (id INTEGER);\nCREATE TABLE c (id INTEGER);\n"
+  );
+}
+
+#[test]
+fn test_database_validate_invariants() {
+  let table = |id: u32, name: &str| {
+    let columns = vec![Column::new(ColumnId::new(1), "id".to_string(), DataType::Integer)];
+    Table::new(
+      TableId::new(id),
+      name.to_string(),
+      columns,
+      None,
+      PageId::new(id + 1),
+    )
+  };
+
+  let fresh = || {
+    let mut db = Database::new(Path::new("/tmp/test.db"));
+    db.add_table(table(1, "users")).unwrap();
+    db.add_table(table(2, "orders")).unwrap();
+    db
+  };
+  assert_eq!(fresh().validate_invariants(), Ok(()));
+
+  // 绕过 add_table 插入同名表
+  let mut dup = fresh();
+  dup.tables.insert(TableId::new(3), table(3, "users"));
+  dup.schema_version.increment();
+  assert!(matches!(
+    dup.validate_invariants(),
+    Err(DomainError::InvariantViolation { message }) if message.contains("duplicate table name 'users'")
+  ));
+  // 同名时固定返回 ID 最小的表
+  assert_eq!(
+    dup.get_table_by_name("users").map(|t| t.id),
+    Some(TableId::new(1))
+  );
+
+  // key 与表 ID 不一致
+  let mut misplaced = fresh();
+  misplaced.tables.insert(TableId::new(9), table(4, "items"));
+  misplaced.schema_version.increment();
+  assert!(matches!(
+    misplaced.validate_invariants(),
+    Err(DomainError::InvariantViolation { .. })
+  ));
+
+  // schema_version 落后于 schema 对象数量
+  let stale = fresh();
+  stale.schema_version.set(1);
+  assert!(matches!(
+    stale.validate_invariants(),
+    Err(DomainError::InvariantViolation { message }) if message.contains("schema version 1")
+  ));
+
+  // 索引引用了不存在的表
+  let mut dangling = fresh();
+  dangling
+    .add_index(IndexId::new(1), TableId::new(2))
+    .unwrap();
+  assert_eq!(dangling.validate_invariants(), Ok(()));
+  dangling.indexes.insert(IndexId::new(2), TableId::new(7));
+  dangling.schema_version.increment();
+  assert!(matches!(
+    dangling.validate_invariants(),
+    Err(DomainError::InvariantViolation { message }) if message.contains("references missing table")
+  ));
+
+  // 删除表时级联删除其索引，不会留下悬空引用
+  let mut cascade = fresh();
+  cascade.add_index(IndexId::new(1), TableId::new(2)).unwrap();
+  cascade.add_index(IndexId::new(2), TableId::new(1)).unwrap();
+  cascade.drop_table(TableId::new(2)).unwrap();
+  assert_eq!(cascade.indexes.len(), 1);
+  assert_eq!(cascade.validate_invariants(), Ok(()));
+}
+
+#[test]
+fn test_database_master_rows() {
+  let mut db = Database::new(Path::new("/tmp/test.db"));
+  for (id, name, root) in [(2, "orders", 7), (1, "users", 3)] {
+    let columns = vec![Column::new(ColumnId::new(1), "id".to_string(), DataType::Integer)];
+    let table = Table::new(
+      TableId::new(id),
+      name.to_string(),
+      columns,
+      None,
+      PageId::new(root),
+    );
+    db.add_table(table).unwrap();
+  }
+
+  let master = Table::master(PageId::new(2));
+  assert_eq!(master.name, MASTER_TABLE_NAME);
+  let names: Vec<&str> = master.columns.iter().map(|c| c.name.as_str()).collect();
+  assert_eq!(names, ["type", "name", "tbl_name", "rootpage", "sql"]);
+
+  let rows = db.master_rows();
+  assert_eq!(rows.len(), 2);
+  for (row, (id, name, root)) in rows.iter().zip([(1, "users", 3), (2, "orders", 7)]) {
+    assert_eq!(row.row_id, RowId::new(id));
+    assert!(master.validate_row(row).is_ok());
+    assert_eq!(
+      row.get_by_name("type", &master),
+      Some(&Value::Text(Cow::Borrowed("table")))
+    );
+    assert_eq!(
+      row.get_by_name("name", &master),
+      Some(&Value::Text(Cow::Borrowed(name)))
+    );
+    assert_eq!(
+      row.get_by_name("tbl_name", &master),
+      Some(&Value::Text(Cow::Borrowed(name)))
+    );
+    assert_eq!(
+      row.get_by_name("rootpage", &master),
+      Some(&Value::Integer(root))
+    );
+    assert_eq!(
+      row.get_by_name("sql", &master),
+      Some(&Value::Text(Cow::Owned(format!(
+        "CREATE TABLE {name} (id INTEGER)"
+      ))))
+    );
+  }
+}
+
+#[test]
+fn test_database_cannot_drop_system_tables() {
+  let mut db = Database::new(Path::new("/tmp/test.db"));
+  let columns = || vec![Column::new(ColumnId::new(1), "id".to_string(), DataType::Integer)];
+  db.add_table(Table::new(
+    TableId::new(1),
+    "users".to_string(),
+    columns(),
+    None,
+    PageId::new(3),
+  ))
+  .unwrap();
+  db.add_table(Table::new(
+    TableId::new(2),
+    "rdb_stats".to_string(),
+    columns(),
+    None,
+    PageId::new(4),
+  ))
+  .unwrap();
+
+  assert_eq!(db.drop_table_by_name("users"), Ok(()));
+  assert!(db.get_table_by_name("users").is_none());
+
+  assert_eq!(
+    db.drop_table_by_name(MASTER_TABLE_NAME),
+    Err(DomainError::CannotDropSystemTable { name: "rdb_master".to_string() })
+  );
+  assert_eq!(
+    db.drop_table(TableId::new(2)),
+    Err(DomainError::CannotDropSystemTable { name: "rdb_stats".to_string() })
+  );
+  assert!(db.get_table(TableId::new(2)).is_some());
+
+  assert_eq!(
+    db.drop_table_by_name("missing"),
+    Err(DomainError::TableNameNotFound { name: "missing".to_string() })
+  );
 }
 
 // ===============================================
@@ -664,3 +2049,228 @@ fn test_domain_error_type_mismatch() {
   };
   assert!(error.to_string().contains("type does not match"));
 }
+
+#[test]
+fn test_value_intern_shares_storage() {
+  let interner = StringInterner::new();
+
+  let a = Value::Text(Cow::Owned("active".to_string())).intern(&interner);
+  let b = Value::Text(Cow::Owned("active".to_string())).intern(&interner);
+  let c = Value::Text(Cow::Borrowed("inactive")).intern(&interner);
+
+  match (&a, &b) {
+    (Value::SharedText(x), Value::SharedText(y)) => assert!(Arc::ptr_eq(x, y)),
+    other => panic!("expected interned text, got {other:?}"),
+  }
+  assert_eq!(a, b);
+  assert_ne!(a, c);
+  assert_eq!(interner.len(), 2);
+
+  // 与普通 Text 等价
+  let plain = Value::Text(Cow::Borrowed("active"));
+  assert_eq!(a, plain);
+  assert_eq!(a.stable_hash(0), plain.stable_hash(0));
+  assert_eq!(a.sql_compare(&plain), Some(Ordering::Equal));
+  assert_eq!(a.data_type(), DataType::Text);
+
+  // 非文本值原样返回
+  assert_eq!(Value::Integer(7).intern(&interner), Value::Integer(7));
+  assert_eq!(interner.len(), 2);
+
+  // 结果是 'static：可以放进 Row<'static>，并比驻留器活得久
+  let row: Row<'static> = Row::new(RowId::new(1), vec![a, c]);
+  drop(interner);
+  assert_eq!(row.get(0).and_then(Value::as_text), Some("active"));
+  assert_eq!(row.get(1).and_then(Value::as_text), Some("inactive"));
+}
+
+#[test]
+fn test_row_compare_key() {
+  let text = |s: &'static str| Value::Text(Cow::Borrowed(s));
+  // 索引列为 (name, age) = (1, 2)
+  let row = Row::new(
+    RowId::new(1),
+    vec![Value::Integer(7), text("bob"), Value::Integer(30)],
+  );
+  let indices = [1, 2];
+
+  assert_eq!(
+    row.compare_key(&indices, &[text("bob"), Value::Integer(25)]),
+    Some(Ordering::Greater)
+  );
+  assert_eq!(
+    row.compare_key(&indices, &[text("bob"), Value::Integer(30)]),
+    Some(Ordering::Equal)
+  );
+  assert_eq!(
+    row.compare_key(&indices, &[text("carol"), Value::Integer(1)]),
+    Some(Ordering::Less)
+  );
+
+  // 前缀 seek
+  assert_eq!(
+    row.compare_key(&indices, &[text("bob")]),
+    Some(Ordering::Equal)
+  );
+
+  // NULL 或越界时不确定
+  assert_eq!(row.compare_key(&indices, &[text("bob"), Value::Null]), None);
+  assert_eq!(row.compare_key(&[5], &[Value::Integer(1)]), None);
+
+  // 第一列已决定结果时不再比较后面的 NULL
+  assert_eq!(
+    row.compare_key(&indices, &[text("alice"), Value::Null]),
+    Some(Ordering::Greater)
+  );
+}
+
+#[test]
+fn test_data_type_infer_from_values() {
+  let ints = [Value::Integer(1), Value::Null, Value::Integer(-3)];
+  assert_eq!(DataType::infer_from_values(&ints), DataType::Integer);
+
+  let mixed = [Value::Integer(1), Value::Real(2.5), Value::Integer(3)];
+  assert_eq!(DataType::infer_from_values(&mixed), DataType::Real);
+
+  assert_eq!(
+    DataType::infer_from_values(&[Value::Null, Value::Null]),
+    DataType::Text
+  );
+  assert_eq!(DataType::infer_from_values(&[]), DataType::Text);
+
+  let text = [Value::Real(1.0), Value::Text(Cow::Borrowed("x"))];
+  assert_eq!(DataType::infer_from_values(&text), DataType::Text);
+
+  let blob = [Value::Text(Cow::Borrowed("x")), Value::Blob(Cow::Borrowed(&[1]))];
+  assert_eq!(DataType::infer_from_values(&blob), DataType::Blob);
+}
+
+#[test]
+fn test_column_max_length() {
+  let limited = |data_type| {
+    Column::with_constraints(
+      ColumnId::new(1),
+      "c".to_string(),
+      data_type,
+      ColumnConstraints { max_length: Some(3), ..Default::default() },
+    )
+  };
+  let text = limited(DataType::Text);
+
+  // TEXT 按字符计数
+  assert!(text
+    .validate_value(&Value::Text(Cow::Borrowed("héé")))
+    .is_ok());
+  assert_eq!(
+    text.validate_value(&Value::Text(Cow::Borrowed("abcd"))),
+    Err(DomainError::LengthExceeded { name: "c".to_string(), max: 3, got: 4 })
+  );
+  assert!(text.validate_value(&Value::Null).is_ok());
+
+  // 数字转成文本后按转换结果检查
+  assert!(text.validate_value_coercing(&Value::Integer(123)).is_ok());
+  assert!(matches!(
+    text.validate_value_coercing(&Value::Integer(1234)),
+    Err(DomainError::LengthExceeded { got: 4, .. })
+  ));
+
+  let blob = limited(DataType::Blob);
+  assert!(matches!(
+    blob.validate_value(&Value::Blob(Cow::Borrowed(&[0; 4]))),
+    Err(DomainError::LengthExceeded { got: 4, .. })
+  ));
+
+  // 数字不受长度限制
+  assert!(limited(DataType::Integer)
+    .validate_value(&Value::Integer(123_456))
+    .is_ok());
+
+  // 不限制
+  let unlimited = Column::new(ColumnId::new(2), "u".to_string(), DataType::Text);
+  let long = "x".repeat(10_000);
+  assert!(unlimited
+    .validate_value(&Value::Text(Cow::Owned(long)))
+    .is_ok());
+}
+
+#[test]
+fn test_table_estimated_row_size() {
+  let table = |types: &[DataType]| {
+    let columns = types
+      .iter()
+      .enumerate()
+      .map(|(i, &t)| Column::new(ColumnId::new(i as u32 + 1), format!("c{i}"), t))
+      .collect();
+    Table::new(
+      TableId::new(1),
+      "t".to_string(),
+      columns,
+      None,
+      PageId::new(1),
+    )
+  };
+
+  // 全部定长：头 1 + 3 个 serial type + 3 * 8
+  let fixed = table(&[DataType::Integer, DataType::Real, DataType::Integer]);
+  assert_eq!(fixed.estimated_row_size(100), 1 + 3 + 24);
+
+  // 混合：TEXT 平均 100 字节，serial type 213 需要 2 字节 varint
+  let mixed = table(&[DataType::Integer, DataType::Text, DataType::Blob]);
+  assert_eq!(
+    mixed.estimated_row_size(100),
+    (1 + 1 + 2 + 2) + (8 + 100 + 100)
+  );
+
+  // 短文本的 serial type 只占 1 字节
+  assert_eq!(
+    mixed.estimated_row_size(10),
+    (1 + 1 + 1 + 1) + (8 + 10 + 10)
+  );
+}
+
+#[test]
+fn test_schema_version_lock_free_reads() {
+  use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+  use std::sync::{Arc, Mutex};
+
+  const TABLES: u32 = 500;
+
+  let db = Arc::new(Mutex::new(Database::new(Path::new("/tmp/test.db"))));
+  let version = db.lock().unwrap().schema_version.clone();
+  let done = Arc::new(AtomicBool::new(false));
+
+  // 读者只持有版本句柄，不锁 Database；看到的版本必须单调不减且在合法范围内
+  let readers: Vec<_> = (0..4)
+    .map(|_| {
+      let version = version.clone();
+      let done = Arc::clone(&done);
+      std::thread::spawn(move || {
+        let mut last = 0;
+        while !done.load(AtomicOrdering::Acquire) {
+          let v = version.get();
+          assert!(v >= last && v <= TABLES, "observed {v} after {last}");
+          last = v;
+        }
+        last
+      })
+    })
+    .collect();
+
+  for id in 1..=TABLES {
+    let table = Table::new(
+      TableId::new(id),
+      format!("t{id}"),
+      vec![Column::new(ColumnId::new(1), "id".to_string(), DataType::Integer)],
+      None,
+      PageId::new(id + 1),
+    );
+    db.lock().unwrap().add_table(table).unwrap();
+  }
+  done.store(true, AtomicOrdering::Release);
+
+  for reader in readers {
+    assert!(reader.join().unwrap() <= TABLES);
+  }
+  assert_eq!(version.get(), TABLES);
+  assert_eq!(db.lock().unwrap().schema_version(), TABLES);
+}
@@ -35,3 +35,71 @@ proptest! {
     prop_assert_eq!(v, v2);
   }
 }
+
+proptest! {
+  #[test]
+  fn value_tagged_roundtrip(v in arb_value()) {
+    let mut bytes = Vec::new();
+    v.encode_tagged(&mut bytes);
+
+    let (decoded, used) = Value::decode_tagged(&bytes).unwrap();
+    prop_assert_eq!(used, bytes.len());
+    prop_assert_eq!(decoded, v);
+  }
+
+  #[test]
+  fn value_tagged_sequence_roundtrip(values in proptest::collection::vec(arb_value(), 0..16)) {
+    let mut bytes = Vec::new();
+    for v in &values {
+      v.encode_tagged(&mut bytes);
+    }
+
+    let mut rest = &bytes[..];
+    let mut decoded = Vec::new();
+    while !rest.is_empty() {
+      let (v, used) = Value::decode_tagged(rest).unwrap();
+      decoded.push(v);
+      rest = &rest[used..];
+    }
+    prop_assert_eq!(decoded, values);
+  }
+
+  #[test]
+  fn value_tagged_truncated_is_rejected(v in arb_value()) {
+    let mut bytes = Vec::new();
+    v.encode_tagged(&mut bytes);
+    prop_assert!(Value::decode_tagged(&bytes[..bytes.len() - 1]).is_none());
+  }
+}
+
+#[test]
+fn value_tagged_empty_and_invalid() {
+  let mut bytes = Vec::new();
+  Value::Text(Cow::Borrowed("")).encode_tagged(&mut bytes);
+  Value::Blob(Cow::Borrowed(&[])).encode_tagged(&mut bytes);
+  assert_eq!(bytes, [3, 0, 4, 0]);
+  assert_eq!(
+    Value::decode_tagged(&bytes),
+    Some((Value::Text(Cow::Borrowed("")), 2))
+  );
+  assert_eq!(
+    Value::decode_tagged(&bytes[2..]),
+    Some((Value::Blob(Cow::Borrowed(&[])), 2))
+  );
+
+  // 长度跨多个 LEB128 字节
+  let long = Value::Blob(Cow::Owned(vec![7; 300]));
+  let mut bytes = Vec::new();
+  long.encode_tagged(&mut bytes);
+  assert_eq!(&bytes[..3], &[4, 0xAC, 0x02]);
+  assert_eq!(Value::decode_tagged(&bytes), Some((long, 303)));
+
+  // 未知标签、非法 UTF-8、溢出的长度
+  assert_eq!(Value::decode_tagged(&[9]), None);
+  assert_eq!(Value::decode_tagged(&[3, 1, 0xFF]), None);
+  assert_eq!(
+    Value::decode_tagged(&[4, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x7F]),
+    None
+  );
+  assert_eq!(Value::decode_tagged(&[]), None);
+}
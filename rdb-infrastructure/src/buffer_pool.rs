@@ -1,2 +1,30 @@
-#[derive(Debug)]
-pub struct BufferPool;
+/// 缓冲池配置
+///
+/// 目前只描述缓存容量（最多缓存的页数），实际的页缓存由 `rdb_storage::pager::Pager` 持有。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BufferPool {
+  capacity: usize,
+}
+
+impl BufferPool {
+  /// 最多缓存 `capacity` 个页
+  pub fn new(capacity: usize) -> Self {
+    Self { capacity }
+  }
+
+  /// 不限制缓存页数（永不淘汰）
+  pub fn unbounded() -> Self {
+    Self { capacity: usize::MAX }
+  }
+
+  /// 最多缓存的页数
+  pub fn capacity(&self) -> usize {
+    self.capacity
+  }
+}
+
+impl Default for BufferPool {
+  fn default() -> Self {
+    Self::unbounded()
+  }
+}
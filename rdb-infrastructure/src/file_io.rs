@@ -8,6 +8,12 @@ use std::os::unix::fs::FileExt;
 #[cfg(windows)]
 use std::os::windows::fs::FileExt;
 
+#[cfg(unix)]
+use std::os::unix::io::AsRawFd;
+
+#[cfg(windows)]
+use std::os::windows::io::AsRawHandle;
+
 /// 从文件的指定偏移读取，直到把 buf 填满（等价于 pread + read_exact)
 pub fn read_exact_at(file: &File, mut buf: &mut [u8], mut off: u64) -> io::Result<()> {
   while !buf.is_empty() {
@@ -74,3 +80,76 @@ pub fn page_offset(page_id: u32, page_size: usize) -> io::Result<u64> {
   }
   Ok((page_id as u64 - 1) * page_size as u64)
 }
+
+/// 文件锁类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockMode {
+  /// 共享锁：允许多个读者同时持有
+  Shared,
+  /// 排他锁：只允许一个写者持有
+  Exclusive,
+}
+
+/// 尝试对整个文件加咨询锁（非阻塞）
+///
+/// Unix 使用 `flock`，Windows 使用 `LockFileEx`。锁已被其他句柄占用时返回
+/// `ErrorKind::WouldBlock`。锁随文件句柄关闭自动释放。
+#[cfg(unix)]
+pub fn try_lock(file: &File, mode: LockMode) -> io::Result<()> {
+  let op = match mode {
+    LockMode::Shared => libc::LOCK_SH,
+    LockMode::Exclusive => libc::LOCK_EX,
+  };
+  // SAFETY: fd 来自存活的 File，flock 不会访问 Rust 管理的内存
+  if unsafe { libc::flock(file.as_raw_fd(), op | libc::LOCK_NB) } == 0 {
+    return Ok(());
+  }
+
+  let err = io::Error::last_os_error();
+  match err.raw_os_error() {
+    Some(code) if code == libc::EWOULDBLOCK => Err(io::Error::new(ErrorKind::WouldBlock, err)),
+    _ => Err(err),
+  }
+}
+
+/// 尝试对整个文件加咨询锁（非阻塞）
+///
+/// Unix 使用 `flock`，Windows 使用 `LockFileEx`。锁已被其他句柄占用时返回
+/// `ErrorKind::WouldBlock`。锁随文件句柄关闭自动释放。
+#[cfg(windows)]
+pub fn try_lock(file: &File, mode: LockMode) -> io::Result<()> {
+  use windows_sys::Win32::Foundation::{ERROR_LOCK_VIOLATION, HANDLE};
+  use windows_sys::Win32::Storage::FileSystem::{
+    LockFileEx, LOCKFILE_EXCLUSIVE_LOCK, LOCKFILE_FAIL_IMMEDIATELY,
+  };
+  use windows_sys::Win32::System::IO::OVERLAPPED;
+
+  let mut flags = LOCKFILE_FAIL_IMMEDIATELY;
+  if mode == LockMode::Exclusive {
+    flags |= LOCKFILE_EXCLUSIVE_LOCK;
+  }
+
+  // SAFETY: handle 来自存活的 File，OVERLAPPED 全零表示从偏移 0 开始锁定
+  let ok = unsafe {
+    let mut overlapped: OVERLAPPED = std::mem::zeroed();
+    LockFileEx(
+      file.as_raw_handle() as HANDLE,
+      flags,
+      0,
+      u32::MAX,
+      u32::MAX,
+      &mut overlapped,
+    )
+  };
+  if ok != 0 {
+    return Ok(());
+  }
+
+  let err = io::Error::last_os_error();
+  match err.raw_os_error() {
+    Some(code) if code == ERROR_LOCK_VIOLATION as i32 => {
+      Err(io::Error::new(ErrorKind::WouldBlock, err))
+    }
+    _ => Err(err),
+  }
+}
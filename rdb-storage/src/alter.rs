@@ -0,0 +1,60 @@
+//! 表结构变更（ALTER TABLE）的存储层部分
+
+use rdb_domain::{Column, DomainError, Table, Value};
+
+use crate::btree;
+use crate::pager::{Pager, Result};
+use crate::record::encode_row;
+use crate::scan::scan_rows;
+
+/// 为新增列回填存储中的已有行，返回更新的行数
+///
+/// `column` 必须已经追加到 `table.columns` 末尾（schema 部分由调用方完成）。
+/// 缺少该列的行在 record 末尾补上列的默认值（没有默认值时为 NULL）并重写 cell；
+/// 已包含该列的行跳过，因此重复调用是安全的。
+///
+/// 默认值必须满足列约束：NOT NULL 列没有默认值时返回 `NotNullViolation`，
+/// 与 SQLite 拒绝 `ADD COLUMN ... NOT NULL` 不带默认值一致。
+/// 重写可能导致根页分裂，因此会更新 `table.root_page`，调用方需要负责持久化。
+pub fn backfill_column(pager: &mut Pager<'_>, table: &mut Table, column: &Column) -> Result<u64> {
+  let position = table
+    .columns
+    .iter()
+    .position(|c| c.id == column.id)
+    .ok_or(DomainError::ColumnIdNotFound { column_id: column.id })?;
+  if position + 1 != table.columns.len() {
+    return Err(
+      DomainError::InvariantViolation {
+        message: format!(
+          "column '{}' is not the last column of the table",
+          column.name
+        ),
+      }
+      .into(),
+    );
+  }
+
+  let default = column.default_value.clone().unwrap_or(Value::Null);
+  column.validate_value(&default)?;
+
+  let mut stale = Vec::new();
+  scan_rows(pager, table, |row| {
+    match row.values.len() {
+      len if len == position => stale.push(row),
+      len if len > position => {}
+      got => {
+        return Err(DomainError::RowLengthMismatch { expected: position, got }.into());
+      }
+    }
+    Ok(())
+  })?;
+
+  let updated = stale.len() as u64;
+  for mut row in stale {
+    row.values.push(default.clone());
+    btree::delete(pager, table.root_page, row.row_id)?;
+    table.root_page = btree::insert(pager, table.root_page, row.row_id, &encode_row(&row))?;
+  }
+
+  Ok(updated)
+}
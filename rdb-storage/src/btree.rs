@@ -0,0 +1,567 @@
+//! 表 B+Tree（以 rowid 为键）
+//!
+//! - 叶子页按 rowid 升序存放 `(rowid, payload)`
+//! - 内部页存放 `(left_child, key)`，页头 `right_child` 指向最右子树
+//! - 根页分裂时分配新根，因此插入返回（可能变化的）根页 ID
+
+use rdb_domain::{PageId, RowId};
+
+use crate::cell::CHILD_PTR_SIZE;
+use crate::cell::{decode_internal_cell, decode_leaf_cell, encode_internal_cell, encode_leaf_cell};
+use crate::page::{
+  split_point_for_sizes, CellScan, Page, PageError, PageType, CELL_POINTER_SIZE, MAX_LOCAL_PAYLOAD,
+  PAGE_HEADER_SIZE, PAGE_SIZE,
+};
+use crate::pager::{Pager, PagerError, Result};
+use crate::stats::TableStats;
+use crate::varint;
+
+/// 单个 payload 的最大字节数（即叶子页的 `Page::max_local_payload`）
+///
+/// 超过此大小需要溢出页（尚未实现）。
+pub const MAX_PAYLOAD: usize = MAX_LOCAL_PAYLOAD;
+
+/// 页分裂策略
+///
+/// - `HalfHalf`：按 cell 数对半分裂
+/// - `RightBiased`：在页尾追加导致分裂时，原页保持满载，新 cell 单独进入右侧新页
+///   （类似 SQLite 的 quickbalance）；其他位置的插入仍按对半分裂。
+///   rowid 单调递增的写入会留下接近满载的左侧页。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SplitPolicy {
+  HalfHalf,
+  #[default]
+  RightBiased,
+}
+
+/// 创建一棵空树，返回根页（空叶子页）
+pub fn create(pager: &mut Pager<'_>) -> Result<PageId> {
+  pager.new_page(PageType::Leaf)
+}
+
+/// 插入 `(rowid, payload)`，使用默认分裂策略
+///
+/// 返回插入后的根页 ID（根页分裂时会变化）。rowid 已存在时返回 `DuplicateRowId`。
+pub fn insert(
+  pager: &mut Pager<'_>,
+  root: PageId,
+  row_id: RowId,
+  payload: &[u8],
+) -> Result<PageId> {
+  insert_with_policy(pager, root, row_id, payload, SplitPolicy::default())
+}
+
+/// 插入 `(rowid, payload)`，使用指定的分裂策略
+pub fn insert_with_policy(
+  pager: &mut Pager<'_>,
+  root: PageId,
+  row_id: RowId,
+  payload: &[u8],
+  policy: SplitPolicy,
+) -> Result<PageId> {
+  let (leaf_id, mut path) = descend(pager, root, row_id)?;
+
+  let leaf = pager.page(leaf_id)?;
+  // TODO: 溢出页实现后，超出部分写入溢出链而不是报错
+  if leaf.needs_overflow(payload.len()) {
+    return Err(PagerError::PayloadTooLarge { len: payload.len(), max: leaf.max_local_payload() });
+  }
+  let num_cells = leaf.num_cells()?;
+  let pos = match search_leaf(&leaf, row_id)? {
+    Ok(_) => return Err(PagerError::DuplicateRowId(row_id)),
+    Err(pos) => pos,
+  };
+  drop(leaf);
+
+  let cell = encode_leaf_cell(row_id, payload);
+  let leaf = pager.get_page_mut(leaf_id)?;
+  leaf.verify_insert_order(pos, row_id)?;
+  match leaf.insert_cell(pos, &cell) {
+    Ok(()) => return Ok(root),
+    Err(PageError::PageFull { .. }) => {}
+    Err(e) => return Err(e.into()),
+  }
+
+  // 叶子页分裂：原页保留左半部分，右半部分进入新页
+  let mut cells = read_cells(&*pager.page(leaf_id)?)?;
+  cells.insert(pos as usize, cell);
+
+  let split = fit_split(&cells, split_index(policy, &cells, pos == num_cells));
+  let right_cells = cells.split_off(split);
+  let separator = leaf_row_id(&cells[cells.len() - 1])?;
+
+  rebuild_page(pager.get_page_mut(leaf_id)?, PageType::Leaf, &cells, 0)?;
+  let right_id = pager.new_page(PageType::Leaf)?;
+  rebuild_page(
+    pager.get_page_mut(right_id)?,
+    PageType::Leaf,
+    &right_cells,
+    0,
+  )?;
+
+  insert_into_parent(pager, root, &mut path, leaf_id, separator, right_id, policy)
+}
+
+/// 插入或替换 `(rowid, payload)`，返回 `(根页 ID, 是否替换了已有行)`
+///
+/// rowid 不存在时等同于 `insert`。已存在时：新 cell 与旧 cell 等长则原地覆盖；
+/// 否则在同一叶子页内删除旧 cell 再插入到原位置；页内放不下时走普通插入（可能分裂叶子页，
+/// 行被移到新页）。
+pub fn upsert(
+  pager: &mut Pager<'_>,
+  root: PageId,
+  row_id: RowId,
+  payload: &[u8],
+) -> Result<(PageId, bool)> {
+  let (leaf_id, _) = descend(pager, root, row_id)?;
+
+  let leaf = pager.page(leaf_id)?;
+  if leaf.needs_overflow(payload.len()) {
+    return Err(PagerError::PayloadTooLarge { len: payload.len(), max: leaf.max_local_payload() });
+  }
+  let found = search_leaf(&leaf, row_id)?;
+  drop(leaf);
+  let index = match found {
+    Ok(index) => index,
+    Err(_) => return Ok((insert(pager, root, row_id, payload)?, false)),
+  };
+
+  let cell = encode_leaf_cell(row_id, payload);
+  let leaf = pager.get_page_mut(leaf_id)?;
+  if leaf.cell(index)?.len() == cell.len() {
+    leaf.cell_mut(index)?.copy_from_slice(&cell);
+    return Ok((root, true));
+  }
+
+  leaf.remove_cell(index)?;
+  match leaf.insert_cell(index, &cell) {
+    Ok(()) => Ok((root, true)),
+    Err(PageError::PageFull { .. }) => Ok((insert(pager, root, row_id, payload)?, true)),
+    Err(e) => Err(e.into()),
+  }
+}
+
+/// 删除 rowid 对应的行，返回是否删除了行
+///
+/// 只做叶子级删除：不合并/重平衡未满的页。叶子页被删空（且不是根）时，
+/// 把它从父节点摘除并通过 `free_page` 归还；父节点因此失去全部子节点时同样向上回收。
+/// 根页 ID 保持不变：根内部页失去所有子节点后退化为空叶子页。
+pub fn delete(pager: &mut Pager<'_>, root: PageId, row_id: RowId) -> Result<bool> {
+  let (leaf_id, mut path) = descend(pager, root, row_id)?;
+
+  let index = match search_leaf(&*pager.page(leaf_id)?, row_id)? {
+    Ok(index) => index,
+    Err(_) => return Ok(false),
+  };
+
+  let leaf = pager.get_page_mut(leaf_id)?;
+  leaf.remove_cell(index)?;
+  if leaf.num_cells()? > 0 || leaf_id == root {
+    return Ok(true);
+  }
+
+  // 叶子页已空：从父节点摘除，必要时继续向上
+  let mut empty = leaf_id;
+  while let Some((parent_id, slot)) = path.pop() {
+    pager.free_page(empty)?;
+
+    let parent = pager.get_page_mut(parent_id)?;
+    let num_cells = parent.num_cells()?;
+    if num_cells == 0 {
+      // 只剩 right_child（即刚清空的子页）：父节点也空了
+      if parent_id == root {
+        rebuild_page(parent, PageType::Leaf, &[], 0)?;
+        return Ok(true);
+      }
+      empty = parent_id;
+      continue;
+    }
+
+    if slot == num_cells {
+      // 删除的是 right_child：最后一个 cell 的左子树接替 right_child
+      let (last_child, _) = decode_internal(parent.cell(num_cells - 1)?)?;
+      parent.remove_cell(num_cells - 1)?;
+      let mut header = parent.try_parse_header()?;
+      header.right_child = last_child.into_inner();
+      parent.write_header(&header);
+    } else {
+      parent.remove_cell(slot)?;
+    }
+    return Ok(true);
+  }
+
+  Ok(true)
+}
+
+/// 按 rowid 查找 payload
+pub fn get(pager: &Pager<'_>, root: PageId, row_id: RowId) -> Result<Option<Vec<u8>>> {
+  let mut page_id = root;
+  loop {
+    let page = pager.page(page_id)?;
+    match page.page_type() {
+      PageType::Leaf => {
+        return match search_leaf(&page, row_id)? {
+          Ok(index) => {
+            let (_, payload) = decode_leaf(page.cell(index)?)?;
+            Ok(Some(payload.to_vec()))
+          }
+          Err(_) => Ok(None),
+        };
+      }
+      PageType::Internal => page_id = child_at(&page, child_slot(&page, row_id)?)?,
+      other => return Err(unexpected_page_type(page_id, other)),
+    }
+  }
+}
+
+/// 定位 rowid 所在的叶子页和 cell 下标，不存在时返回 `None`
+pub(crate) fn locate(
+  pager: &Pager<'_>,
+  root: PageId,
+  row_id: RowId,
+) -> Result<Option<(PageId, u16)>> {
+  let (leaf_id, _) = descend(pager, root, row_id)?;
+  Ok(
+    search_leaf(&*pager.page(leaf_id)?, row_id)?
+      .ok()
+      .map(|index| (leaf_id, index)),
+  )
+}
+
+/// 统计行数
+///
+/// 对叶子页中未软删除的 cell 计数（只读 cell pointer），内部页只解析子页指针，
+/// 不解码任何 payload。
+pub fn count(pager: &Pager<'_>, root: PageId) -> Result<u64> {
+  Ok(analyze(pager, root)?.row_count)
+}
+
+/// 遍历整棵树收集统计信息（每个页只访问一次）
+pub(crate) fn analyze(pager: &Pager<'_>, root: PageId) -> Result<TableStats> {
+  let mut stats = TableStats { row_count: 0, leaf_pages: 0, depth: 0 };
+  let mut stack = vec![(root, 1u8)];
+
+  while let Some((page_id, depth)) = stack.pop() {
+    let page = pager.page(page_id)?;
+    let header = page.try_parse_header()?;
+    stats.depth = stats.depth.max(depth);
+    match header.page_type {
+      PageType::Leaf => {
+        stats.row_count += u64::from(page.num_live_cells()?);
+        stats.leaf_pages += 1;
+      }
+      PageType::Internal => {
+        let child_depth = depth
+          .checked_add(1)
+          .ok_or_else(|| invariant("btree depth overflow"))?;
+        stack.push((PageId::new(header.right_child), child_depth));
+        for i in 0..header.num_cells {
+          stack.push((decode_internal(page.cell(i)?)?.0, child_depth));
+        }
+      }
+      other => return Err(unexpected_page_type(page_id, other)),
+    }
+  }
+
+  Ok(stats)
+}
+
+/// 树中最大的 rowid，空树返回 `None`
+pub fn max_row_id(pager: &Pager<'_>, root: PageId) -> Result<Option<RowId>> {
+  // 删除可能留下空叶子页，从右往左找第一个非空的
+  for leaf in leaf_pages(pager, root)?.into_iter().rev() {
+    let page = pager.page(leaf)?;
+    let n = page.num_cells()?;
+    if n > 0 {
+      return Ok(Some(leaf_row_id(page.cell(n - 1)?)?));
+    }
+  }
+  Ok(None)
+}
+
+/// 按 rowid 升序遍历树中的所有 rowid（跳过已软删除的行）
+///
+/// 只解码每个叶子 cell 开头的 rowid varint，不读 payload，比 `scan::scan_rows` 便宜得多，
+/// 适合重建索引等只需要 rowid 的场景。每次读取一个叶子页；出错时产出该错误后结束迭代。
+pub fn rowids<'a>(
+  pager: &'a Pager<'_>,
+  root: PageId,
+) -> Box<dyn Iterator<Item = Result<RowId>> + 'a> {
+  let mut leaves = match leaf_pages(pager, root) {
+    Ok(leaves) => Ok(leaves.into_iter()),
+    Err(e) => Err(Some(e)),
+  };
+  let mut buffered = Vec::new().into_iter();
+
+  Box::new(std::iter::from_fn(move || loop {
+    if let Some(row_id) = buffered.next() {
+      return Some(Ok(row_id));
+    }
+    let leaf = match &mut leaves {
+      Ok(leaves) => leaves.next()?,
+      Err(e) => return e.take().map(Err),
+    };
+    match leaf_rowids(pager, leaf) {
+      Ok(ids) => buffered = ids.into_iter(),
+      Err(e) => {
+        // 出错后结束迭代，不再读取后面的叶子页
+        leaves = Err(None);
+        return Some(Err(e));
+      }
+    }
+  }))
+}
+
+/// 一个叶子页中未软删除的 rowid
+fn leaf_rowids(pager: &Pager<'_>, leaf: PageId) -> Result<Vec<RowId>> {
+  let page = pager.page(leaf)?;
+  let ids = page
+    .iter_cells(CellScan::SkipTombstones)?
+    .map(|cell| {
+      let (_, cell) = cell?;
+      varint::decode(cell)
+        .map(|(row_id, _)| RowId::new(row_id as i64))
+        .ok_or_else(|| invariant("corrupt leaf cell"))
+    })
+    .collect();
+  ids
+}
+
+/// 按 rowid 顺序返回所有叶子页 ID
+pub fn leaf_pages(pager: &Pager<'_>, root: PageId) -> Result<Vec<PageId>> {
+  let mut leaves = Vec::new();
+  let mut stack = vec![root];
+
+  while let Some(page_id) = stack.pop() {
+    let page = pager.page(page_id)?;
+    match page.page_type() {
+      PageType::Leaf => leaves.push(page_id),
+      PageType::Internal => {
+        // 逆序入栈，保证按从左到右的顺序出栈
+        stack.push(PageId::new(page.try_parse_header()?.right_child));
+        for i in (0..page.num_cells()?).rev() {
+          stack.push(decode_internal(page.cell(i)?)?.0);
+        }
+      }
+      other => return Err(unexpected_page_type(page_id, other)),
+    }
+  }
+
+  Ok(leaves)
+}
+
+/// 从根下降到 `row_id` 所属的叶子页，返回叶子页 ID 和路径 `(内部页, 子节点槽位)`
+///
+/// 槽位 `== num_cells` 表示走的是 `right_child`。
+fn descend(pager: &Pager<'_>, root: PageId, row_id: RowId) -> Result<(PageId, Vec<(PageId, u16)>)> {
+  let mut path = Vec::new();
+  let mut page_id = root;
+  loop {
+    let page = pager.page(page_id)?;
+    match page.page_type() {
+      PageType::Leaf => return Ok((page_id, path)),
+      PageType::Internal => {
+        let slot = child_slot(&page, row_id)?;
+        path.push((page_id, slot));
+        page_id = child_at(&page, slot)?;
+      }
+      other => return Err(unexpected_page_type(page_id, other)),
+    }
+  }
+}
+
+/// 把分裂产生的 `(left, separator, right)` 插入父节点，必要时继续向上分裂
+fn insert_into_parent(
+  pager: &mut Pager<'_>,
+  root: PageId,
+  path: &mut Vec<(PageId, u16)>,
+  mut left: PageId,
+  mut separator: RowId,
+  mut right: PageId,
+  policy: SplitPolicy,
+) -> Result<PageId> {
+  loop {
+    let Some((parent_id, slot)) = path.pop() else {
+      // 根页分裂：新建内部页作为根
+      let new_root = pager.new_page(PageType::Internal)?;
+      let cell = encode_internal_cell(left, separator);
+      rebuild_page(
+        pager.get_page_mut(new_root)?,
+        PageType::Internal,
+        &[cell],
+        right.into_inner(),
+      )?;
+      return Ok(new_root);
+    };
+
+    // 原来指向 left 的槽位改为指向 right，再在该槽位插入 (left, separator)
+    let parent = pager.get_page_mut(parent_id)?;
+    let num_cells = parent.num_cells()?;
+    set_child(parent, slot, right)?;
+
+    let cell = encode_internal_cell(left, separator);
+    match parent.insert_cell(slot, &cell) {
+      Ok(()) => return Ok(root),
+      Err(PageError::PageFull { .. }) => {}
+      Err(e) => return Err(e.into()),
+    }
+
+    // 内部页分裂：split 位置的 cell 上推，其左子树成为左页的 right_child
+    let parent = pager.page(parent_id)?;
+    let right_child = parent.try_parse_header()?.right_child;
+    let mut cells = read_cells(&parent)?;
+    drop(parent);
+    cells.insert(slot as usize, cell);
+
+    // 右侧至少保留一个 cell
+    let split = split_index(policy, &cells, slot == num_cells).min(cells.len() - 2);
+    let right_cells = cells.split_off(split + 1);
+    let promoted = cells
+      .pop()
+      .ok_or_else(|| invariant("internal split with no cells"))?;
+    let (promoted_child, promoted_key) = decode_internal(&promoted)?;
+
+    rebuild_page(
+      pager.get_page_mut(parent_id)?,
+      PageType::Internal,
+      &cells,
+      promoted_child.into_inner(),
+    )?;
+    let new_right = pager.new_page(PageType::Internal)?;
+    rebuild_page(
+      pager.get_page_mut(new_right)?,
+      PageType::Internal,
+      &right_cells,
+      right_child,
+    )?;
+
+    left = parent_id;
+    separator = promoted_key;
+    right = new_right;
+  }
+}
+
+/// cell 大小相差超过这个倍数时，按字节而不是按个数均分
+const SIZE_SKEW_FACTOR: usize = 2;
+
+/// 计算分裂位置：返回留在左页的 cell 数
+///
+/// 顺序追加（`RightBiased`）时只把新 cell 移到右页；否则按个数对半分，
+/// cell 大小悬殊时改用 `split_point_for_sizes` 按字节对半分。
+fn split_index(policy: SplitPolicy, cells: &[Vec<u8>], appended: bool) -> usize {
+  let len = cells.len();
+  match policy {
+    SplitPolicy::RightBiased if appended => len - 1,
+    _ => {
+      let sizes: Vec<usize> = cells.iter().map(Vec::len).collect();
+      let (min, max) = (sizes.iter().min(), sizes.iter().max());
+      match min.zip(max) {
+        Some((&min, &max)) if max > min * SIZE_SKEW_FACTOR => split_point_for_sizes(&sizes),
+        _ => len / 2,
+      }
+    }
+  }
+}
+
+/// 按 cell 数选出的分裂位置在 cell 大小悬殊时可能让一侧放不下，这里向另一侧挪动直到两侧都放得下
+fn fit_split(cells: &[Vec<u8>], mut split: usize) -> usize {
+  let usable = PAGE_SIZE - PAGE_HEADER_SIZE;
+  let used = |cells: &[Vec<u8>]| {
+    cells
+      .iter()
+      .map(|c| c.len() + CELL_POINTER_SIZE)
+      .sum::<usize>()
+  };
+
+  while split > 1 && used(&cells[..split]) > usable {
+    split -= 1;
+  }
+  while split < cells.len() - 1 && used(&cells[split..]) > usable {
+    split += 1;
+  }
+  split
+}
+
+/// 用给定的 cell 重建整页（保留页头中 lsn/checksum/reserved）
+fn rebuild_page(
+  page: &mut Page<'_>,
+  page_type: PageType,
+  cells: &[Vec<u8>],
+  right_child: u32,
+) -> Result<()> {
+  let mut header = page.try_parse_header()?;
+  header.page_type = page_type;
+  header.first_freeblock = 0;
+  header.num_cells = 0;
+  header.cell_content_area = PAGE_SIZE as u16;
+  header.fragmented_bytes = 0;
+  header.right_child = right_child;
+  page.write_header(&header);
+
+  for (i, cell) in cells.iter().enumerate() {
+    page.insert_cell(i as u16, cell)?;
+  }
+  Ok(())
+}
+
+fn read_cells(page: &Page<'_>) -> Result<Vec<Vec<u8>>> {
+  (0..page.num_cells()?)
+    .map(|i| Ok(page.cell(i)?.to_vec()))
+    .collect()
+}
+
+/// 在叶子页中查找 rowid：找到返回 `Ok(index)`，否则返回 `Err(插入位置)`
+fn search_leaf(page: &Page<'_>, row_id: RowId) -> Result<std::result::Result<u16, u16>> {
+  // cell 数量不超过 u16，下标转换不会截断
+  Ok(match page.find_cell_by_rowid(row_id)? {
+    Ok(i) => Ok(i as u16),
+    Err(i) => Err(i as u16),
+  })
+}
+
+/// 在内部页中选择 rowid 所属的子节点槽位：第一个 key >= rowid 的 cell，否则为 right_child
+fn child_slot(page: &Page<'_>, row_id: RowId) -> Result<u16> {
+  let (Ok(i) | Err(i)) = page.find_cell_by_rowid(row_id)?;
+  Ok(i as u16)
+}
+
+pub(crate) fn child_at(page: &Page<'_>, slot: u16) -> Result<PageId> {
+  if slot == page.num_cells()? {
+    return Ok(PageId::new(page.try_parse_header()?.right_child));
+  }
+  Ok(decode_internal(page.cell(slot)?)?.0)
+}
+
+fn set_child(page: &mut Page<'_>, slot: u16, child: PageId) -> Result<()> {
+  if slot == page.num_cells()? {
+    let mut header = page.try_parse_header()?;
+    header.right_child = child.into_inner();
+    page.write_header(&header);
+  } else {
+    let cell = page.cell_mut(slot)?;
+    cell[..CHILD_PTR_SIZE].copy_from_slice(&child.into_inner().to_le_bytes());
+  }
+  Ok(())
+}
+
+pub(crate) fn leaf_row_id(cell: &[u8]) -> Result<RowId> {
+  Ok(decode_leaf(cell)?.0)
+}
+
+fn decode_leaf(cell: &[u8]) -> Result<(RowId, &[u8])> {
+  decode_leaf_cell(cell).ok_or_else(|| invariant("corrupt leaf cell"))
+}
+
+fn decode_internal(cell: &[u8]) -> Result<(PageId, RowId)> {
+  decode_internal_cell(cell).ok_or_else(|| invariant("corrupt internal cell"))
+}
+
+pub(crate) fn unexpected_page_type(page_id: PageId, page_type: PageType) -> PagerError {
+  invariant(&format!(
+    "page {page_id:?} has unexpected type {page_type:?} in btree"
+  ))
+}
+
+fn invariant(message: &str) -> PagerError {
+  PagerError::BTreeInvariant(message.to_string())
+}
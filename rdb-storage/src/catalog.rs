@@ -0,0 +1,61 @@
+//! 系统目录（schema 的持久化）
+//!
+//! 系统目录是一棵普通的表 B+Tree，根页记录在 `DbHeader::catalog_root`。
+//! 每行描述一个 schema 对象，即 `Table::master` 描述的 `rdb_master` 表，
+//! 列布局与 SQLite 的 `sqlite_master` 一致：
+//!
+//! ```text
+//! (type TEXT, name TEXT, tbl_name TEXT, rootpage INTEGER, sql TEXT)
+//! ```
+//!
+//! 表的行以 `TableId` 作为 rowid。
+
+use rdb_domain::{PageId, RowId, Table, Value};
+
+use crate::btree;
+use crate::cell::decode_leaf_cell;
+use crate::db_header::DB_HEADER_PAGE;
+use crate::pager::{Pager, PagerError, Result};
+use crate::record::{decode_values, encode_row};
+
+/// `rootpage` 列的下标
+pub(crate) const COL_ROOTPAGE: usize = 3;
+
+/// 把表写入系统目录
+///
+/// 目录根页分裂时会同步更新数据库头中的 `catalog_root`。
+pub fn add_table(pager: &mut Pager<'_>, table: &Table) -> Result<()> {
+  let row = table.to_master_row();
+  insert_row(pager, row.row_id, &encode_row(&row))
+}
+
+/// 把一条已编码的目录行写入系统目录，目录根页分裂时同步更新数据库头
+pub(crate) fn insert_row(pager: &mut Pager<'_>, row_id: RowId, record: &[u8]) -> Result<()> {
+  let mut header = pager.db_header()?;
+  let root = btree::insert(pager, header.catalog_root, row_id, record)?;
+
+  if root != header.catalog_root {
+    header.catalog_root = root;
+    header.write(pager.get_page_mut(DB_HEADER_PAGE)?)?;
+  }
+  Ok(())
+}
+
+/// 读取系统目录中所有对象的根页（按 rowid 顺序）
+pub fn table_roots(pager: &Pager<'_>, catalog_root: PageId) -> Result<Vec<PageId>> {
+  let mut roots = Vec::new();
+  for leaf in btree::leaf_pages(pager, catalog_root)? {
+    let page = pager.page(leaf)?;
+    for i in 0..page.num_cells()? {
+      let (_, payload) = decode_leaf_cell(page.cell(i)?)
+        .ok_or_else(|| PagerError::BTreeInvariant("corrupt catalog cell".to_string()))?;
+      let root = decode_values(payload)?
+        .get(COL_ROOTPAGE)
+        .and_then(Value::as_integer)
+        .and_then(|id| u32::try_from(id).ok())
+        .ok_or_else(|| PagerError::BTreeInvariant("catalog row without rootpage".to_string()))?;
+      roots.push(PageId::new(root));
+    }
+  }
+  Ok(roots)
+}
@@ -0,0 +1,72 @@
+//! B+Tree cell 编码
+//!
+//! 两种 cell 格式：
+//! - 叶子 cell：`[rowid varint][payload_len varint][payload]`
+//! - 内部 cell：`[left_child u32 (LE)][rowid varint]`
+//!
+//! 内部节点语义：cell `(child, key)` 的子树中所有 rowid <= key；
+//! 大于最后一个 key 的 rowid 位于页头的 `right_child`。
+
+use rdb_domain::{PageId, RowId};
+
+use crate::page::PageType;
+use crate::varint;
+
+/// 内部 cell 中子页指针的字节数
+pub const CHILD_PTR_SIZE: usize = 4;
+
+/// 编码叶子 cell
+pub fn encode_leaf_cell(row_id: RowId, payload: &[u8]) -> Vec<u8> {
+  let mut out = Vec::with_capacity(2 * varint::MAX_VARINT_LEN + payload.len());
+  varint::encode(row_id.into_inner() as u64, &mut out);
+  varint::encode(payload.len() as u64, &mut out);
+  out.extend_from_slice(payload);
+  out
+}
+
+/// 解码叶子 cell，返回 `(rowid, payload)`
+///
+/// cell 截断或长度字段越界时返回 `None`。
+pub fn decode_leaf_cell(cell: &[u8]) -> Option<(RowId, &[u8])> {
+  let (row_id, n1) = varint::decode(cell)?;
+  let (len, n2) = varint::decode(&cell[n1..])?;
+  let start = n1 + n2;
+  let end = start.checked_add(usize::try_from(len).ok()?)?;
+  let payload = cell.get(start..end)?;
+  Some((RowId::new(row_id as i64), payload))
+}
+
+/// 编码内部 cell
+pub fn encode_internal_cell(left_child: PageId, row_id: RowId) -> Vec<u8> {
+  let mut out = Vec::with_capacity(CHILD_PTR_SIZE + varint::MAX_VARINT_LEN);
+  out.extend_from_slice(&left_child.into_inner().to_le_bytes());
+  varint::encode(row_id.into_inner() as u64, &mut out);
+  out
+}
+
+/// 解码内部 cell，返回 `(left_child, rowid)`
+pub fn decode_internal_cell(cell: &[u8]) -> Option<(PageId, RowId)> {
+  let child = cell.get(..CHILD_PTR_SIZE)?;
+  let child = u32::from_le_bytes([child[0], child[1], child[2], child[3]]);
+  let (row_id, _) = varint::decode(&cell[CHILD_PTR_SIZE..])?;
+  Some((PageId::new(child), RowId::new(row_id as i64)))
+}
+
+/// 根据页类型计算从 `buf` 开头开始的 cell 占用的字节数
+///
+/// 非 B+Tree 页（Overflow/Freelist/Meta）没有 cell，返回 `None`；cell 损坏时也返回 `None`。
+pub fn cell_size(page_type: PageType, buf: &[u8]) -> Option<usize> {
+  match page_type {
+    PageType::Leaf => {
+      let (_, n1) = varint::decode(buf)?;
+      let (len, n2) = varint::decode(&buf[n1..])?;
+      let size = (n1 + n2).checked_add(usize::try_from(len).ok()?)?;
+      (size <= buf.len()).then_some(size)
+    }
+    PageType::Internal => {
+      let (_, n) = varint::decode(buf.get(CHILD_PTR_SIZE..)?)?;
+      Some(CHILD_PTR_SIZE + n)
+    }
+    PageType::Overflow | PageType::Freelist | PageType::Meta => None,
+  }
+}
@@ -0,0 +1,131 @@
+//! record 压缩（可选）
+//!
+//! 压缩后的 payload 格式：
+//!
+//! ```text
+//! [0x00 标记][压缩算法 id][原始长度 varint][压缩数据 ...]
+//! ```
+//!
+//! 普通 record 的首字节是 `header_size`（至少为 1），不可能是 0x00，
+//! 因此标记字节不会与旧文件冲突，解码时可以透明地识别并解压。
+//!
+//! 只有压缩后确实变小时才写压缩格式，否则保留原 record。
+//! 解码只认识内置算法：[`NoCompression`]（id 0，不产生压缩 payload）
+//! 和开启 `lz4` feature 时的 [`Lz4`]（id 1）。
+
+use std::borrow::Cow;
+
+use crate::record::DecodeError;
+use crate::varint;
+
+/// 压缩 payload 的首字节
+pub const COMPRESSED_MARKER: u8 = 0x00;
+
+/// 不压缩的算法 id
+pub const NO_COMPRESSION_ID: u8 = 0;
+
+/// LZ4 的算法 id
+pub const LZ4_ID: u8 = 1;
+
+/// record 压缩算法
+pub trait Compressor {
+  /// 写入压缩 payload 的算法 id，解码时据此选择解压算法
+  fn id(&self) -> u8;
+
+  /// 压缩整条 record
+  fn compress(&self, input: &[u8]) -> Vec<u8>;
+
+  /// 解压；`raw_len` 为压缩前的长度，数据损坏时返回 `None`
+  fn decompress(&self, input: &[u8], raw_len: usize) -> Option<Vec<u8>>;
+}
+
+/// 不压缩（默认）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoCompression;
+
+impl Compressor for NoCompression {
+  fn id(&self) -> u8 {
+    NO_COMPRESSION_ID
+  }
+
+  fn compress(&self, input: &[u8]) -> Vec<u8> {
+    input.to_vec()
+  }
+
+  fn decompress(&self, input: &[u8], raw_len: usize) -> Option<Vec<u8>> {
+    (input.len() == raw_len).then(|| input.to_vec())
+  }
+}
+
+/// LZ4 块压缩
+#[cfg(feature = "lz4")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Lz4;
+
+#[cfg(feature = "lz4")]
+impl Compressor for Lz4 {
+  fn id(&self) -> u8 {
+    LZ4_ID
+  }
+
+  fn compress(&self, input: &[u8]) -> Vec<u8> {
+    lz4_flex::block::compress(input)
+  }
+
+  fn decompress(&self, input: &[u8], raw_len: usize) -> Option<Vec<u8>> {
+    lz4_flex::block::decompress(input, raw_len).ok()
+  }
+}
+
+/// 按 id 查找内置的压缩算法
+pub fn builtin(id: u8) -> Option<&'static dyn Compressor> {
+  match id {
+    NO_COMPRESSION_ID => Some(&NoCompression),
+    #[cfg(feature = "lz4")]
+    LZ4_ID => Some(&Lz4),
+    _ => None,
+  }
+}
+
+/// payload 是否为压缩格式
+pub fn is_compressed(payload: &[u8]) -> bool {
+  payload.first() == Some(&COMPRESSED_MARKER)
+}
+
+/// 用 `compressor` 压缩 record；压缩后不更小（或算法为 [`NoCompression`]）时原样返回
+pub fn compress_payload(record: Vec<u8>, compressor: &dyn Compressor) -> Vec<u8> {
+  if compressor.id() == NO_COMPRESSION_ID {
+    return record;
+  }
+
+  let compressed = compressor.compress(&record);
+  let mut out = Vec::with_capacity(2 + varint::MAX_VARINT_LEN + compressed.len());
+  out.push(COMPRESSED_MARKER);
+  out.push(compressor.id());
+  varint::encode(record.len() as u64, &mut out);
+  out.extend_from_slice(&compressed);
+
+  if out.len() < record.len() {
+    out
+  } else {
+    record
+  }
+}
+
+/// 把 payload 还原为普通 record；未压缩时直接借用原字节
+pub fn decompress_payload(payload: &[u8]) -> Result<Cow<'_, [u8]>, DecodeError> {
+  if !is_compressed(payload) {
+    return Ok(Cow::Borrowed(payload));
+  }
+
+  let id = *payload.get(1).ok_or(DecodeError::Truncated)?;
+  let compressor = builtin(id).ok_or(DecodeError::UnsupportedCompression(id))?;
+  let (raw_len, n) = varint::decode(&payload[2..]).ok_or(DecodeError::Truncated)?;
+  let raw_len = usize::try_from(raw_len).map_err(|_| DecodeError::CorruptCompressed)?;
+
+  let raw = compressor
+    .decompress(&payload[2 + n..], raw_len)
+    .filter(|raw| raw.len() == raw_len)
+    .ok_or(DecodeError::CorruptCompressed)?;
+  Ok(Cow::Owned(raw))
+}
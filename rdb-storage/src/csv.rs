@@ -0,0 +1,228 @@
+//! CSV 导入/导出
+//!
+//! 格式：第一行为列名，之后每行一条记录，行尾为 `\n`（导入时也接受 `\r\n`）。
+//! 含逗号、双引号或换行的字段用双引号包裹，内部的 `"` 转义为 `""`；
+//! NULL 输出为空字段，空字符串输出为 `""`，BLOB 输出为大写十六进制。
+
+use std::borrow::Cow;
+use std::io::{self, BufRead, Write};
+
+use rdb_domain::{DataType, Row, RowId, Table, Value};
+
+use crate::btree;
+use crate::pager::{Pager, PagerError, Result};
+use crate::record::encode_row;
+use crate::scan::scan_rows;
+
+/// 把表的全部行按 rowid 顺序以 CSV 写入 `writer`
+///
+/// 逐行扫描、逐行写出，不缓存整张表。存储层错误转换为 `io::Error`
+/// （`PagerError::Io` 原样返回，其余为 `ErrorKind::Other`）。
+pub fn export_csv(pager: &Pager<'_>, table: &Table, mut writer: impl Write) -> io::Result<()> {
+  let header: Vec<String> = table
+    .columns
+    .iter()
+    .map(|c| escape_field(&c.name))
+    .collect();
+  writeln!(writer, "{}", header.join(","))?;
+
+  scan_rows(pager, table, |row| {
+    let fields: Vec<String> = row.values.iter().map(render_field).collect();
+    writeln!(writer, "{}", fields.join(","))?;
+    Ok(())
+  })
+  .map_err(|e| match e {
+    PagerError::Io(e) => e,
+    other => io::Error::other(other),
+  })?;
+
+  writer.flush()
+}
+
+/// 从 `reader` 逐行读取 CSV 并插入表，返回导入的行数
+///
+/// - 第一行是列名，按名称对应到表的列，顺序可以与表不同；CSV 中缺少的列取 NULL
+/// - 空字段（未加引号）为 NULL；BLOB 列的字段按十六进制解码；
+///   其他字段按列类型转换（见 `Value::coerce_to`），再用 `Table::validate_row` 校验
+/// - 表有 INTEGER 主键时用主键值作为 rowid，否则从当前最大 rowid 之后依次分配
+///
+/// 插入可能导致根页分裂，因此会更新 `table.root_page`，调用方需要负责持久化。
+/// 解析或校验失败时返回 `PagerError::Csv`，`line` 为该记录起始的行号（从 1 开始，表头为第 1 行）；
+/// 出错前已插入的行不会回滚。
+pub fn import_csv(
+  pager: &mut Pager<'_>,
+  table: &mut Table,
+  mut reader: impl BufRead,
+) -> Result<u64> {
+  let mut line = 0;
+  let Some((_, header)) = read_record(&mut reader, &mut line)? else {
+    return Err(csv_error(1, "missing header line"));
+  };
+
+  // CSV 第 i 个字段对应表的第 mapping[i] 列
+  let mut mapping = Vec::with_capacity(header.len());
+  for field in &header {
+    let name = field.as_deref().unwrap_or_default();
+    let index = table
+      .columns
+      .iter()
+      .position(|c| c.name == name)
+      .ok_or_else(|| csv_error(1, &format!("unknown column '{name}'")))?;
+    if mapping.contains(&index) {
+      return Err(csv_error(1, &format!("duplicate column '{name}'")));
+    }
+    mapping.push(index);
+  }
+
+  let pk_index = table
+    .primary_key_column()
+    .filter(|c| c.data_type == DataType::Integer)
+    .and_then(|pk| table.columns.iter().position(|c| c.id == pk.id));
+  let mut next_row_id =
+    btree::max_row_id(pager, table.root_page)?.map_or(1, |id| id.into_inner() + 1);
+
+  let mut imported = 0;
+  while let Some((start, fields)) = read_record(&mut reader, &mut line)? {
+    if fields.len() != mapping.len() {
+      return Err(csv_error(
+        start,
+        &format!("expected {} fields, got {}", mapping.len(), fields.len()),
+      ));
+    }
+
+    let mut values = vec![Value::Null; table.columns.len()];
+    for (field, &index) in fields.into_iter().zip(&mapping) {
+      let column = &table.columns[index];
+      values[index] = match field {
+        None => Value::Null,
+        Some(text) if column.data_type == DataType::Blob => decode_hex(&text)
+          .map(|bytes| Value::Blob(Cow::Owned(bytes)))
+          .ok_or_else(|| csv_error(start, &format!("column '{}': invalid hex", column.name)))?,
+        Some(text) => column
+          .validate_value_coercing(&Value::Text(Cow::Owned(text)))
+          .map_err(|e| csv_error(start, &e.to_string()))?,
+      };
+    }
+
+    let row_id = match pk_index.and_then(|i| values[i].as_integer()) {
+      Some(pk) => pk,
+      None => next_row_id,
+    };
+    next_row_id = next_row_id.max(row_id.saturating_add(1));
+
+    let row = Row::new(RowId::new(row_id), values);
+    table
+      .validate_row(&row)
+      .map_err(|e| csv_error(start, &e.to_string()))?;
+    table.root_page = btree::insert(pager, table.root_page, row.row_id, &encode_row(&row))?;
+    imported += 1;
+  }
+
+  Ok(imported)
+}
+
+fn render_field(value: &Value<'_>) -> String {
+  match value {
+    Value::Null => String::new(),
+    // 与 NULL 的空字段区分
+    text if text.as_text() == Some("") => "\"\"".to_string(),
+    other => escape_field(&other.to_string()),
+  }
+}
+
+fn escape_field(field: &str) -> String {
+  if field.contains([',', '"', '\n', '\r']) {
+    format!("\"{}\"", field.replace('"', "\"\""))
+  } else {
+    field.to_string()
+  }
+}
+
+/// 读取一条记录（引号内的换行会跨行），返回起始行号和字段；未加引号的空字段为 `None`
+fn read_record(
+  reader: &mut impl BufRead,
+  line: &mut usize,
+) -> Result<Option<(usize, Vec<Option<String>>)>> {
+  let mut record = String::new();
+  let start = *line + 1;
+  loop {
+    let mut buf = String::new();
+    if reader.read_line(&mut buf)? == 0 {
+      if record.is_empty() {
+        return Ok(None);
+      }
+      return Err(csv_error(start, "unterminated quoted field"));
+    }
+    *line += 1;
+    record.push_str(&buf);
+
+    // 引号成对出现才说明记录结束
+    if record.matches('"').count() % 2 == 0 {
+      break;
+    }
+  }
+
+  let record = record
+    .strip_suffix('\n')
+    .map(|r| r.strip_suffix('\r').unwrap_or(r))
+    .unwrap_or(&record);
+  parse_fields(record)
+    .map(|fields| Some((start, fields)))
+    .ok_or_else(|| csv_error(start, "malformed quoted field"))
+}
+
+fn parse_fields(record: &str) -> Option<Vec<Option<String>>> {
+  let mut fields = Vec::new();
+  let mut chars = record.chars().peekable();
+
+  loop {
+    let mut field = String::new();
+    let mut quoted = false;
+
+    if chars.peek() == Some(&'"') {
+      quoted = true;
+      chars.next();
+      loop {
+        match chars.next()? {
+          '"' if chars.peek() == Some(&'"') => {
+            chars.next();
+            field.push('"');
+          }
+          '"' => break,
+          c => field.push(c),
+        }
+      }
+    }
+
+    while let Some(&c) = chars.peek() {
+      if c == ',' {
+        break;
+      }
+      // 右引号之后只能是分隔符或行尾
+      if quoted {
+        return None;
+      }
+      field.push(c);
+      chars.next();
+    }
+
+    fields.push((quoted || !field.is_empty()).then_some(field));
+    if chars.next().is_none() {
+      return Some(fields);
+    }
+  }
+}
+
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+  if text.len() % 2 != 0 || !text.bytes().all(|b| b.is_ascii_hexdigit()) {
+    return None;
+  }
+  (0..text.len())
+    .step_by(2)
+    .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+    .collect()
+}
+
+fn csv_error(line: usize, message: &str) -> PagerError {
+  PagerError::Csv { line, message: message.to_string() }
+}
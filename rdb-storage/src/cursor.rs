@@ -0,0 +1,195 @@
+//! B+Tree 游标：在 rowid 树上双向逐行移动
+//!
+//! 叶子页之间没有兄弟指针，跨叶子移动时沿记录的根到叶路径回溯到父节点，
+//! 再从相邻的子树重新下降（parent re-descent）。删除留下的空叶子页会被跳过。
+
+use rdb_domain::{PageId, RowId, SortDirection, Table};
+
+use crate::btree::{child_at, leaf_row_id, unexpected_page_type};
+use crate::cell::decode_leaf_cell;
+use crate::page::PageType;
+use crate::pager::{Pager, PagerError, Result};
+
+/// rowid B+Tree 上的只读游标
+///
+/// 新建的游标未定位，需要先调用 `first`/`last`（或按表的默认方向 `rewind`）。
+/// 移动方法返回是否停在了某一行上；越过任一端后游标失效，需要重新定位。
+/// 游标持有 `&Pager`，存活期间树不能被修改。
+///
+/// # Examples
+///
+/// let mut cursor = Cursor::new(&pager, table.root_page);
+/// let mut valid = cursor.last()?;
+/// while valid {
+///   println!("{:?}", cursor.row_id()?);
+///   valid = cursor.prev()?;
+/// }
+///
+pub struct Cursor<'a, 'db> {
+  pager: &'a Pager<'db>,
+  root: PageId,
+  order: SortDirection,
+  /// 从根到当前叶子的路径：`(内部页, 子节点槽位)`，槽位等于 cell 数表示 right_child
+  path: Vec<(PageId, u16)>,
+  /// 当前行：`(叶子页, cell 下标)`；`None` 表示未定位或已越过两端
+  position: Option<(PageId, u16)>,
+}
+
+impl<'a, 'db> Cursor<'a, 'db> {
+  /// 在以 `root` 为根的树上创建游标，默认方向为升序
+  pub fn new(pager: &'a Pager<'db>, root: PageId) -> Self {
+    Self { pager, root, order: SortDirection::Ascending, path: Vec::new(), position: None }
+  }
+
+  /// 在表的 B+Tree 上创建游标，`rewind`/`step` 按表的 `rowid_order` 移动
+  pub fn for_table(pager: &'a Pager<'db>, table: &Table) -> Self {
+    Self { order: table.rowid_order, ..Self::new(pager, table.root_page) }
+  }
+
+  /// 定位到最小的 rowid，空树返回 `false`
+  pub fn first(&mut self) -> Result<bool> {
+    self.path.clear();
+    let leaf = self.descend_edge(self.root, SortDirection::Ascending)?;
+    self.settle(leaf, SortDirection::Ascending)
+  }
+
+  /// 定位到最大的 rowid，空树返回 `false`
+  pub fn last(&mut self) -> Result<bool> {
+    self.path.clear();
+    let leaf = self.descend_edge(self.root, SortDirection::Descending)?;
+    self.settle(leaf, SortDirection::Descending)
+  }
+
+  /// 移动到下一个（更大的）rowid，已在最后一行时返回 `false` 并失效
+  ///
+  /// 与 `prev` 对称的游标操作，不是 `Iterator::next`：只移动位置，行数据用 `row_id`/`payload` 读取。
+  #[allow(clippy::should_implement_trait)]
+  pub fn next(&mut self) -> Result<bool> {
+    self.advance(SortDirection::Ascending)
+  }
+
+  /// 移动到上一个（更小的）rowid，已在第一行时返回 `false` 并失效
+  pub fn prev(&mut self) -> Result<bool> {
+    self.advance(SortDirection::Descending)
+  }
+
+  /// 按默认方向定位到第一行：升序时同 `first`，降序时同 `last`
+  pub fn rewind(&mut self) -> Result<bool> {
+    match self.order {
+      SortDirection::Ascending => self.first(),
+      SortDirection::Descending => self.last(),
+    }
+  }
+
+  /// 按默认方向移动一行：升序时同 `next`，降序时同 `prev`
+  pub fn step(&mut self) -> Result<bool> {
+    self.advance(self.order)
+  }
+
+  /// 游标当前是否停在某一行上
+  pub fn is_valid(&self) -> bool {
+    self.position.is_some()
+  }
+
+  /// 当前行的 rowid，游标失效时返回 `None`
+  pub fn row_id(&self) -> Result<Option<RowId>> {
+    let Some((leaf, index)) = self.position else {
+      return Ok(None);
+    };
+    Ok(Some(leaf_row_id(self.pager.page(leaf)?.cell(index)?)?))
+  }
+
+  /// 当前行的 payload（record 编码），游标失效时返回 `None`
+  pub fn payload(&self) -> Result<Option<Vec<u8>>> {
+    let Some((leaf, index)) = self.position else {
+      return Ok(None);
+    };
+    let page = self.pager.page(leaf)?;
+    let (_, payload) = decode_leaf_cell(page.cell(index)?)
+      .ok_or_else(|| PagerError::BTreeInvariant(format!("corrupt leaf cell in {leaf:?}")))?;
+    Ok(Some(payload.to_vec()))
+  }
+
+  fn advance(&mut self, direction: SortDirection) -> Result<bool> {
+    let Some((leaf, index)) = self.position else {
+      return Ok(false);
+    };
+    let num_cells = self.pager.page(leaf)?.num_cells()?;
+    let within = match direction {
+      SortDirection::Ascending => index.checked_add(1).filter(|&i| i < num_cells),
+      SortDirection::Descending => index.checked_sub(1),
+    };
+    if let Some(index) = within {
+      self.position = Some((leaf, index));
+      return Ok(true);
+    }
+    match self.sibling_leaf(direction)? {
+      Some(leaf) => self.settle(leaf, direction),
+      None => Ok(self.invalidate()),
+    }
+  }
+
+  /// 停在 `leaf` 沿 `direction` 方向的第一行；叶子为空时继续找相邻叶子
+  fn settle(&mut self, mut leaf: PageId, direction: SortDirection) -> Result<bool> {
+    loop {
+      let num_cells = self.pager.page(leaf)?.num_cells()?;
+      if num_cells > 0 {
+        let index = match direction {
+          SortDirection::Ascending => 0,
+          SortDirection::Descending => num_cells - 1,
+        };
+        self.position = Some((leaf, index));
+        return Ok(true);
+      }
+      match self.sibling_leaf(direction)? {
+        Some(next) => leaf = next,
+        None => return Ok(self.invalidate()),
+      }
+    }
+  }
+
+  /// 回溯路径找到相邻子树并下降到其最靠近当前位置的叶子，已到树的一端时返回 `None`
+  fn sibling_leaf(&mut self, direction: SortDirection) -> Result<Option<PageId>> {
+    while let Some((parent, slot)) = self.path.pop() {
+      let page = self.pager.page(parent)?;
+      let num_cells = page.num_cells()?;
+      let sibling = match direction {
+        SortDirection::Ascending => slot.checked_add(1).filter(|&s| s <= num_cells),
+        SortDirection::Descending => slot.checked_sub(1),
+      };
+      if let Some(slot) = sibling {
+        let child = child_at(&page, slot)?;
+        drop(page);
+        self.path.push((parent, slot));
+        // 进入右侧子树时走最左边，进入左侧子树时走最右边
+        return self.descend_edge(child, direction).map(Some);
+      }
+    }
+    Ok(None)
+  }
+
+  /// 从 `page_id` 一路下降到最左（升序）或最右（降序）的叶子，并记录路径
+  fn descend_edge(&mut self, mut page_id: PageId, direction: SortDirection) -> Result<PageId> {
+    loop {
+      let page = self.pager.page(page_id)?;
+      match page.page_type() {
+        PageType::Leaf => return Ok(page_id),
+        PageType::Internal => {
+          let slot = match direction {
+            SortDirection::Ascending => 0,
+            SortDirection::Descending => page.num_cells()?,
+          };
+          self.path.push((page_id, slot));
+          page_id = child_at(&page, slot)?;
+        }
+        other => return Err(unexpected_page_type(page_id, other)),
+      }
+    }
+  }
+
+  fn invalidate(&mut self) -> bool {
+    self.path.clear();
+    self.position = None;
+    false
+  }
+}
@@ -0,0 +1,124 @@
+//! 数据库头（第 1 页）
+//!
+//! 第 1 页是 `PageType::Meta` 页：32 字节页头之后是数据库头（小端序，与页头一致）：
+//!
+//! ```text
+//! offset  size  字段
+//! 32      16    magic："rdb format 1\0\0\0\0"
+//! 48      4     page_size
+//! 52      4     format_version
+//! 56      4     catalog_root：系统目录 B+Tree 的根页
+//! 60      4     freelist_head：第一个 freelist 页，0 表示没有
+//! ```
+
+use rdb_domain::PageId;
+
+use crate::page::{Page, PageType, PAGE_HEADER_SIZE};
+use crate::pager::{PagerError, Result};
+
+/// 数据库头所在的页
+pub const DB_HEADER_PAGE: PageId = PageId::new(1);
+
+/// 文件魔数
+pub const MAGIC: &[u8; 16] = b"rdb format 1\0\0\0\0";
+
+/// 当前文件格式版本
+pub const FORMAT_VERSION: u32 = 1;
+
+const OFF_MAGIC: usize = PAGE_HEADER_SIZE;
+const OFF_PAGE_SIZE: usize = OFF_MAGIC + 16;
+const OFF_FORMAT_VERSION: usize = OFF_PAGE_SIZE + 4;
+const OFF_CATALOG_ROOT: usize = OFF_FORMAT_VERSION + 4;
+const OFF_FREELIST_HEAD: usize = OFF_CATALOG_ROOT + 4;
+
+/// 数据库头
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DbHeader {
+  pub page_size: u32,
+  pub format_version: u32,
+  /// 系统目录 B+Tree 的根页
+  pub catalog_root: PageId,
+  /// 第一个 freelist 页
+  pub freelist_head: Option<PageId>,
+}
+
+impl DbHeader {
+  /// 新数据库的头
+  pub fn new(page_size: u32, catalog_root: PageId) -> Self {
+    Self { page_size, format_version: FORMAT_VERSION, catalog_root, freelist_head: None }
+  }
+
+  /// 从第 1 页读取并校验数据库头
+  pub fn read(page: &Page<'_>) -> Result<Self> {
+    if page.page_type() != PageType::Meta {
+      return Err(invalid(format!(
+        "page 1 has type {:?}, expected Meta",
+        page.page_type()
+      )));
+    }
+
+    let data = page.data();
+    if &data[OFF_MAGIC..OFF_MAGIC + MAGIC.len()] != MAGIC {
+      return Err(invalid("bad magic".to_string()));
+    }
+
+    let header = Self {
+      page_size: read_u32(data, OFF_PAGE_SIZE),
+      format_version: read_u32(data, OFF_FORMAT_VERSION),
+      catalog_root: PageId::new(read_u32(data, OFF_CATALOG_ROOT)),
+      freelist_head: match read_u32(data, OFF_FREELIST_HEAD) {
+        0 => None,
+        id => Some(PageId::new(id)),
+      },
+    };
+
+    if header.format_version != FORMAT_VERSION {
+      return Err(invalid(format!(
+        "unsupported format version {}",
+        header.format_version
+      )));
+    }
+    if header.catalog_root.into_inner() <= DB_HEADER_PAGE.into_inner() {
+      return Err(invalid(format!(
+        "catalog root {:?} is invalid",
+        header.catalog_root
+      )));
+    }
+
+    Ok(header)
+  }
+
+  /// 把数据库头写入第 1 页（页类型须为 Meta）
+  pub fn write(&self, page: &mut Page<'_>) -> Result<()> {
+    if page.page_type() != PageType::Meta {
+      return Err(invalid(format!(
+        "page 1 has type {:?}, expected Meta",
+        page.page_type()
+      )));
+    }
+
+    let data = page.data_mut();
+    data[OFF_MAGIC..OFF_MAGIC + MAGIC.len()].copy_from_slice(MAGIC);
+    write_u32(data, OFF_PAGE_SIZE, self.page_size);
+    write_u32(data, OFF_FORMAT_VERSION, self.format_version);
+    write_u32(data, OFF_CATALOG_ROOT, self.catalog_root.into_inner());
+    write_u32(
+      data,
+      OFF_FREELIST_HEAD,
+      self.freelist_head.map_or(0, PageId::into_inner),
+    );
+    Ok(())
+  }
+}
+
+fn invalid(message: String) -> PagerError {
+  PagerError::InvalidDbHeader(message)
+}
+
+fn read_u32(data: &[u8; 4096], off: usize) -> u32 {
+  u32::from_le_bytes([data[off], data[off + 1], data[off + 2], data[off + 3]])
+}
+
+fn write_u32(data: &mut [u8; 4096], off: usize, v: u32) {
+  data[off..off + 4].copy_from_slice(&v.to_le_bytes());
+}
@@ -0,0 +1,130 @@
+//! Freelist 页格式
+//!
+//! Freelist 页在 32 字节页头之后的布局（均为小端序）：
+//!
+//! ```text
+//! [count u32][page_id u32 * count ...]            [next u32]
+//!  ^ PAGE_HEADER_SIZE                               ^ PAGE_SIZE - 4
+//! ```
+//!
+//! - `count`：本页记录的空闲页数量
+//! - `page_id`：空闲页 ID，按压入顺序排列，`pop` 从尾部取（LIFO）
+//! - `next`：下一个 freelist 页的 ID，0 表示链表结束
+//!
+//! 单页写满后由调用方（Pager）再分配一个 freelist 页并通过 `next` 串起来。
+
+use rdb_domain::PageId;
+
+use crate::page::{Page, PageError, PageType, PAGE_HEADER_SIZE, PAGE_SIZE};
+
+/// `count` 字段偏移
+const OFF_COUNT: usize = PAGE_HEADER_SIZE;
+
+/// 第一个 page_id 的偏移
+const OFF_IDS: usize = OFF_COUNT + 4;
+
+/// `next` 字段偏移（页尾 4 字节）
+const OFF_NEXT: usize = PAGE_SIZE - 4;
+
+/// 单个 freelist 页最多可记录的空闲页数量
+pub const FREELIST_CAPACITY: usize = (OFF_NEXT - OFF_IDS) / 4;
+
+/// 解码后的 freelist 页内容
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Freelist {
+  /// 下一个 freelist 页
+  pub next: Option<PageId>,
+  /// 本页记录的空闲页（按压入顺序）
+  pub ids: Vec<PageId>,
+}
+
+impl Freelist {
+  /// 读取 freelist 页的全部内容
+  pub fn read(page: &Page) -> Result<Self, PageError> {
+    let count = count(page)?;
+    let ids = (0..count)
+      .map(|i| PageId::new(read_u32(page, id_offset(i))))
+      .collect();
+    Ok(Self { next: next(page)?, ids })
+  }
+
+  /// 把空闲页 `id` 压入本页
+  ///
+  /// 本页已满时返回 `PageFull`，调用方应改为压入新的 freelist 页。
+  pub fn push(page: &mut Page, id: PageId) -> Result<(), PageError> {
+    let count = count(page)?;
+    if count == FREELIST_CAPACITY {
+      return Err(PageError::PageFull { needed: 4, available: 0 });
+    }
+
+    write_u32(page, id_offset(count), id.into_inner());
+    write_u32(page, OFF_COUNT, count as u32 + 1);
+    Ok(())
+  }
+
+  /// 弹出最后压入的空闲页，本页为空时返回 `None`
+  pub fn pop(page: &mut Page) -> Result<Option<PageId>, PageError> {
+    let count = count(page)?;
+    if count == 0 {
+      return Ok(None);
+    }
+
+    let id = read_u32(page, id_offset(count - 1));
+    write_u32(page, OFF_COUNT, count as u32 - 1);
+    Ok(Some(PageId::new(id)))
+  }
+
+  /// 本页是否已写满
+  pub fn is_full(page: &Page) -> Result<bool, PageError> {
+    Ok(count(page)? == FREELIST_CAPACITY)
+  }
+
+  /// 读取下一个 freelist 页
+  pub fn next(page: &Page) -> Result<Option<PageId>, PageError> {
+    next(page)
+  }
+
+  /// 设置下一个 freelist 页（`None` 表示链表结束）
+  pub fn set_next(page: &mut Page, next: Option<PageId>) -> Result<(), PageError> {
+    check_type(page)?;
+    write_u32(page, OFF_NEXT, next.map_or(0, PageId::into_inner));
+    Ok(())
+  }
+}
+
+fn check_type(page: &Page) -> Result<(), PageError> {
+  match page.page_type() {
+    PageType::Freelist => Ok(()),
+    other => Err(PageError::UnsupportedPageType(other)),
+  }
+}
+
+fn count(page: &Page) -> Result<usize, PageError> {
+  check_type(page)?;
+  let count = read_u32(page, OFF_COUNT) as usize;
+  if count > FREELIST_CAPACITY {
+    return Err(PageError::CorruptCell { offset: OFF_COUNT });
+  }
+  Ok(count)
+}
+
+fn next(page: &Page) -> Result<Option<PageId>, PageError> {
+  check_type(page)?;
+  Ok(match read_u32(page, OFF_NEXT) {
+    0 => None,
+    id => Some(PageId::new(id)),
+  })
+}
+
+fn id_offset(i: usize) -> usize {
+  OFF_IDS + i * 4
+}
+
+fn read_u32(page: &Page, off: usize) -> u32 {
+  let b = &page.data()[off..off + 4];
+  u32::from_le_bytes([b[0], b[1], b[2], b[3]])
+}
+
+fn write_u32(page: &mut Page, off: usize, v: u32) {
+  page.data_mut()[off..off + 4].copy_from_slice(&v.to_le_bytes());
+}
@@ -1,5 +1,20 @@
+pub mod alter;
+pub mod blob;
+pub mod btree;
+pub mod catalog;
+pub mod cell;
+pub mod compress;
+pub mod csv;
+pub mod cursor;
+pub mod db_header;
+pub mod freelist;
 pub mod page;
 pub mod pager;
+pub mod record;
+pub mod scan;
+pub mod stats;
+pub mod vacuum;
+pub mod varint;
 
 // #[cfg(test)]
 pub mod test_support;
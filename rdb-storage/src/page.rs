@@ -1,16 +1,23 @@
 use core::fmt;
 use core::marker::PhantomData;
+use core::ops::Range;
 use core::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
 
-use rdb_domain::PageId;
+use rdb_domain::{DomainError, PageId, RowId, TransactionId};
 
-/// 页类型（写入/读取页头的第 0 字节）
+use crate::cell::{self, CHILD_PTR_SIZE};
+use crate::record::ByteOrder;
+use crate::varint;
+
+/// 页类型（写入/读取页头第 0 字节的低 5 位，高 3 位为页头版本，见 `PAGE_HEADER_VERSION`）
 ///
 /// 磁盘编码
 /// - 0x05: Internal
 /// - 0x0D: Leaf
 /// - 0x02: Overflow
 /// - 0x01: Freelist
+/// - 0x10: Meta
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum PageType {
@@ -22,6 +29,8 @@ pub enum PageType {
   Overflow = 0x02,
   /// Freelist 管理页：记录可复用的空闲页
   Freelist = 0x01,
+  /// 数据库头页（固定为第 1 页）：页头之后存放 `DbHeader`
+  Meta = 0x10,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -35,6 +44,80 @@ impl fmt::Display for InvalidPageType {
 
 impl std::error::Error for InvalidPageType {}
 
+/// 页头解码错误
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeaderError {
+  #[error(transparent)]
+  InvalidPageType(#[from] InvalidPageType),
+
+  #[error("unsupported page header version {0} (max {PAGE_HEADER_VERSION})")]
+  UnsupportedHeaderVersion(u8),
+}
+
+/// 页布局校验错误（见 `Page::validate_layout`）
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutError {
+  #[error(transparent)]
+  Header(#[from] HeaderError),
+
+  #[error(
+    "cell_content_area={cell_content_area} overlaps pointer array ending at {pointer_array_end}"
+  )]
+  ContentAreaOverlapsPointers { cell_content_area: usize, pointer_array_end: usize },
+
+  #[error("cell_content_area={cell_content_area} is past the end of the page")]
+  ContentAreaPastEnd { cell_content_area: usize },
+
+  #[error("cell pointer {index} -> {offset} is outside the content area [{cell_content_area}, {PAGE_SIZE})")]
+  CellPointerOutOfRange { index: u16, offset: usize, cell_content_area: usize },
+}
+
+/// 页内 cell 操作错误
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum PageError {
+  #[error("invalid page type: {0}")]
+  InvalidPageType(#[from] InvalidPageType),
+
+  #[error("unsupported page header version {0} (max {PAGE_HEADER_VERSION})")]
+  UnsupportedHeaderVersion(u8),
+
+  #[error("page type {0:?} does not hold cells")]
+  UnsupportedPageType(PageType),
+
+  #[error("page full: need {needed} bytes, {available} available")]
+  PageFull { needed: usize, available: usize },
+
+  #[error("cell index {index} out of range (num_cells={num_cells})")]
+  CellIndexOutOfRange { index: u16, num_cells: u16 },
+
+  #[error("corrupt cell at offset {offset}")]
+  CorruptCell { offset: usize },
+
+  #[error("invalid page layout: cell_content_area={cell_content_area}, num_cells={num_cells}")]
+  InvalidLayout { cell_content_area: usize, num_cells: u16 },
+
+  #[error("page header needs {PAGE_HEADER_SIZE} bytes, got {len}")]
+  HeaderTooShort { len: usize },
+
+  #[error("duplicate rowid in page: {0:?}")]
+  DuplicateRowId(RowId),
+
+  #[error("corrupt page layout: {0}")]
+  Layout(#[from] LayoutError),
+
+  #[error("{0}")]
+  Invariant(#[from] DomainError),
+}
+
+impl From<HeaderError> for PageError {
+  fn from(e: HeaderError) -> Self {
+    match e {
+      HeaderError::InvalidPageType(e) => PageError::InvalidPageType(e),
+      HeaderError::UnsupportedHeaderVersion(v) => PageError::UnsupportedHeaderVersion(v),
+    }
+  }
+}
+
 impl TryFrom<u8> for PageType {
   type Error = InvalidPageType;
 
@@ -44,6 +127,7 @@ impl TryFrom<u8> for PageType {
       0x0D => Ok(Self::Leaf),
       0x02 => Ok(Self::Overflow),
       0x01 => Ok(Self::Freelist),
+      0x10 => Ok(Self::Meta),
       other => Err(InvalidPageType(other)),
     }
   }
@@ -56,9 +140,50 @@ impl PageType {
   }
 }
 
+/// 当前页头版本，写在页类型字节的高 3 位
+///
+/// 版本 0 表示引入版本号之前写入的页，布局与版本 1 相同，解码时照常接受。
+pub const PAGE_HEADER_VERSION: u8 = 1;
+
+// 页类型字节：低 5 位为页类型，高 3 位为页头版本
+const PAGE_TYPE_MASK: u8 = 0x1F;
+const HEADER_VERSION_SHIFT: u8 = 5;
+
+/// 拆分页类型字节并校验，返回 `(页类型, 页头版本)`
+fn decode_type_byte(byte: u8) -> Result<(PageType, u8), HeaderError> {
+  let page_type = PageType::try_from(byte & PAGE_TYPE_MASK).map_err(|_| InvalidPageType(byte))?;
+  let version = byte >> HEADER_VERSION_SHIFT;
+  if version > PAGE_HEADER_VERSION {
+    return Err(HeaderError::UnsupportedHeaderVersion(version));
+  }
+  Ok((page_type, version))
+}
+
+/// 页头字段的字节序（小端）；注意 record 内部是大端，见 `record::RECORD_BYTE_ORDER`
+pub const PAGE_HEADER_BYTE_ORDER: ByteOrder = ByteOrder::LittleEndian;
+
 // 页头固定为 32 字节（0x20）
 pub const PAGE_HEADER_SIZE: usize = 32;
 
+// 页大小（当前固定 4096）
+pub const PAGE_SIZE: usize = 4096;
+
+// cell pointer array 每项 2 字节（小端序，指向 cell 在页内的偏移）
+pub const CELL_POINTER_SIZE: usize = 2;
+
+/// 叶子页中单个 payload 可以就地存放的最大字节数，超过则需要溢出页
+///
+/// 与 SQLite 固定 `U - 35` 不同，这里保证一个叶子 cell（payload + rowid/长度两个 varint + 指针）
+/// 不超过可用空间（页大小减页头）的 1/4，这样 B+Tree 分裂后两侧总能放下。
+pub const MAX_LOCAL_PAYLOAD: usize =
+  (PAGE_SIZE - PAGE_HEADER_SIZE) / 4 - CELL_POINTER_SIZE - 2 * varint::MAX_VARINT_LEN;
+
+// 页头碎片计数的上限（与 SQLite 相同）。再产生碎片会越过上限时改为整理整页，计数不会截断或饱和
+const MAX_FRAGMENTED_BYTES: usize = 60;
+
+// cell pointer 的最高位：叶子 cell 已被软删除（墓碑）。页内偏移 < 4096，最高位恒为空闲
+const TOMBSTONE_FLAG: u16 = 0x8000;
+
 // 各字段在页头中的固定便宜（byte offset)
 pub const OFF_PAGE_TYPE: usize = 0x0000; // 页类型（1 byte
 pub(crate) const OFF_FIRST_FREEBLOCK: usize = 0x0001; // 第一个空闲块偏移（2 bytes）
@@ -66,9 +191,28 @@ pub(crate) const OFF_NUM_CELLS: usize = 0x0003; // 页内 cell 数量（2 bytes
 pub(crate) const OFF_CELL_CONTENT_AREA: usize = 0x0005; // cell 内容区域起始偏移（2 bytes）
 pub(crate) const OFF_FRAGMENTED_BYTES: usize = 0x0007; // 碎片字节数（1 byte）
 pub(crate) const OFF_RIGHT_CHILD: usize = 0x0008; // 仅内部节点：最右子页 ID（4 bytes）
-pub(crate) const OFF_LSN: usize = 0x000C; // MVCC: 日志序列号（8 bytes）
+pub(crate) const OFF_LSN: usize = 0x000C; // WAL 日志序号（8 bytes）
 pub(crate) const OFF_CHECKSUM: usize = 0x0014; // CRC32 校验和（4 bytes）
-pub(crate) const OFF_RESERVED: usize = 0x0018; // 预留用于集群元数据（8 bytes）
+pub(crate) const OFF_RESERVED: usize = 0x0018; // 事务 stamp 或集群元数据（8 bytes）
+
+/// `Page::defragment` 的整理统计
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DefragmentStats {
+  /// 并入连续空闲区的字节数（原 freeblock 与碎片）
+  pub reclaimed_bytes: usize,
+  /// 位置发生变化的 cell 数
+  pub cells_moved: u16,
+}
+
+/// `Page::iter_cells` 的遍历方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CellScan {
+  /// 包含已软删除的 cell
+  #[default]
+  All,
+  /// 跳过已软删除的 cell
+  SkipTombstones,
+}
 
 /// 页头（逻辑结构）
 ///
@@ -82,17 +226,49 @@ pub struct PageHeader {
   pub cell_content_area: u16,
   pub fragmented_bytes: u8,
   pub right_child: u32, // 仅 Internal 有意义；其它页一般写 0
-  pub lsn: u64,         // 预留
+  pub lsn: u64,         // WAL 日志序号，见 Page::lsn
   pub checksum: u32,    // 预留/或后续做 CRC32
-  pub reserved: u64,    // 预留用于集群元数据
+  pub reserved: u64,    // 事务 stamp（见 Page::txn）或集群元数据，见 PageHeader::reserved_bytes
 }
 
 impl PageHeader {
   pub const SIZE: usize = PAGE_HEADER_SIZE;
 
+  /// reserved 字段的原始字节（磁盘上的小端序布局）
+  ///
+  /// 该字段留给复制/集群层存放元数据，存储层本身不解释其内容。
+  /// 它同时是 `Page::txn` 事务 stamp 的存储位置，两种用途只能择一。
+  /// 注意它没有单独的校验和，只能依赖整页 checksum（尚未实现）发现损坏。
+  pub fn reserved_bytes(&self) -> [u8; 8] {
+    self.reserved.to_le_bytes()
+  }
+
+  /// reserved 前 4 字节：节点 ID
+  pub fn node_id(&self) -> u32 {
+    self.reserved as u32
+  }
+
+  /// reserved 后 4 字节：epoch
+  pub fn epoch(&self) -> u32 {
+    (self.reserved >> 32) as u32
+  }
+
+  /// 设置节点 ID（不影响 epoch）
+  pub fn set_node_id(&mut self, node_id: u32) {
+    self.reserved = (self.reserved & !u64::from(u32::MAX)) | u64::from(node_id);
+  }
+
+  /// 设置 epoch（不影响节点 ID）
+  pub fn set_epoch(&mut self, epoch: u32) {
+    self.reserved = (self.reserved & u64::from(u32::MAX)) | (u64::from(epoch) << 32);
+  }
+
   /// 从"页头 32 字节"解析出 PageHeader（小端序）
-  pub fn decode(buf: &[u8; PAGE_HEADER_SIZE]) -> Result<Self, InvalidPageType> {
-    let page_type = PageType::try_from(buf[OFF_PAGE_TYPE])?;
+  ///
+  /// 页类型非法返回 `InvalidPageType`，页头版本高于 `PAGE_HEADER_VERSION` 返回
+  /// `UnsupportedHeaderVersion`（由更新的格式写入，布局可能已变化）。
+  pub fn decode(buf: &[u8; PAGE_HEADER_SIZE]) -> Result<Self, HeaderError> {
+    let (page_type, _) = decode_type_byte(buf[OFF_PAGE_TYPE])?;
 
     Ok(Self {
       page_type,
@@ -112,7 +288,7 @@ impl PageHeader {
     // 先清零，避免旧数据残留（尤其 reserved / right_child 这类字段）
     *buf = [0u8; PAGE_HEADER_SIZE];
 
-    buf[OFF_PAGE_TYPE] = self.page_type.as_u8();
+    buf[OFF_PAGE_TYPE] = self.page_type.as_u8() | (PAGE_HEADER_VERSION << HEADER_VERSION_SHIFT);
     write_u16_le(buf, OFF_FIRST_FREEBLOCK, self.first_freeblock);
     write_u16_le(buf, OFF_NUM_CELLS, self.num_cells);
     write_u16_le(buf, OFF_CELL_CONTENT_AREA, self.cell_content_area);
@@ -124,6 +300,43 @@ impl PageHeader {
   }
 }
 
+/// 按字节均分的分裂位置：累计大小（含 cell pointer）首次达到总量一半时左侧的 cell 数
+///
+/// 结果限制在 `1..=len-1`，保证两侧都非空；少于两个 cell 时返回 `len`。
+pub(crate) fn split_point_for_sizes(sizes: &[usize]) -> usize {
+  if sizes.len() < 2 {
+    return sizes.len();
+  }
+  let total: usize = sizes.iter().map(|s| s + CELL_POINTER_SIZE).sum();
+  let mut acc = 0;
+  let mut split = sizes.len() - 1;
+  for (i, size) in sizes.iter().enumerate() {
+    acc += size + CELL_POINTER_SIZE;
+    if acc * 2 >= total {
+      split = i + 1;
+      break;
+    }
+  }
+  split.clamp(1, sizes.len() - 1)
+}
+
+/// `num_cells` 个 cell 时 pointer array 的结束偏移
+fn pointer_array_end(num_cells: u16) -> usize {
+  PAGE_HEADER_SIZE + num_cells as usize * CELL_POINTER_SIZE
+}
+
+/// 从任意字节切片的前 32 字节解析页头，不构造 `Page`
+///
+/// 只需要页头时（工具、完整性检查）可避免复制整页 4KB。
+/// 切片不足 32 字节返回 `HeaderTooShort`，其余错误同 `PageHeader::decode`。
+pub fn decode_header(bytes: &[u8]) -> Result<PageHeader, PageError> {
+  let buf: &[u8; PAGE_HEADER_SIZE] = bytes
+    .get(..PAGE_HEADER_SIZE)
+    .and_then(|b| b.try_into().ok())
+    .ok_or(PageError::HeaderTooShort { len: bytes.len() })?;
+  Ok(PageHeader::decode(buf)?)
+}
+
 // ---- 小端序读写工具（只操作 buf，不做任何 unsafe）----
 
 fn read_u16_le(buf: &[u8; PAGE_HEADER_SIZE], off: usize) -> u16 {
@@ -192,7 +405,9 @@ pub struct Page<'page> {
   pub(crate) dirty: bool,
 
   /// Pin 计数：>0 表示该页正在被使用，不能被缓存淘汰
-  pub(crate) pin_count: AtomicU32,
+  ///
+  /// 用 Arc 共享给 `PinGuard`，守卫不借用 Pager 也能在 drop 时减计数
+  pub(crate) pin_count: Arc<AtomicU32>,
 
   /// 把生命周期 `'page` 绑定到这个类型上（后续 Pager/BufferPoll 会用到）
   pub(crate) _phantom: PhantomData<&'page mut ()>,
@@ -205,7 +420,7 @@ impl<'page> Page<'page> {
       data: [0u8; 4096],
       page_id,
       dirty: false,
-      pin_count: AtomicU32::new(0),
+      pin_count: Arc::new(AtomicU32::new(0)),
       _phantom: PhantomData,
     };
 
@@ -231,12 +446,27 @@ impl<'page> Page<'page> {
     page
   }
 
-  /// 从磁盘字节载入一个页（会校验第 0 字节的 page_type 是否合法）
-  pub fn from_bytes(page_id: PageId, data: [u8; 4096]) -> Result<Self, InvalidPageType> {
+  /// 从磁盘字节载入一个页（会校验第 0 字节的 page_type 和页头版本是否合法）
+  pub fn from_bytes(page_id: PageId, data: [u8; 4096]) -> Result<Self, HeaderError> {
     // 校验页类型字节，避免后续解析把坏页当好页
-    let _ = PageType::try_from(data[OFF_PAGE_TYPE])?;
+    decode_type_byte(data[OFF_PAGE_TYPE])?;
 
-    Ok(Self { data, page_id, dirty: false, pin_count: AtomicU32::new(0), _phantom: PhantomData })
+    Ok(Self::from_bytes_unchecked(page_id, data))
+  }
+
+  /// 从字节构造页，不校验 page_type 字节和页头版本（供数据恢复/导入工具使用）
+  ///
+  /// 风险：类型字节未知时 `page_type` 返回兜底的 `Freelist`，按此分派的代码（B+Tree、
+  /// Freelist 等）会把它当成别的页处理，写回磁盘就会静默损坏文件。不会引起内存不安全，
+  /// 因此没有标记为 `unsafe`；调用方应先用 `page_type_checked` 确认类型再交给其他模块。
+  pub fn from_bytes_unchecked(page_id: PageId, data: [u8; 4096]) -> Self {
+    Self {
+      data,
+      page_id,
+      dirty: false,
+      pin_count: Arc::new(AtomicU32::new(0)),
+      _phantom: PhantomData,
+    }
   }
 
   #[inline]
@@ -246,73 +476,717 @@ impl<'page> Page<'page> {
 
   /// 返回页类型（因为 from_bytes/new 已保证合法，所以这里不需要 Result）
   pub fn page_type(&self) -> PageType {
-    // SAFETY: new()/from_bytes 保证 data[0] 必定合法 page_type
-    match self.data[OFF_PAGE_TYPE] {
+    // SAFETY: new()/from_bytes 保证 data[0] 必定合法 page_type；
+    // from_bytes_unchecked 构造的页可能不合法，见 page_type_checked
+    match self.data[OFF_PAGE_TYPE] & PAGE_TYPE_MASK {
       0x05 => PageType::Internal,
       0x0D => PageType::Leaf,
       0x02 => PageType::Overflow,
       0x01 => PageType::Freelist,
+      0x10 => PageType::Meta,
       _ => PageType::Freelist, // 理论上到不了；为了避免 panic/unwrap，写个兜底
     }
   }
 
+  /// 校验并返回页类型：类型字节未知或页头版本过新时返回错误
+  ///
+  /// 对 `new`/`from_bytes` 构造的页总是成功；用于检查 `from_bytes_unchecked` 载入的页。
+  pub fn page_type_checked(&self) -> Result<PageType, HeaderError> {
+    decode_type_byte(self.data[OFF_PAGE_TYPE]).map(|(page_type, _)| page_type)
+  }
+
+  /// 本页单个 payload 可以就地存放的最大字节数
+  ///
+  /// 叶子页为 `MAX_LOCAL_PAYLOAD`；其他页类型不存放 payload，返回 0。
+  pub fn max_local_payload(&self) -> usize {
+    match self.page_type() {
+      PageType::Leaf => MAX_LOCAL_PAYLOAD,
+      PageType::Internal | PageType::Overflow | PageType::Freelist | PageType::Meta => 0,
+    }
+  }
+
+  /// 长度为 `payload_len` 的 payload 是否需要溢出页
+  pub fn needs_overflow(&self, payload_len: usize) -> bool {
+    payload_len > self.max_local_payload()
+  }
+
+  /// 页头版本（0 表示引入版本号之前写入的页）
+  pub fn header_version(&self) -> u8 {
+    self.data[OFF_PAGE_TYPE] >> HEADER_VERSION_SHIFT
+  }
+
   #[inline]
   pub(crate) fn mark_dirty(&mut self) {
     self.dirty = true;
   }
 
+  /// 页内容是否被修改、尚未刷盘
+  #[inline]
+  pub fn is_dirty(&self) -> bool {
+    self.dirty
+  }
+
+  /// 清除脏标记：页内容已由外部写回磁盘时调用
+  #[inline]
+  pub fn mark_clean(&mut self) {
+    self.dirty = false;
+  }
+
+  /// 只读访问页字节（不影响脏标记）
   #[inline]
   pub fn data(&self) -> &[u8; 4096] {
     &self.data
   }
 
+  /// 可写访问页字节：调用即表示"将要修改"，无条件标记脏页
+  ///
+  /// 只读场景请使用 `data()`，避免产生多余的刷盘。
   #[inline]
-  pub(crate) fn data_mut(&mut self) -> &mut [u8; 4096] {
+  pub fn data_mut(&mut self) -> &mut [u8; 4096] {
     self.mark_dirty();
     &mut self.data
   }
 
-  // （可选）后面做 BufferPool 会用到：pin/unpin
-  #[allow(dead_code)]
-  pub(crate) fn pin(&self) {
-    self.pin_count.fetch_add(1, Ordering::Relaxed);
+  /// 当前 pin 计数
+  pub fn pin_count(&self) -> u32 {
+    self.pin_count.load(Ordering::Relaxed)
   }
 
-  #[allow(dead_code)]
-  pub(crate) fn unpin(&self) {
-    self.pin_count.fetch_sub(1, Ordering::Relaxed);
+  /// pin 计数加一，返回共享的计数器（由 `PinGuard` 在 drop 时减一）
+  pub(crate) fn pin(&self) -> Arc<AtomicU32> {
+    self.pin_count.fetch_add(1, Ordering::Relaxed);
+    Arc::clone(&self.pin_count)
   }
 
   /// 安全版：推荐内部都用这个（不吞错误）
-  pub fn try_parse_header(&self) -> Result<PageHeader, InvalidPageType> {
+  pub fn try_parse_header(&self) -> Result<PageHeader, HeaderError> {
     let mut buf = [0u8; PAGE_HEADER_SIZE];
     buf.copy_from_slice(&self.data[..PAGE_HEADER_SIZE]);
     PageHeader::decode(&buf)
   }
 
-  /// 兼容 spec 草图：返回 PageHeader（遇到坏页类型会退成一个“最保守的 header”）
-  pub(crate) fn parse_header(&self) -> PageHeader {
-    match self.try_parse_header() {
-      Ok(h) => h,
-      Err(_) => PageHeader {
-        page_type: PageType::Freelist, // 兜底：避免继续按 BTree 页解析
-        first_freeblock: 0,
-        num_cells: 0,
-        cell_content_area: 4096,
-        fragmented_bytes: 0,
-        right_child: 0,
-        lsn: 0,
-        checksum: 0,
-        reserved: 0,
-      },
-    }
-  }
-
-  /// 写入页头：写回 data[0..32] 并标记脏页
+  /// 写入页头：写回 data[0..32]，仅在字节实际变化时标记脏页
   pub fn write_header(&mut self, header: &PageHeader) {
     let mut buf = [0u8; PAGE_HEADER_SIZE];
     header.encode(&mut buf);
-    self.data[..PAGE_HEADER_SIZE].copy_from_slice(&buf);
+    if self.data[..PAGE_HEADER_SIZE] != buf {
+      self.data[..PAGE_HEADER_SIZE].copy_from_slice(&buf);
+      self.mark_dirty();
+    }
+  }
+
+  /// 页头中的 WAL 日志序号（刷盘前的 flush barrier 据此确保日志已落盘）
+  pub fn lsn(&self) -> u64 {
+    self.header_u64(OFF_LSN)
+  }
+
+  /// 最后写入本页的事务 ID（存放在页头 reserved 字段，未写入过时为 0）
+  ///
+  /// 供后续 MVCC 层做乐观并发检测：读时记下 stamp，写回前比较是否被其他事务改过。
+  /// 不占用 lsn 字段，盖章不会影响 WAL 顺序。
+  pub fn txn(&self) -> TransactionId {
+    TransactionId::new(self.header_u64(OFF_RESERVED))
+  }
+
+  /// 用事务 ID 标记本页（写入页头 reserved 字段），仅在值变化时标记脏页
+  pub fn set_txn(&mut self, txn: TransactionId) {
+    self.write_header_bytes(OFF_RESERVED, &txn.into_inner().to_le_bytes());
+  }
+
+  /// 直接写入页头 reserved 字段的 8 个字节（见 `PageHeader::reserved_bytes`），
+  /// 仅在值变化时标记脏页
+  pub fn set_reserved(&mut self, bytes: [u8; 8]) {
+    self.write_header_bytes(OFF_RESERVED, &bytes);
+  }
+
+  fn header_u64(&self, off: usize) -> u64 {
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(&self.data[off..off + 8]);
+    u64::from_le_bytes(buf)
+  }
+
+  fn write_header_bytes(&mut self, off: usize, bytes: &[u8]) {
+    let target = &mut self.data[off..off + bytes.len()];
+    if target != bytes {
+      target.copy_from_slice(bytes);
+      self.mark_dirty();
+    }
+  }
+
+  // ---- cell 操作（仅 Internal/Leaf 页）----
+  //
+  // 页内布局：
+  // [header 32B][cell pointer array →]  ...空闲...  [← cell content area][页尾]
+  //
+  // 删除 cell 释放的空间：>= 4 字节挂到 freeblock 链表（每块前 4 字节为 next u16 + size u16），
+  // 更小的碎片计入 fragmented_bytes（不超过 MAX_FRAGMENTED_BYTES）；
+  // 空间不足时 insert_cell 会先 defragment 再分配。
+  //
+  // 软删除：叶子 cell 的 pointer 最高位为墓碑标记，cell 仍占用空间，vacuum 时才真正删除。
+
+  /// 页内 cell 数量
+  pub fn num_cells(&self) -> Result<u16, PageError> {
+    Ok(self.try_parse_header()?.num_cells)
+  }
+
+  /// 未软删除的 cell 数量（只读取 cell pointer，不读 cell 内容）
+  pub fn num_live_cells(&self) -> Result<u16, PageError> {
+    let header = self.cell_header()?;
+    let live = (0..header.num_cells)
+      .filter(|&index| self.cell_pointer(index) & TOMBSTONE_FLAG == 0)
+      .count();
+    Ok(live as u16)
+  }
+
+  /// 返回第 `index` 个 cell 的字节
+  pub fn cell(&self, index: u16) -> Result<&[u8], PageError> {
+    Ok(&self.data[self.cell_range(index)?])
+  }
+
+  /// 第 `index` 个 cell 在页内的字节范围
+  pub(crate) fn cell_range(&self, index: u16) -> Result<Range<usize>, PageError> {
+    let header = self.cell_header()?;
+    let offset = self.cell_offset(&header, index)?;
+    let size = self.cell_size_at(header.page_type, offset)?;
+    Ok(offset..offset + size)
+  }
+
+  /// 按页内顺序遍历 cell，返回 `(index, cell 字节)`
+  ///
+  /// `CellScan::SkipTombstones` 跳过已软删除的 cell，`index` 仍是原始下标。
+  pub fn iter_cells(
+    &self,
+    scan: CellScan,
+  ) -> Result<impl Iterator<Item = Result<(u16, &[u8]), PageError>> + '_, PageError> {
+    let header = self.cell_header()?;
+    Ok((0..header.num_cells).filter_map(move |index| {
+      if scan == CellScan::SkipTombstones && self.cell_pointer(index) & TOMBSTONE_FLAG != 0 {
+        return None;
+      }
+      Some(self.cell(index).map(|cell| (index, cell)))
+    }))
+  }
+
+  /// 第 `index` 个 cell 是否已被软删除
+  pub fn is_tombstoned(&self, index: u16) -> Result<bool, PageError> {
+    let header = self.cell_header()?;
+    self.cell_offset(&header, index)?;
+    Ok(self.cell_pointer(index) & TOMBSTONE_FLAG != 0)
+  }
+
+  /// 软删除第 `index` 个叶子 cell
+  ///
+  /// 只在 cell pointer 上打墓碑标记，cell 字节和 `num_cells` 不变；
+  /// 空间在 `vacuum` 时才回收。仅支持叶子页。
+  pub fn tombstone_cell(&mut self, index: u16) -> Result<(), PageError> {
+    let header = self.cell_header()?;
+    if header.page_type != PageType::Leaf {
+      return Err(PageError::UnsupportedPageType(header.page_type));
+    }
+    let offset = self.cell_offset(&header, index)?;
+    self.write_cell_pointer(index, offset as u16 | TOMBSTONE_FLAG);
     self.mark_dirty();
+    Ok(())
+  }
+
+  /// 物理删除所有已软删除的 cell，返回删除的数量
+  pub fn vacuum(&mut self) -> Result<u16, PageError> {
+    let header = self.cell_header()?;
+    let mut removed = 0;
+    // 从后往前删，前面的下标不受影响
+    for index in (0..header.num_cells).rev() {
+      if self.cell_pointer(index) & TOMBSTONE_FLAG != 0 {
+        self.remove_cell(index)?;
+        removed += 1;
+      }
+    }
+    Ok(removed)
+  }
+
+  /// 按 rowid 二分查找 cell（要求 cell 按 rowid 升序排列）
+  ///
+  /// 返回 `Ok(Ok(index))` 表示找到；`Ok(Err(index))` 表示不存在，`index` 为保持有序的插入位置。
+  /// 只解码每个候选 cell 开头的 rowid（内部页跳过 4 字节子页指针），比较次数为 O(log n)。
+  /// 内部页上的 `Err(index)` 恰好是第一个 key > rowid 的 cell，即 rowid 所属子树的槽位。
+  pub fn find_cell_by_rowid(&self, rowid: RowId) -> Result<Result<usize, usize>, PageError> {
+    let header = self.cell_header()?;
+    let (mut lo, mut hi) = (0usize, usize::from(header.num_cells));
+
+    while lo < hi {
+      let mid = lo + (hi - lo) / 2;
+      let offset = self.cell_offset(&header, mid as u16)?;
+      match self.cell_rowid_at(header.page_type, offset)?.cmp(&rowid) {
+        core::cmp::Ordering::Less => lo = mid + 1,
+        core::cmp::Ordering::Greater => hi = mid,
+        core::cmp::Ordering::Equal => return Ok(Ok(mid)),
+      }
+    }
+
+    Ok(Err(lo))
+  }
+
+  /// 返回第 `index` 个 cell 的可变字节（只允许原地修改，不能改变长度）
+  pub(crate) fn cell_mut(&mut self, index: u16) -> Result<&mut [u8], PageError> {
+    let header = self.cell_header()?;
+    let offset = self.cell_offset(&header, index)?;
+    let size = self.cell_size_at(header.page_type, offset)?;
+    self.mark_dirty();
+    Ok(&mut self.data[offset..offset + size])
+  }
+
+  /// 按字节而不是按 cell 数选择分裂位置：返回留在左页的 cell 数
+  ///
+  /// 累计 cell 大小（含 cell pointer）首次达到已用空间一半处即为分裂点，
+  /// 结果在 `1..=num_cells-1` 之间；cell 大小差异很大时比 `num_cells / 2` 更均衡。
+  /// 少于两个 cell 时返回 `num_cells`（无法分裂）。
+  pub fn split_point_by_size(&self) -> Result<u16, PageError> {
+    let header = self.cell_header()?;
+    let mut sizes = Vec::with_capacity(usize::from(header.num_cells));
+    for index in 0..header.num_cells {
+      let offset = self.cell_offset(&header, index)?;
+      sizes.push(self.cell_size_at(header.page_type, offset)?);
+    }
+    // 结果不超过 num_cells，转换不会截断
+    Ok(split_point_for_sizes(&sizes) as u16)
+  }
+
+  /// 校验 cell 布局：`PAGE_HEADER_SIZE + 2 * num_cells <= cell_content_area <= PAGE_SIZE`，
+  /// 且每个 cell pointer（去掉墓碑标记后）都落在 `[cell_content_area, PAGE_SIZE)` 内
+  ///
+  /// 损坏的页可能让 content area 伸进 pointer array，或让 pointer 指向页头/pointer array，
+  /// 之后的读取会把这些字节当作 cell 解析。不保存 cell 的页类型（溢出页、freelist 页等）总是通过。
+  pub fn validate_layout(&self) -> Result<(), LayoutError> {
+    self.validate_header()?;
+    let header = self.try_parse_header()?;
+    if !matches!(header.page_type, PageType::Internal | PageType::Leaf) {
+      return Ok(());
+    }
+
+    let cell_content_area = header.cell_content_area as usize;
+    for index in 0..header.num_cells {
+      let offset = (self.cell_pointer(index) & !TOMBSTONE_FLAG) as usize;
+      if !(cell_content_area..PAGE_SIZE).contains(&offset) {
+        return Err(LayoutError::CellPointerOutOfRange { index, offset, cell_content_area });
+      }
+    }
+    Ok(())
+  }
+
+  /// 只校验页头自身是否一致：类型字节合法，且
+  /// `PAGE_HEADER_SIZE + 2 * num_cells <= cell_content_area <= PAGE_SIZE`
+  ///
+  /// 是 `validate_layout` 的前半部分，不读取 cell pointer。
+  pub fn validate_header(&self) -> Result<(), LayoutError> {
+    let header = self.try_parse_header()?;
+    if !matches!(header.page_type, PageType::Internal | PageType::Leaf) {
+      return Ok(());
+    }
+
+    let cell_content_area = header.cell_content_area as usize;
+    let pointer_array_end = pointer_array_end(header.num_cells);
+    if cell_content_area < pointer_array_end {
+      return Err(LayoutError::ContentAreaOverlapsPointers {
+        cell_content_area,
+        pointer_array_end,
+      });
+    }
+    if cell_content_area > PAGE_SIZE {
+      return Err(LayoutError::ContentAreaPastEnd { cell_content_area });
+    }
+    Ok(())
+  }
+
+  /// 页内可用空间（未分配区 + freeblock + 碎片），已扣除页头和 cell pointer array
+  pub fn free_space(&self) -> Result<usize, PageError> {
+    let header = self.cell_header()?;
+    let mut free = self.unallocated_space(&header);
+    for (_, size) in self.freeblock_list(&header)? {
+      free += size as usize;
+    }
+    Ok(free + header.fragmented_bytes as usize)
+  }
+
+  /// 遍历 freeblock 链表，按链表顺序返回 `(offset, size)`
+  ///
+  /// 链表越界或成环时返回 `CorruptCell`。
+  pub fn free_blocks(&self) -> Result<impl Iterator<Item = (u16, u16)>, PageError> {
+    let header = self.cell_header()?;
+    Ok(self.freeblock_list(&header)?.into_iter())
+  }
+
+  /// 在第 `index` 个位置插入 cell（原 `index..` 的 cell 后移）
+  ///
+  /// 与 SQLite 一样优先复用 freeblock：选能放下 cell 的最小块（best fit），
+  /// 从块尾部切出 cell，剩余不足 4 字节时计入碎片（碎片计数会越过上限的块不参与选择）。
+  /// 没有合适的块时从 content area 分配；
+  /// 空间不足时先整理碎片，整理后仍放不下返回 `PageFull`。
+  pub fn insert_cell(&mut self, index: u16, cell: &[u8]) -> Result<(), PageError> {
+    let mut header = self.cell_header()?;
+    if index > header.num_cells {
+      return Err(PageError::CellIndexOutOfRange { index, num_cells: header.num_cells });
+    }
+
+    let needed = cell.len() + CELL_POINTER_SIZE;
+    // pointer array 多出的一项只能来自未分配区
+    let from_freeblock = if self.unallocated_space(&header) >= CELL_POINTER_SIZE {
+      self.take_freeblock(&mut header, cell.len())?
+    } else {
+      None
+    };
+
+    let offset = match from_freeblock {
+      Some(offset) => offset,
+      None => {
+        if self.unallocated_space(&header) < needed {
+          let available = self.free_space()?;
+          if available < needed {
+            return Err(PageError::PageFull { needed, available });
+          }
+          self.defragment()?;
+          header = self.cell_header()?;
+        }
+
+        // 在 content area 头部分配 cell，且不能覆盖（多出一项的）pointer array
+        let offset = (header.cell_content_area as usize)
+          .checked_sub(cell.len())
+          .filter(|&offset| offset >= pointer_array_end(header.num_cells + 1))
+          .ok_or(PageError::InvalidLayout {
+            cell_content_area: header.cell_content_area as usize,
+            num_cells: header.num_cells,
+          })?;
+        header.cell_content_area = offset as u16;
+        offset
+      }
+    };
+    self.data[offset..offset + cell.len()].copy_from_slice(cell);
+
+    // pointer array 中 index.. 后移 2 字节
+    let ptr = PAGE_HEADER_SIZE + index as usize * CELL_POINTER_SIZE;
+    let end = pointer_array_end(header.num_cells);
+    self.data.copy_within(ptr..end, ptr + CELL_POINTER_SIZE);
+    self.write_cell_pointer(index, offset as u16);
+
+    header.num_cells += 1;
+    self.write_header(&header);
+    Ok(())
+  }
+
+  /// 从 freeblock 链表中按 best fit 切出 `len` 字节，返回其偏移；没有能放下的块时返回 `None`
+  ///
+  /// 只修改页内链表和传入的 `header`，页头由调用方写回。
+  /// 与 SQLite 一样，切出后剩余的零头会让碎片计数超过 `MAX_FRAGMENTED_BYTES` 的块不会被选中。
+  fn take_freeblock(
+    &mut self,
+    header: &mut PageHeader,
+    len: usize,
+  ) -> Result<Option<usize>, PageError> {
+    let blocks = self.freeblock_list(header)?;
+    let fits = |size: usize| {
+      size >= len
+        && (size - len >= 4
+          || header.fragmented_bytes as usize + size - len <= MAX_FRAGMENTED_BYTES)
+    };
+    let Some(k) = (0..blocks.len())
+      .filter(|&k| fits(blocks[k].1 as usize))
+      .min_by_key(|&k| blocks[k].1)
+    else {
+      return Ok(None);
+    };
+    let (offset, size) = (blocks[k].0 as usize, blocks[k].1 as usize);
+    if offset + size > PAGE_SIZE {
+      return Err(PageError::CorruptCell { offset });
+    }
+
+    let remaining = size - len;
+    if remaining >= 4 {
+      // 保留块的前半部分，只改大小
+      self.data[offset + 2..offset + 4].copy_from_slice(&(remaining as u16).to_le_bytes());
+    } else {
+      // 整块摘出链表，零头计入碎片
+      let next = [self.data[offset], self.data[offset + 1]];
+      match k.checked_sub(1) {
+        None => header.first_freeblock = u16::from_le_bytes(next),
+        Some(prev) => {
+          let prev = blocks[prev].0 as usize;
+          self.data[prev..prev + 2].copy_from_slice(&next);
+        }
+      }
+      // fits 已保证不超过上限
+      header.fragmented_bytes += remaining as u8;
+    }
+    Ok(Some(offset + remaining))
+  }
+
+  /// 按 cell 的 rowid 插入到保持升序的位置，返回插入后的 cell 下标
+  ///
+  /// 位置由 `find_cell_by_rowid` 二分查找得到，调用者无需自己计算；
+  /// cell 格式需与页类型一致（内部页 cell 以 4 字节子页指针开头）。
+  /// rowid 已存在时返回 `DuplicateRowId`，空间不足时同 `insert_cell`。
+  /// 插入前确认 rowid 顺序（见 `verify_insert_order`），失败时页保持不变。
+  pub fn insert_cell_ordered(&mut self, cell: &[u8]) -> Result<u16, PageError> {
+    let header = self.cell_header()?;
+    let start = match header.page_type {
+      PageType::Internal => CHILD_PTR_SIZE,
+      _ => 0,
+    };
+    let (rowid, _) = cell
+      .get(start..)
+      .and_then(varint::decode)
+      .ok_or(PageError::CorruptCell { offset: 0 })?;
+    let rowid = RowId::new(rowid as i64);
+
+    // cell 数量不超过 u16，下标转换不会截断
+    let index = match self.find_cell_by_rowid(rowid)? {
+      Ok(_) => return Err(PageError::DuplicateRowId(rowid)),
+      Err(index) => index as u16,
+    };
+    self.verify_insert_order(index, rowid)?;
+    self.insert_cell(index, cell)?;
+    Ok(index)
+  }
+
+  /// 检查 cell 是否按 rowid 严格递增（B+Tree 的二分查找依赖这一不变量）
+  ///
+  /// 违反时返回 `InvariantViolation`，指出第一个乱序的 cell；cell 无法解析同样视为违反。
+  /// 不存放 cell 的页类型总是返回 `Ok`。
+  pub fn check_rowid_order(&self) -> Result<(), DomainError> {
+    let violation = |detail: String| DomainError::InvariantViolation {
+      message: format!("page {:?}: {detail}", self.page_id),
+    };
+    let header = match self.cell_header() {
+      Ok(header) => header,
+      Err(PageError::UnsupportedPageType(_)) => return Ok(()),
+      Err(e) => return Err(violation(e.to_string())),
+    };
+
+    let mut prev = None;
+    for index in 0..header.num_cells {
+      let rowid = self
+        .cell_offset(&header, index)
+        .and_then(|offset| self.cell_rowid_at(header.page_type, offset))
+        .map_err(|e| violation(e.to_string()))?;
+      if let Some(prev) = prev.filter(|&prev| prev >= rowid) {
+        return Err(violation(format!(
+          "rowid {rowid:?} at cell {index} is not greater than {prev:?}"
+        )));
+      }
+      prev = Some(rowid);
+    }
+    Ok(())
+  }
+
+  /// 插入前确认在第 `index` 个位置放入 `rowid` 后 rowid 仍然有序：
+  /// 开启 `strict` feature 时返回错误，否则只在 debug 构建中断言
+  ///
+  /// 只读检查，调用方在它通过之后才修改页，因此失败时不会留下写了一半的脏页。
+  pub(crate) fn verify_insert_order(&self, index: u16, rowid: RowId) -> Result<(), PageError> {
+    if cfg!(feature = "strict") {
+      self.check_insert_order(index, rowid)?;
+    } else {
+      debug_assert!(
+        self.check_insert_order(index, rowid).is_ok(),
+        "cells out of rowid order in {:?}",
+        self.page_id
+      );
+    }
+    Ok(())
+  }
+
+  /// 现有 cell 按 rowid 严格递增，且 `rowid` 大于第 `index - 1` 个、小于第 `index` 个 cell 的 rowid
+  fn check_insert_order(&self, index: u16, rowid: RowId) -> Result<(), DomainError> {
+    self.check_rowid_order()?;
+    let header = match self.cell_header() {
+      Ok(header) => header,
+      Err(_) => return Ok(()),
+    };
+
+    let rowid_at = |index: u16| {
+      self
+        .cell_offset(&header, index)
+        .and_then(|offset| self.cell_rowid_at(header.page_type, offset))
+    };
+    let out_of_order = (index > 0 && rowid_at(index - 1).is_ok_and(|prev| prev >= rowid))
+      || (index < header.num_cells && rowid_at(index).is_ok_and(|next| next <= rowid));
+    if out_of_order {
+      return Err(DomainError::InvariantViolation {
+        message: format!(
+          "page {:?}: rowid {rowid:?} does not fit at cell {index}",
+          self.page_id
+        ),
+      });
+    }
+    Ok(())
+  }
+
+  /// 删除第 `index` 个 cell，释放其空间
+  ///
+  /// 不足 4 字节的空间计入碎片；碎片计数会超过 `MAX_FRAGMENTED_BYTES` 时改为整理整页。
+  pub fn remove_cell(&mut self, index: u16) -> Result<(), PageError> {
+    let mut header = self.cell_header()?;
+    let offset = self.cell_offset(&header, index)?;
+    let size = self.cell_size_at(header.page_type, offset)?;
+
+    // pointer array 中 index+1.. 前移 2 字节
+    let ptr = PAGE_HEADER_SIZE + index as usize * CELL_POINTER_SIZE;
+    let end = pointer_array_end(header.num_cells);
+    self.data.copy_within(ptr + CELL_POINTER_SIZE..end, ptr);
+    header.num_cells -= 1;
+
+    if offset == header.cell_content_area as usize {
+      // 位于 content area 头部：直接归还给未分配区（cell_size_at 已保证不越过页尾）
+      header.cell_content_area = (offset + size) as u16;
+    } else if size >= 4 {
+      // 挂到 freeblock 链表头部
+      let next = header.first_freeblock.to_le_bytes();
+      let len = (size as u16).to_le_bytes();
+      self.data[offset..offset + 4].copy_from_slice(&[next[0], next[1], len[0], len[1]]);
+      header.first_freeblock = offset as u16;
+    } else if header.fragmented_bytes as usize + size <= MAX_FRAGMENTED_BYTES {
+      header.fragmented_bytes += size as u8;
+    } else {
+      // 删除后的 pointer array 已不再引用这块空间，整理时自然被回收
+      self.write_header(&header);
+      self.defragment()?;
+      return Ok(());
+    }
+
+    self.write_header(&header);
+    Ok(())
+  }
+
+  /// 整理碎片：把所有 cell 紧密排到页尾，清空 freeblock 链表和碎片计数
+  ///
+  /// 返回本次整理的统计：`reclaimed_bytes` 为并入连续空闲区的字节数
+  /// （原 freeblock + 碎片），`cells_moved` 为位置发生变化的 cell 数。
+  /// 没有 cell 被搬动时不会标记脏页。
+  pub fn defragment(&mut self) -> Result<DefragmentStats, PageError> {
+    let mut header = self.cell_header()?;
+    let unallocated_before = self.unallocated_space(&header);
+
+    let mut cells = Vec::with_capacity(header.num_cells as usize);
+    for i in 0..header.num_cells {
+      cells.push((self.cell_offset(&header, i)?, self.cell(i)?.to_vec()));
+    }
+    let flags: Vec<u16> = (0..header.num_cells)
+      .map(|i| self.cell_pointer(i) & TOMBSTONE_FLAG)
+      .collect();
+
+    // cell 互相重叠的坏页，紧排后可能越过 pointer array：先校验再搬动
+    let total: usize = cells.iter().map(|(_, cell)| cell.len()).sum();
+    if PAGE_SIZE
+      .checked_sub(total)
+      .map_or(true, |c| c < pointer_array_end(header.num_cells))
+    {
+      return Err(PageError::InvalidLayout {
+        cell_content_area: header.cell_content_area as usize,
+        num_cells: header.num_cells,
+      });
+    }
+
+    let mut content = PAGE_SIZE;
+    let mut cells_moved = 0u16;
+    for (i, (old_offset, cell)) in cells.iter().enumerate() {
+      content -= cell.len();
+      if *old_offset != content {
+        self.data[content..content + cell.len()].copy_from_slice(cell);
+        self.write_cell_pointer(i as u16, content as u16 | flags[i]);
+        cells_moved += 1;
+      }
+    }
+
+    header.cell_content_area = content as u16;
+    header.first_freeblock = 0;
+    header.fragmented_bytes = 0;
+    self.write_header(&header);
+    if cells_moved > 0 {
+      // cell 已搬动而页头可能不变，显式标记
+      self.mark_dirty();
+    }
+
+    Ok(DefragmentStats {
+      reclaimed_bytes: self.unallocated_space(&header) - unallocated_before,
+      cells_moved,
+    })
+  }
+
+  /// 解析页头并确认页类型持有 cell
+  ///
+  /// 同时校验 `cell_content_area` 位于 `[pointer array 末尾, PAGE_SIZE]` 之内，
+  /// 损坏的页头返回 `InvalidLayout`，避免后续偏移计算下溢或越界。
+  fn cell_header(&self) -> Result<PageHeader, PageError> {
+    let header = self.try_parse_header()?;
+    match header.page_type {
+      PageType::Internal | PageType::Leaf => {}
+      other => return Err(PageError::UnsupportedPageType(other)),
+    }
+
+    let content = header.cell_content_area as usize;
+    if !(pointer_array_end(header.num_cells)..=PAGE_SIZE).contains(&content) {
+      return Err(PageError::InvalidLayout {
+        cell_content_area: content,
+        num_cells: header.num_cells,
+      });
+    }
+    Ok(header)
+  }
+
+  fn cell_offset(&self, header: &PageHeader, index: u16) -> Result<usize, PageError> {
+    if index >= header.num_cells {
+      return Err(PageError::CellIndexOutOfRange { index, num_cells: header.num_cells });
+    }
+    Ok((self.cell_pointer(index) & !TOMBSTONE_FLAG) as usize)
+  }
+
+  /// 第 `index` 项 cell pointer 的原始值（含墓碑标记）
+  fn cell_pointer(&self, index: u16) -> u16 {
+    let ptr = PAGE_HEADER_SIZE + index as usize * CELL_POINTER_SIZE;
+    u16::from_le_bytes([self.data[ptr], self.data[ptr + 1]])
+  }
+
+  fn write_cell_pointer(&mut self, index: u16, offset: u16) {
+    let ptr = PAGE_HEADER_SIZE + index as usize * CELL_POINTER_SIZE;
+    self.data[ptr..ptr + CELL_POINTER_SIZE].copy_from_slice(&offset.to_le_bytes());
+  }
+
+  fn cell_size_at(&self, page_type: PageType, offset: usize) -> Result<usize, PageError> {
+    if !(PAGE_HEADER_SIZE..PAGE_SIZE).contains(&offset) {
+      return Err(PageError::CorruptCell { offset });
+    }
+    cell::cell_size(page_type, &self.data[offset..]).ok_or(PageError::CorruptCell { offset })
+  }
+
+  /// 解码 `offset` 处 cell 开头的 rowid
+  fn cell_rowid_at(&self, page_type: PageType, offset: usize) -> Result<RowId, PageError> {
+    let start = match page_type {
+      PageType::Internal => offset + CHILD_PTR_SIZE,
+      _ => offset,
+    };
+    let (rowid, _) = self
+      .data
+      .get(start..)
+      .and_then(varint::decode)
+      .ok_or(PageError::CorruptCell { offset })?;
+    Ok(RowId::new(rowid as i64))
+  }
+
+  /// pointer array 末尾到 content area 之间的未分配字节数
+  fn unallocated_space(&self, header: &PageHeader) -> usize {
+    (header.cell_content_area as usize).saturating_sub(pointer_array_end(header.num_cells))
+  }
+
+  /// 遍历 freeblock 链表，返回 `(offset, size)`；链表越界或成环视为损坏
+  fn freeblock_list(&self, header: &PageHeader) -> Result<Vec<(u16, u16)>, PageError> {
+    let mut blocks = Vec::new();
+    let mut offset = header.first_freeblock as usize;
+    while offset != 0 {
+      if offset < PAGE_HEADER_SIZE || offset + 4 > PAGE_SIZE || blocks.len() > PAGE_SIZE / 4 {
+        return Err(PageError::CorruptCell { offset });
+      }
+      let next = u16::from_le_bytes([self.data[offset], self.data[offset + 1]]);
+      let size = u16::from_le_bytes([self.data[offset + 2], self.data[offset + 3]]);
+      blocks.push((offset as u16, size));
+      offset = next as usize;
+    }
+    Ok(blocks)
   }
 }
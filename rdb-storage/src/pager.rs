@@ -1,18 +1,26 @@
 use core::marker::PhantomData;
 use core::sync::atomic::Ordering;
+use std::cell::{Cell, Ref, RefCell, RefMut};
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{self, ErrorKind};
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::ops::{Deref, DerefMut};
+use std::path::Path;
 use std::rc::Rc;
 use std::sync::atomic::AtomicU32;
 use std::sync::Arc;
-use std::{cell::RefCell, os::unix::fs::FileExt};
 
-use rdb_domain::PageId;
-use rdb_infrastructure::file_io::{page_offset, read_exact_at, write_all_at};
+use rdb_domain::{DomainError, PageId, RowId, TransactionId};
+use rdb_infrastructure::file_io::{read_exact_at, try_lock, write_all_at, LockMode};
 use rdb_infrastructure::BufferPool;
 
-use crate::page::{InvalidPageType, Page};
+use crate::catalog;
+use crate::db_header::{DbHeader, DB_HEADER_PAGE};
+use crate::freelist::Freelist;
+use crate::page::{
+  HeaderError, InvalidPageType, LayoutError, Page, PageError, PageType, PAGE_SIZE,
+};
+use crate::record::DecodeError;
 
 #[derive(thiserror::Error, Debug)]
 pub enum PagerError {
@@ -22,6 +30,9 @@ pub enum PagerError {
   #[error("invalid page type: {0}")]
   InvalidPageType(#[from] InvalidPageType),
 
+  #[error("unsupported page header version {0}")]
+  UnsupportedHeaderVersion(u8),
+
   #[error("unsupported page_size={0} (currently only 4096 is supported)")]
   UnsupportedPageSize(usize),
 
@@ -30,41 +41,240 @@ pub enum PagerError {
 
   #[error("page not found: {0:?}")]
   PageNotFound(PageId),
+
+  #[error("page error: {0}")]
+  Page(#[from] PageError),
+
+  #[error("duplicate rowid: {0:?}")]
+  DuplicateRowId(RowId),
+
+  #[error("payload too large: {len} bytes (max {max})")]
+  PayloadTooLarge { len: usize, max: usize },
+
+  #[error("btree invariant violation: {0}")]
+  BTreeInvariant(String),
+
+  #[error("page {0:?} is already borrowed")]
+  PageBorrowed(PageId),
+
+  #[error("refusing to write page {page_id:?} with inconsistent layout: {source}")]
+  InvalidPageState { page_id: PageId, source: LayoutError },
+
+  #[error("page {0:?} is pinned")]
+  Pinned(PageId),
+
+  #[error("database file is locked by another pager")]
+  Locked,
+
+  #[error("invalid database header: {0}")]
+  InvalidDbHeader(String),
+
+  #[error("record error: {0}")]
+  Record(#[from] DecodeError),
+
+  #[error("csv line {line}: {message}")]
+  Csv { line: usize, message: String },
+
+  #[error("domain error: {0}")]
+  Domain(#[from] DomainError),
+
+  #[error("database file is empty")]
+  EmptyFile,
+
+  #[error("row not found: {0:?}")]
+  RowNotFound(RowId),
+
+  #[error("blob access out of bounds: offset={offset} len={len} blob_size={size}")]
+  BlobOutOfBounds { offset: usize, len: usize, size: usize },
+
+  #[error("no version of page {page_id:?} visible to transaction {txn:?}")]
+  VersionNotFound { page_id: PageId, txn: TransactionId },
+
+  #[error("write conflict on page {page_id:?}: stamped by {stamp:?}, writer is {txn:?}")]
+  WriteConflict { page_id: PageId, stamp: TransactionId, txn: TransactionId },
+
+  #[error("read out of bounds: offset={offset} len={len} file_size={file_len}")]
+  OutOfBounds { offset: u64, len: usize, file_len: u64 },
 }
 
 pub type Result<T> = std::result::Result<T, PagerError>;
 
+impl From<HeaderError> for PagerError {
+  fn from(e: HeaderError) -> Self {
+    match e {
+      HeaderError::InvalidPageType(e) => PagerError::InvalidPageType(e),
+      HeaderError::UnsupportedHeaderVersion(v) => PagerError::UnsupportedHeaderVersion(v),
+    }
+  }
+}
+
+/// 存储层错误转换为领域错误
+///
+/// 注意：转换是有损的，`DomainError::Storage` 只保留 `PagerError` 的 Display 文本，
+/// 原始的错误变体（以及 `io::Error` 等 source）不再可用于匹配。
+/// 例外是 `PagerError::Domain`，原样还原为内部的 `DomainError`。
+/// 需要区分具体存储错误的调用方应直接处理 `PagerError`。
+impl From<PagerError> for DomainError {
+  fn from(e: PagerError) -> Self {
+    match e {
+      PagerError::Domain(e) | PagerError::Page(PageError::Invariant(e)) => e,
+      other => DomainError::Storage { message: other.to_string() },
+    }
+  }
+}
+
+/// Pager 的 I/O 计数（见 `Pager::stats`）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PagerStats {
+  /// 从磁盘读入的页数
+  pub page_reads: u64,
+  /// 写入磁盘的页数（含分配时的清零写）
+  pub page_writes: u64,
+  /// 访问页时已在缓存中的次数
+  pub cache_hits: u64,
+  /// 访问页时需要从磁盘读入的次数（`warm` 预加载不计入）
+  pub cache_misses: u64,
+}
+
+/// 已打开文件的几何信息（见 `Pager::describe`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PagerInfo {
+  /// 页大小（字节）
+  pub page_size: usize,
+  /// Pager 记录的总页数（含已分配但尚未写盘的页）
+  pub page_count: u32,
+  /// 文件在磁盘上的实际长度（字节）
+  pub file_len: u64,
+  /// 是否以只读方式打开
+  pub read_only: bool,
+}
+
+/// 一次 checkpoint 的结果（见 `Pager::checkpoint`）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CheckpointResult {
+  /// 写入磁盘的脏页数
+  pub pages_written: u32,
+  /// 写入的字节数
+  pub bytes_written: u64,
+}
+
+/// 打开 Pager 的选项（见 `Pager::from_file`）
+#[derive(Debug, Clone, Default)]
+pub struct PagerOptions {
+  /// 页缓存配置，可在多个 Pager 间共享；默认不限容量
+  pub buffer_pool: Arc<BufferPool>,
+  /// 以只读方式打开（共享锁）
+  pub read_only: bool,
+  /// 从磁盘读入页时校验 cell 布局（见 `Pager::set_strict_layout`）
+  pub strict_layout: bool,
+}
+
+/// 写盘回调：`(page_id, 文件偏移)`
+type WriteHook = Box<dyn FnMut(PageId, u64)>;
+
+/// 写前屏障：返回错误时放弃本次写盘
+type FlushBarrier = Box<dyn Fn(PageId) -> io::Result<()>>;
+
+/// 持久化模式（见 `Pager::set_durability`）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Durability {
+  /// 每次 `flush_page`/`checkpoint` 写盘后都 fsync（默认）
+  #[default]
+  Full,
+  /// 刷盘只写入操作系统缓存，不 fsync；只有 `Pager::sync` 保证持久化
+  Deferred,
+}
+
 /// 页管理器
 ///
 /// - `file`：数据库文件句柄
 /// - `page_size`：页大小（通常 4096）
 /// - `page_count`：当前总页数
-/// - `buffer_pool`：缓存池（占位类型，T38 会实现）
+/// - `read_only`：是否以只读方式（共享锁）打开
+/// - `buffer_pool`：缓存配置（容量）
 /// - `page_index`：页索引（page_id -> index）
 /// - `pages`：页容器（page_id -> Page）
+/// - `clock`：LRU 时钟，每次访问缓存页时递增
+/// - `on_evict`：淘汰回调（见 `set_on_evict`）
+/// - `on_write`：写盘回调（见 `set_on_write`）
+/// - `flush_barrier`：写前屏障（见 `set_flush_barrier`）
+/// - `versions`：页的历史版本，按事务 stamp 升序（见 `set_versioning`），`None` 表示未开启
+/// - `sparse_allocation`：分配页时是否跳过清零写（见 `set_sparse_allocation`）
+/// - `has_db_header`：第 1 页是否是数据库头；只有这时分配/释放页才使用 freelist
+/// - `strict_layout`：读入页时是否校验 cell 布局（见 `set_strict_layout`）
+/// - `durability`：刷盘后是否 fsync（见 `set_durability`）
+/// - `on_sync`：fsync 回调（见 `set_on_sync`）
+/// - `stats`：I/O 计数
 /// - `_not_send_sync`：用 Rc 把 Pager 变成 !Send + !Sync
 /// - `_phantom`：绑定 'db 生命周期
+///
+/// 缓存淘汰：缓存页数超过 `buffer_pool.capacity()` 时，在持有 `&mut self` 的操作
+/// （`get_page_mut`/`new_page` 等）中按 LRU 淘汰未被 pin 的页；脏页一定先写回磁盘再移出缓存。
+/// 只读路径（`&self`）可能暂时超出容量，因为那时仍可能有外部引用指向缓存页。
+///
+/// 容量为 0 表示不缓存：干净且没有守卫或 pin 的页每次访问都重新从磁盘读入，
+/// 其缓存槽位在下一次 `&mut self` 操作时释放；脏页和被 pin 的页仍保留到写回或守卫释放为止。
 pub struct Pager<'db> {
   pub(crate) file: File,
   pub(crate) page_size: usize,
   pub(crate) page_count: AtomicU32,
+  pub(crate) read_only: bool,
   pub(crate) buffer_pool: Arc<BufferPool>,
 
-  // 先用最简单的“内部缓存”：page_id -> index, pages 存 Box<Page> 保证地址稳定
+  // 先用最简单的“内部缓存”：page_id -> index, pages 存 Box 保证地址稳定
+  // 每页一个 RefCell：PageRef/PageRefMut 守卫按页跟踪借用
   pub(crate) page_index: RefCell<HashMap<PageId, usize>>,
-  pub(crate) pages: RefCell<Vec<Box<Page<'db>>>>,
+  pub(crate) pages: RefCell<Vec<Box<CachedPage<'db>>>>,
+  pub(crate) clock: Cell<u64>,
+  pub(crate) on_evict: Option<Box<dyn FnMut(PageId, bool)>>,
+  pub(crate) on_write: RefCell<Option<WriteHook>>,
+  pub(crate) flush_barrier: Option<FlushBarrier>,
+  pub(crate) versions: Option<HashMap<PageId, Vec<Box<Page<'db>>>>>,
+  pub(crate) sparse_allocation: bool,
+  pub(crate) has_db_header: bool,
+  pub(crate) strict_layout: bool,
+  pub(crate) durability: Durability,
+  pub(crate) on_sync: RefCell<Option<Box<dyn FnMut()>>>,
+
+  pub(crate) stats: Cell<PagerStats>,
 
   pub(crate) _not_send_sync: PhantomData<Rc<()>>,
   pub(crate) _phantom: PhantomData<&'db mut ()>,
 }
 
 impl<'db> Pager<'db> {
+  /// 以读写方式打开：对文件加排他锁，已被其他 Pager 锁定时返回 `Locked`
+  ///
+  /// 锁在 Pager drop（文件句柄关闭）时释放。
   pub(crate) fn new(file: File, page_size: usize, buffer_pool: Arc<BufferPool>) -> Result<Self> {
+    Self::with_lock(file, page_size, buffer_pool, LockMode::Exclusive)
+  }
+
+  /// 以只读方式打开：对文件加共享锁，可与其他只读 Pager 共存，但与读写 Pager 互斥
+  pub(crate) fn new_read_only(
+    file: File,
+    page_size: usize,
+    buffer_pool: Arc<BufferPool>,
+  ) -> Result<Self> {
+    Self::with_lock(file, page_size, buffer_pool, LockMode::Shared)
+  }
+
+  fn with_lock(
+    file: File,
+    page_size: usize,
+    buffer_pool: Arc<BufferPool>,
+    mode: LockMode,
+  ) -> Result<Self> {
     // 由于 Page 固定是 [u8; 4096], 这里先支持 4096
     if page_size != 4096 {
       return Err(PagerError::UnsupportedPageSize(page_size));
     }
 
+    try_lock(&file, mode).map_err(|e| match e.kind() {
+      io::ErrorKind::WouldBlock => PagerError::Locked,
+      _ => PagerError::Io(e),
+    })?;
+
     let len = file.metadata()?.len();
     if len % page_size as u64 != 0 {
       return Err(PagerError::CorruptFile { len, page_size });
@@ -76,94 +286,473 @@ impl<'db> Pager<'db> {
       file,
       page_size,
       page_count: AtomicU32::new(page_count),
+      read_only: mode == LockMode::Shared,
       buffer_pool,
 
       page_index: RefCell::new(HashMap::new()),
       pages: RefCell::new(Vec::new()),
+      clock: Cell::new(0),
+      on_evict: None,
+      on_write: RefCell::new(None),
+      flush_barrier: None,
+      versions: None,
+      sparse_allocation: false,
+      has_db_header: false,
+      strict_layout: false,
+      durability: Durability::default(),
+      on_sync: RefCell::new(None),
+
+      stats: Cell::new(PagerStats::default()),
 
       _not_send_sync: PhantomData,
       _phantom: PhantomData,
     })
   }
 
+  /// 打开数据库文件，不存在（或为空）时创建并初始化
+  ///
+  /// 新文件会写入两页：第 1 页为数据库头（`DbHeader`），第 2 页为空的系统目录叶子页。
+  /// 已有文件必须带有合法的数据库头，否则返回 `InvalidDbHeader`；
+  /// 打开后会读取系统目录，并预加载第 1 页、目录根页和所有表的根页（见 `warm`）。
+  pub fn create(path: impl AsRef<Path>) -> Result<Pager<'static>> {
+    let file = OpenOptions::new()
+      .read(true)
+      .write(true)
+      .create(true)
+      .truncate(false)
+      .open(path)?;
+    Self::from_file(file, PagerOptions::default())
+  }
+
+  /// 打开已有的数据库文件，不创建也不初始化
+  ///
+  /// 文件不存在时返回 `Io`（`NotFound`），长度为 0（如刚 `touch` 出来的文件）时返回 `EmptyFile`；
+  /// 需要自动初始化空文件时使用 `create`。
+  pub fn open(path: impl AsRef<Path>) -> Result<Pager<'static>> {
+    let file = OpenOptions::new().read(true).write(true).open(path)?;
+    if file.metadata()?.len() == 0 {
+      return Err(PagerError::EmptyFile);
+    }
+    Self::from_file(file, PagerOptions::default())
+  }
+
+  /// 从调用方已经打开的文件构造 Pager（沙箱中只能拿到文件句柄、不能按路径打开时使用）
+  ///
+  /// 与 `create` 的初始化/校验流程相同，页数按文件当前长度计算。
+  /// 读写模式下 `file` 需要可读可写；只读模式（`options.read_only`）遇到空文件时
+  /// 无法初始化，返回 `EmptyFile`。
+  pub fn from_file(file: File, options: PagerOptions) -> Result<Pager<'static>> {
+    let mut pager = if options.read_only {
+      Pager::new_read_only(file, PAGE_SIZE, options.buffer_pool)?
+    } else {
+      Pager::new(file, PAGE_SIZE, options.buffer_pool)?
+    };
+    pager.strict_layout = options.strict_layout;
+
+    if pager.page_count() == 0 {
+      if options.read_only {
+        return Err(PagerError::EmptyFile);
+      }
+
+      let meta = pager.new_page(PageType::Meta)?;
+      let catalog = pager.new_page(PageType::Leaf)?;
+      DbHeader::new(PAGE_SIZE as u32, catalog).write(pager.get_page_mut(meta)?)?;
+      pager.flush_all()?;
+    } else {
+      let header = pager.db_header()?;
+      if header.page_size as usize != PAGE_SIZE {
+        return Err(PagerError::UnsupportedPageSize(header.page_size as usize));
+      }
+
+      // 打开后的第一批查询总会访问第 1 页和各表根页，提前载入
+      let mut warm = vec![DB_HEADER_PAGE, header.catalog_root];
+      warm.extend(catalog::table_roots(&pager, header.catalog_root)?);
+      pager.warm(&warm)?;
+    }
+    pager.has_db_header = true;
+
+    Ok(pager)
+  }
+
+  /// 读取第 1 页的数据库头
+  pub fn db_header(&self) -> Result<DbHeader> {
+    DbHeader::read(&*self.page(DB_HEADER_PAGE)?)
+  }
+
   pub(crate) fn page_count(&self) -> u32 {
     self.page_count.load(Ordering::Relaxed)
   }
 
-  pub(crate) fn get_page(&self, page_id: PageId) -> Result<&Page<'db>> {
-    // 1) 命中缓存：用 raw ptr 脱离 RefCell borrow 的生命周期
-    if let Some(ptr) = self.get_cached_ptr(page_id) {
-      // SAFETY: ptr 指向 Box<Page> 的堆内存，生命周期受 Pager 管控
-      return Ok(unsafe { &*ptr });
+  /// 已打开文件的几何信息：页大小、页数、文件长度、是否只读
+  ///
+  /// 用于诊断工具打印文件概况；只查询一次文件元数据，不分配内存。
+  /// `file_len` 可能与 `page_count * page_size` 不一致（如稀疏分配的页尚未写盘），
+  /// 这本身就是诊断线索。
+  pub fn describe(&self) -> Result<PagerInfo> {
+    Ok(PagerInfo {
+      page_size: self.page_size,
+      page_count: self.page_count(),
+      file_len: self.file.metadata()?.len(),
+      read_only: self.read_only,
+    })
+  }
+
+  /// 当前的 I/O 计数
+  pub fn stats(&self) -> PagerStats {
+    self.stats.get()
+  }
+
+  pub(crate) fn get_page_mut(&mut self, page_id: PageId) -> Result<&mut Page<'db>> {
+    self.cached_cell(page_id)?;
+    self.evict_to_capacity(Some(page_id))?;
+    let idx = self
+      .cached_index(page_id)
+      .ok_or(PagerError::PageNotFound(page_id))?;
+    // &mut self 保证不存在任何守卫或其他引用，可以直接拿到 &mut
+    Ok(self.pages.get_mut()[idx].page.get_mut())
+  }
+
+  /// 预加载一组页到缓存
+  ///
+  /// 已缓存的页跳过；预加载不计入 `cache_hits`/`cache_misses`。
+  /// 只读路径不会触发淘汰，因此预加载的页数可能暂时超过缓存容量。
+  pub fn warm(&self, page_ids: &[PageId]) -> Result<()> {
+    for &page_id in page_ids {
+      self.load(page_id)?;
     }
+    Ok(())
+  }
 
-    // 2) 缓存未命中：从磁盘读入并放入缓存
-    let data = self.read_page_bytes(page_id)?;
-    let page = Page::from_bytes(page_id, data)?; // 这里会校验 page_type 字节
+  /// 获取只读页守卫
+  ///
+  /// 守卫持有该页的 `Ref`，借用由 RefCell 在运行时跟踪：可以同时持有多个只读守卫，
+  /// 但与同一页的 `PageRefMut` 互斥（冲突时返回 `PageBorrowed`）。
+  pub fn page(&self, page_id: PageId) -> Result<PageRef<'_, 'db>> {
+    let cell = self.cached_cell(page_id)?;
+    let inner = cell
+      .try_borrow()
+      .map_err(|_| PagerError::PageBorrowed(page_id))?;
+    Ok(PageRef { inner })
+  }
 
-    let mut pages = self.pages.borrow_mut();
-    let mut index = self.page_index.borrow_mut();
+  /// 获取可写页守卫
+  ///
+  /// 需要 `&mut self`：持有期间不可能再拿到任何页的守卫，读写互斥由借用检查保证。
+  /// 与 `get_page_mut` 一样，缓存超出容量时先淘汰其他页。
+  pub fn page_mut(&mut self, page_id: PageId) -> Result<PageRefMut<'_, 'db>> {
+    self.cached_cell(page_id)?;
+    self.evict_to_capacity(Some(page_id))?;
+    let idx = self
+      .cached_index(page_id)
+      .ok_or(PagerError::PageNotFound(page_id))?;
+    let inner = self.pages.get_mut()[idx]
+      .page
+      .try_borrow_mut()
+      .map_err(|_| PagerError::PageBorrowed(page_id))?;
+    Ok(PageRefMut { inner })
+  }
 
-    let idx = pages.len();
-    pages.push(Box::new(page));
-    index.insert(page_id, idx);
+  /// 开启/关闭多版本页缓存（默认关闭），关闭时丢弃所有历史版本
+  ///
+  /// 开启后写入方通过 `begin_page_write` 修改页，旧内容作为历史版本保留在内存中，
+  /// 读取方用 `get_page_as_of` 按自己的事务看到一致的快照。
+  /// 历史版本不参与缓存淘汰也不写盘，需要调用 `prune_versions` 回收。
+  pub fn set_versioning(&mut self, enabled: bool) {
+    match (enabled, self.versions.is_some()) {
+      (true, false) => self.versions = Some(HashMap::new()),
+      (false, true) => self.versions = None,
+      _ => {}
+    }
+  }
 
-    let ptr = (&*pages[idx]) as *const Page<'db>;
-    drop(index);
-    drop(pages);
+  /// 以事务 `txn` 的身份获取可写页守卫，并把页的 stamp 更新为 `txn`
+  ///
+  /// 开启多版本时，若页的当前 stamp 早于 `txn`，先把当前内容保存为历史版本；
+  /// 同一事务重复调用不会产生新版本。页已被更新的事务写过时返回 `WriteConflict`。
+  pub fn begin_page_write(
+    &mut self,
+    page_id: PageId,
+    txn: TransactionId,
+  ) -> Result<PageRefMut<'_, 'db>> {
+    let stamp = self.get_page_mut(page_id)?.txn();
+    if stamp > txn {
+      return Err(PagerError::WriteConflict { page_id, stamp, txn });
+    }
+    if stamp < txn && self.versions.is_some() {
+      let snapshot = Page::from_bytes(page_id, self.page(page_id)?.data)?;
+      if let Some(versions) = self.versions.as_mut() {
+        versions
+          .entry(page_id)
+          .or_default()
+          .push(Box::new(snapshot));
+      }
+    }
 
-    // SAFETY: 同上
-    Ok(unsafe { &*ptr })
+    let mut page = self.page_mut(page_id)?;
+    page.set_txn(txn);
+    Ok(page)
   }
 
-  pub(crate) fn get_page_mut(&mut self, page_id: PageId) -> Result<&mut Page<'db>> {
-    // 先检查缓存（确保 borrow() 的 Ref 在这一行结束后就被 drop）
-    let cached_idx = self.page_index.borrow().get(&page_id).copied();
-    
-    let idx = if let Some(i) = cached_idx {
-      i
-    } else {
-      // 缓存未命中：从磁盘读入
-      let data = self.read_page_bytes(page_id)?;
-      let page = Page::from_bytes(page_id, data)?;
+  /// 事务 `txn` 可见的页版本：stamp `<= txn` 的最新版本
+  ///
+  /// 当前页可见时返回当前页的只读守卫，否则在历史版本中查找；
+  /// 都不可见（页在 `txn` 之后才创建，或旧版本已被回收）时返回 `VersionNotFound`。
+  pub fn get_page_as_of(
+    &self,
+    page_id: PageId,
+    txn: TransactionId,
+  ) -> Result<PageVersion<'_, 'db>> {
+    let current = self.page(page_id)?;
+    if current.txn() <= txn {
+      return Ok(PageVersion::Current(current));
+    }
+    self
+      .versions
+      .as_ref()
+      .and_then(|versions| versions.get(&page_id))
+      .and_then(|versions| versions.iter().rev().find(|v| v.txn() <= txn))
+      .map(|v| PageVersion::Snapshot(v))
+      .ok_or(PagerError::VersionNotFound { page_id, txn })
+  }
 
-      let mut pages = self.pages.borrow_mut();
-      let mut index = self.page_index.borrow_mut();
+  /// 回收不再被任何读取方需要的历史版本，返回回收的版本数
+  ///
+  /// `oldest_active` 是仍在运行的最老事务：每页只保留它可见的那个版本及之后的版本。
+  pub fn prune_versions(&mut self, oldest_active: TransactionId) -> usize {
+    let Some(versions) = self.versions.as_mut() else {
+      return 0;
+    };
+    let mut pruned = 0;
+    for list in versions.values_mut() {
+      // list 按 stamp 升序；oldest_active 可见的版本是最后一个 stamp <= oldest_active 的
+      let visible = list.iter().rposition(|v| v.txn() <= oldest_active);
+      if let Some(keep_from) = visible {
+        pruned += keep_from;
+        list.drain(..keep_from);
+      }
+    }
+    // 当前页对 oldest_active 已可见时，历史版本全部可以丢弃
+    let pages = self.page_index.borrow();
+    let cached = self.pages.borrow();
+    versions.retain(|page_id, list| {
+      let current_visible = pages
+        .get(page_id)
+        .and_then(|&idx| cached[idx].page.try_borrow().ok())
+        .is_some_and(|page| page.txn() <= oldest_active);
+      if current_visible {
+        pruned += list.len();
+      }
+      !current_visible && !list.is_empty()
+    });
+    pruned
+  }
 
-      let idx = pages.len();
-      pages.push(Box::new(page));
-      index.insert(page_id, idx);
+  /// pin 住一页，返回 RAII 守卫
+  ///
+  /// 守卫存活期间该页不会被缓存淘汰；守卫 drop 时 pin 计数自动减一，
+  /// 避免手动 pin/unpin 配对出错。守卫不借用 Pager，持有期间仍可调用 `&mut self` 方法。
+  pub fn pin(&self, page_id: PageId) -> Result<PinGuard> {
+    let cell = self.cached_cell(page_id)?;
+    let page = cell
+      .try_borrow()
+      .map_err(|_| PagerError::PageBorrowed(page_id))?;
+    Ok(PinGuard { page_id, count: page.pin() })
+  }
 
-      idx
+  pub(crate) fn allocate_page(&mut self) -> Result<PageId> {
+    let (page_id, reused) = match self.pop_free_page()? {
+      Some(page_id) => (page_id, true),
+      None => (self.extend_file()?, false),
     };
 
-    // 返回 &mut: 同样用 raw ptr 脱离 RefCell borrow
-    let mut pages = self.pages.borrow_mut();
-    let ptr = (&mut *pages[idx]) as *mut Page<'db>;
-    drop(pages);
+    // 文件尾部扩展出的区域：set_len 保证读出来是 0，稀疏模式下不必再写；
+    // 从 freelist 复用的页是旧数据，总是要清零
+    if reused || !self.sparse_allocation {
+      // 把新页内容写成全 0 （避免读到旧垃圾数据）
+      let zero = [0u8; 4096];
+      self.write_page_bytes(page_id, &zero)?;
+    }
 
-    // SAFETY: get_page_mut 需要 &mut self，外部无法同时持有同 Pager 的其它引用
-    Ok(unsafe { &mut *ptr })
+    Ok(page_id)
   }
 
-  pub(crate) fn allocate_page(&mut self) -> Result<PageId> {
-    // TODO: (T93) 先从 Freelist 分配；这里先实现“文件尾部扩展”
+  /// 开启/关闭稀疏分配（默认关闭）
+  ///
+  /// 开启后 `allocate_page` 只用 `set_len` 扩展文件，不再显式写入一整页 0，
+  /// 依赖 `File::set_len` 的保证：扩展出的区域读出来全是 0。
+  ///
+  /// 平台行为：
+  /// - Linux（ext4/xfs/btrfs/tmpfs）、macOS（APFS）：扩展部分成为文件空洞，不占用磁盘块，
+  ///   直到第一次写入
+  /// - Windows（NTFS）：文件默认不是稀疏文件，扩展时由文件系统负责清零
+  /// - 不支持空洞的文件系统（如 FAT）：由文件系统在扩展时写 0
+  ///
+  /// 任何平台上读到的内容都一样，区别只在于是否省下一次写 I/O 和磁盘空间。
+  /// 关闭时保留显式清零写，作为最保守的可移植做法。
+  pub fn set_sparse_allocation(&mut self, enabled: bool) {
+    self.sparse_allocation = enabled;
+  }
+
+  /// 分配一个新页，但不做清零写
+  ///
+  /// 返回页的磁盘内容是未指定的（文件尾部扩展时通常为 0，从 Freelist 复用时是旧数据）。
+  /// 调用方必须保证在读取该页之前完整写入整页内容（例如批量导入时直接构造整页再刷盘），
+  /// 否则可能读到旧数据或非法的页类型。
+  pub(crate) fn allocate_page_raw(&mut self) -> Result<PageId> {
+    match self.pop_free_page()? {
+      Some(page_id) => Ok(page_id),
+      None => self.extend_file(),
+    }
+  }
+
+  /// 从 freelist 取出一个空闲页，freelist 为空或没有数据库头时返回 `None`
+  ///
+  /// 链表头页记录的页号用完后，链表头页本身也会被复用。取出的页已移出缓存。
+  fn pop_free_page(&mut self) -> Result<Option<PageId>> {
+    if !self.has_db_header {
+      return Ok(None);
+    }
+    let mut header = self.db_header()?;
+    let Some(head) = header.freelist_head else {
+      return Ok(None);
+    };
+
+    let trunk = self.get_page_mut(head)?;
+    let page_id = match Freelist::pop(trunk)? {
+      Some(page_id) => page_id,
+      None => {
+        header.freelist_head = Freelist::next(trunk)?;
+        header.write(self.get_page_mut(DB_HEADER_PAGE)?)?;
+        head
+      }
+    };
+    self.discard(page_id)?;
+    Ok(Some(page_id))
+  }
+
+  /// 在文件尾部扩展出一个新页
+  fn extend_file(&mut self) -> Result<PageId> {
     let next = self.page_count.load(Ordering::Relaxed) + 1;
 
     // 扩展文件长度
     let new_len = next as u64 * self.page_size as u64;
     self.file.set_len(new_len)?;
 
-    // 把新页内容写成全 0 （避免读到旧垃圾数据）
-    let zero = [0u8; 4096];
-    let off = (next as u64 - 1) * self.page_size as u64;
-    write_all_at(&self.file, &zero, off)?;
-
     self.page_count.store(next, Ordering::Relaxed);
     Ok(PageId::new(next))
   }
 
-  pub(crate) fn flush_page(&mut self, page_id: PageId) -> Result<()> {
+  /// 分配一个新页并以 `page_type` 初始化，直接放入缓存（标记为脏页）
+  pub(crate) fn new_page(&mut self, page_type: PageType) -> Result<PageId> {
+    // 整页都在缓存里构造并标记为脏页，刷盘时会完整写入，不需要先清零
+    let page_id = self.allocate_page_raw()?;
+
+    let mut page = Page::new(page_id, page_type);
+    page.mark_dirty();
+    self.cache_page(page);
+    self.evict_to_capacity(Some(page_id))?;
+
+    Ok(page_id)
+  }
+
+  /// 把一页移出缓存，返回它原本是否在缓存中
+  ///
+  /// 脏页先写回磁盘（写盘失败时页仍留在缓存里）；被 pin 的页返回 `Pinned`。
+  /// 与容量触发的淘汰一样会调用 `on_evict` 回调。
+  pub fn evict(&mut self, page_id: PageId) -> Result<bool> {
+    let Some(idx) = self.cached_index(page_id) else {
+      return Ok(false);
+    };
+    if self.pages.get_mut()[idx].page.get_mut().pin_count() > 0 {
+      return Err(PagerError::Pinned(page_id));
+    }
+    self.evict_at(idx)?;
+    Ok(true)
+  }
+
+  /// 设置淘汰回调：每个页被移出缓存时调用 `f(page_id, flushed)`，
+  /// `flushed` 表示该页是脏页、淘汰前已写回磁盘。用于统计/调试。
+  pub fn set_on_evict(&mut self, f: impl FnMut(PageId, bool) + 'static) {
+    self.on_evict = Some(Box::new(f));
+  }
+
+  /// 设置写盘回调：每次把页写入文件时调用 `f(page_id, offset)`。用于统计/调试。
+  pub fn set_on_write(&mut self, f: impl FnMut(PageId, u64) + 'static) {
+    *self.on_write.get_mut() = Some(Box::new(f));
+  }
+
+  /// 设置写前屏障：每次把页写入文件之前调用 `f(page_id)`
+  ///
+  /// 用于接入外部 WAL：在屏障中把日志 fsync 到该页的 LSN，保证日志先于数据页落盘。
+  /// 屏障返回错误时本次写盘不会发生，错误原样返回给触发刷盘的调用者（脏页保持为脏）。
+  /// `write_flush_batch` 不经过 Pager，调用者需自行保证顺序。
+  pub fn set_flush_barrier(&mut self, f: impl Fn(PageId) -> io::Result<()> + 'static) {
+    self.flush_barrier = Some(Box::new(f));
+  }
+
+  /// 开启/关闭严格布局校验（默认关闭）
+  ///
+  /// 开启后每个从磁盘读入的页都先经过 `Page::validate_layout`，布局损坏时
+  /// `page`/`page_mut` 等返回 `Page(Layout(..))`，而不是在之后的 cell 读取中解析出垃圾数据。
+  /// 只校验从磁盘读入的页，已在缓存中的页不受影响。
+  pub fn set_strict_layout(&mut self, strict: bool) {
+    self.strict_layout = strict;
+  }
+
+  /// 设置持久化模式（默认 `Full`）
+  ///
+  /// `Deferred` 适合批量导入：刷盘只是把页交给操作系统，结束时调用一次 `sync`。
+  /// 代价是崩溃一致性：在 `sync` 之前进程崩溃不影响已写入的数据（它们在操作系统缓存里），
+  /// 但断电或系统崩溃可能丢失任意一部分已刷盘的页，而且丢失的不一定是最后写的那些，
+  /// 文件可能处于新旧页混杂的状态。因此在 `sync` 成功之前，整个批次都应视为未提交。
+  pub fn set_durability(&mut self, durability: Durability) {
+    self.durability = durability;
+  }
+
+  /// 当前的持久化模式
+  pub fn durability(&self) -> Durability {
+    self.durability
+  }
+
+  /// 设置 fsync 回调：每次对数据库文件 fsync 时调用。用于统计/测试。
+  pub fn set_on_sync(&mut self, f: impl FnMut() + 'static) {
+    *self.on_sync.get_mut() = Some(Box::new(f));
+  }
+
+  /// 强制把已写入文件的内容（含文件长度）落盘，与持久化模式无关
+  ///
+  /// 只同步已经刷盘的页；缓存中的脏页需要先 `checkpoint`。
+  pub fn sync(&self) -> Result<()> {
+    self.file.sync_all()?;
+    if let Some(on_sync) = self.on_sync.borrow_mut().as_mut() {
+      on_sync();
+    }
+    Ok(())
+  }
+
+  /// `Full` 模式下 fsync，`Deferred` 模式下什么都不做
+  fn sync_if_full(&self) -> Result<()> {
+    match self.durability {
+      Durability::Full => self.sync(),
+      Durability::Deferred => Ok(()),
+    }
+  }
+
+  /// 把缓存中的页写回磁盘并按持久化模式 fsync，返回是否真的写了（干净页不写）
+  pub(crate) fn flush_page(&mut self, page_id: PageId) -> Result<bool> {
+    let written = self.write_back(page_id)?;
+    if written {
+      self.sync_if_full()?;
+    }
+    Ok(written)
+  }
+
+  /// 把脏页写入文件（不 fsync），返回是否真的写了
+  ///
+  /// 写盘前用 `Page::validate_layout` 校验页头和 cell pointer，不一致时返回
+  /// `InvalidPageState`，页不写盘、保持脏标记，避免把损坏的页持久化。
+  /// 刷盘、checkpoint 和淘汰都经过这里。
+  fn write_back(&mut self, page_id: PageId) -> Result<bool> {
     let idx = self
       .page_index
       .borrow()
@@ -172,49 +761,342 @@ impl<'db> Pager<'db> {
       .ok_or(PagerError::PageNotFound(page_id))?;
 
     let mut pages = self.pages.borrow_mut();
-    let page: &mut Page<'db> = &mut *pages[idx];
+    let page = pages[idx].page.get_mut();
+
+    if !page.dirty {
+      return Ok(false);
+    }
+    page
+      .validate_layout()
+      .map_err(|source| PagerError::InvalidPageState { page_id, source })?;
+    self.write_page_bytes(page_id, &page.data)?;
+    page.dirty = false;
+
+    Ok(true)
+  }
+
+  /// 取出所有脏页的快照，交给 `write_flush_batch` 在别的线程写盘
+  ///
+  /// 复制每个脏页的字节（按 page id 升序）并清除脏标记，Pager 可以继续在当前线程使用。
+  /// 与 `write_back` 一样先用 `Page::validate_layout` 校验每个脏页：任何一页不一致时返回
+  /// `InvalidPageState`，不生成 batch，所有脏页保持脏标记。
+  /// 由于脏标记已清除，写盘失败时调用方需要重试同一个 batch，否则修改会丢失。
+  /// batch 的写入不经过 Pager，因此不计入 `page_writes`，也不会触发 `on_write`，
+  /// 也不会 fsync：需要持久化时由调用方在写完后对文件句柄调用 `sync_all`。
+  pub fn take_flush_batch(&mut self) -> Result<FlushBatch> {
+    for entry in self.pages.get_mut().iter_mut() {
+      let page = entry.page.get_mut();
+      if page.dirty {
+        page
+          .validate_layout()
+          .map_err(|source| PagerError::InvalidPageState { page_id: page.page_id, source })?;
+      }
+    }
+
+    let mut pages: Vec<(PageId, Box<[u8; 4096]>)> = self
+      .pages
+      .get_mut()
+      .iter_mut()
+      .filter_map(|entry| {
+        let page = entry.page.get_mut();
+        page.dirty.then(|| {
+          page.dirty = false;
+          (page.page_id, Box::new(page.data))
+        })
+      })
+      .collect();
+    pages.sort_unstable_by_key(|(id, _)| id.into_inner());
+
+    Ok(FlushBatch { page_size: self.page_size, pages })
+  }
 
-    if page.dirty {
-      let off = (u64::from(page_id.into_inner()) - 1) * self.page_size as u64;
-      write_all_at(&self.file, &page.data, off)?;
-      page.dirty = false;
+  /// 把页 `from` 的内容复制到页 `to`（文件级碎片整理/vacuum 用）
+  ///
+  /// `to` 必须是已分配的页；其缓存内容被整页覆盖并标记为脏页，刷盘后字节出现在新偏移处。
+  /// 只搬运字节：父页指针、系统目录中的 rootpage 等引用由调用方根据返回的页类型修正，
+  /// `from` 本身保持不变，由调用方决定何时释放。`to` 被 pin 时返回 `PageBorrowed`。
+  pub fn relocate_page(&mut self, from: PageId, to: PageId) -> Result<PageType> {
+    if to.into_inner() == 0 || to.into_inner() > self.page_count() {
+      return Err(PagerError::PageNotFound(to));
     }
 
+    let src = self.get_page_mut(from)?;
+    let (data, page_type) = (src.data, src.page_type());
+    if from == to {
+      return Ok(page_type);
+    }
+
+    match self.cached_index(to) {
+      Some(idx) => {
+        let dst = self.pages.get_mut()[idx].page.get_mut();
+        if dst.pin_count() != 0 {
+          return Err(PagerError::PageBorrowed(to));
+        }
+        dst.data = data;
+        dst.mark_dirty();
+      }
+      // 不从磁盘读入 `to`：它可能是刚分配的全 0 页，页类型字节不合法
+      None => {
+        let mut page = Page::from_bytes(to, data)?;
+        page.mark_dirty();
+        self.cache_page(page);
+      }
+    }
+    self.evict_to_capacity(Some(to))?;
+
+    Ok(page_type)
+  }
+
+  /// 从文件的任意偏移读取 `buf.len()` 字节（恢复工具用）
+  ///
+  /// 绕过页号换算和缓存，偏移可以不按页对齐；读的是磁盘上的内容，看不到缓存中未刷盘的修改。
+  /// 超出文件末尾时返回 `OutOfBounds`，不做部分读取。不计入 `page_reads`。
+  pub fn read_raw(&self, offset: u64, buf: &mut [u8]) -> Result<()> {
+    let file_len = self.file.metadata()?.len();
+    let in_bounds = offset
+      .checked_add(buf.len() as u64)
+      .is_some_and(|end| end <= file_len);
+    if !in_bounds {
+      return Err(PagerError::OutOfBounds { offset, len: buf.len(), file_len });
+    }
+
+    read_exact_at(&self.file, buf, offset)?;
     Ok(())
   }
 
+  /// 比较缓存中的页与磁盘上的内容（诊断用）
+  ///
+  /// 返回每个不同字节的 `(offset, 内存中的字节, 磁盘上的字节)`，按 offset 升序。
+  /// 页未缓存时返回 `PageNotFound`。读取磁盘会计入 `page_reads`。
+  pub fn diff_page(&self, page_id: PageId) -> Result<Vec<(usize, u8, u8)>> {
+    let idx = self
+      .cached_index(page_id)
+      .ok_or(PagerError::PageNotFound(page_id))?;
+    let on_disk = self.read_page_bytes(page_id)?;
+
+    let pages = self.pages.borrow();
+    let page = pages[idx]
+      .page
+      .try_borrow()
+      .map_err(|_| PagerError::PageBorrowed(page_id))?;
+
+    Ok(
+      page
+        .data
+        .iter()
+        .zip(on_disk.iter())
+        .enumerate()
+        .filter(|(_, (mem, disk))| mem != disk)
+        .map(|(offset, (&mem, &disk))| (offset, mem, disk))
+        .collect(),
+    )
+  }
+
   pub(crate) fn flush_all(&mut self) -> Result<()> {
-    // 把当前缓存里的所有脏页刷盘
-    let ids: Vec<PageId> = self.page_index.borrow().keys().copied().collect();
+    self.checkpoint().map(|_| ())
+  }
+
+  /// 把缓存里的所有脏页刷盘，返回本次写入的页数和字节数（干净页不计入）
+  ///
+  /// 按 page id 升序写入，偏移递增，接近顺序 I/O；`Full` 模式下全部写完后 fsync 一次。
+  /// 中途写盘失败时返回错误，已写入的页保持干净，其余页仍是脏页。
+  pub fn checkpoint(&mut self) -> Result<CheckpointResult> {
+    let mut ids: Vec<PageId> = self.page_index.borrow().keys().copied().collect();
+    ids.sort_unstable_by_key(|id| id.into_inner());
+
+    let mut result = CheckpointResult::default();
     for id in ids {
-      self.flush_page(id)?;
+      if self.write_back(id)? {
+        result.pages_written += 1;
+        result.bytes_written += self.page_size as u64;
+      }
     }
-    Ok(())
+    if result.pages_written > 0 {
+      self.sync_if_full()?;
+    }
+    Ok(result)
   }
 
+  /// 释放一个不再使用的页，放入 freelist 供之后的分配复用
+  ///
+  /// 链表头的 freelist 页还有空位时把页号记在其中；没有 freelist 页或链表头已满时，
+  /// 被释放的页本身成为新的链表头。页的旧内容直接丢弃，不会写回。
+  /// 链表头记录在数据库头中，没有数据库头时返回 `InvalidDbHeader`。
   pub(crate) fn free_page(&mut self, page_id: PageId) -> Result<()> {
-    // TODO: T90/T91 接入 Freelist
+    let id = page_id.into_inner();
+    if id <= DB_HEADER_PAGE.into_inner() || id > self.page_count() {
+      return Err(PagerError::PageNotFound(page_id));
+    }
+    if !self.has_db_header {
+      return Err(PagerError::InvalidDbHeader(
+        "no database header to record the freelist".to_string(),
+      ));
+    }
+    let mut header = self.db_header()?;
+    self.discard(page_id)?;
+
+    if let Some(head) = header.freelist_head {
+      let trunk = self.get_page_mut(head)?;
+      if !Freelist::is_full(trunk)? {
+        Freelist::push(trunk, page_id)?;
+        return Ok(());
+      }
+    }
+
+    let mut trunk = Page::new(page_id, PageType::Freelist);
+    Freelist::set_next(&mut trunk, header.freelist_head)?;
+    trunk.mark_dirty();
+    self.cache_page(trunk);
+    header.freelist_head = Some(page_id);
+    header.write(self.get_page_mut(DB_HEADER_PAGE)?)?;
     Ok(())
   }
 
-  pub(crate) unsafe fn get_page_ptr(&self, page_id: PageId) -> *const Page<'db> {
-    match self.get_page(page_id) {
-      Ok(p) => p as *const Page<'db>,
-      Err(_) => core::ptr::null(),
+  fn cached_index(&self, page_id: PageId) -> Option<usize> {
+    self.page_index.borrow().get(&page_id).copied()
+  }
+
+  /// 淘汰最久未使用、未被 pin 的页，直到缓存页数不超过容量
+  ///
+  /// `keep` 指定的页不会被淘汰（调用方马上要返回它的引用）。
+  /// 脏页先写回磁盘再移出缓存；所有页都不可淘汰时保留超出的部分，不报错。
+  fn evict_to_capacity(&mut self, keep: Option<PageId>) -> Result<()> {
+    let capacity = self.buffer_pool.capacity();
+
+    while self.pages.get_mut().len() > capacity {
+      let victim = self
+        .pages
+        .get_mut()
+        .iter_mut()
+        .enumerate()
+        .filter_map(|(idx, entry)| {
+          let page = entry.page.get_mut();
+          let evictable = Some(page.page_id) != keep && page.pin_count() == 0;
+          evictable.then_some((idx, entry.last_used.get()))
+        })
+        .min_by_key(|&(_, last_used)| last_used)
+        .map(|(idx, _)| idx);
+
+      let Some(idx) = victim else {
+        break;
+      };
+      self.evict_at(idx)?;
     }
+
+    Ok(())
   }
 
-  pub(crate) unsafe fn get_page_mut_ptr(&mut self, page_id: PageId) -> *mut Page<'db> {
-    match self.get_page_mut(page_id) {
-      Ok(p) => p as *mut Page<'db>,
-      Err(_) => core::ptr::null_mut(),
+  /// 把下标为 `idx` 的缓存页移出缓存（脏页先刷盘）
+  fn evict_at(&mut self, idx: usize) -> Result<()> {
+    let page_id = self.pages.get_mut()[idx].page.get_mut().page_id;
+
+    let flushed = self.pages.get_mut()[idx].page.get_mut().dirty;
+    if flushed {
+      // 写盘失败时页仍留在缓存里，数据不会丢
+      self.flush_page(page_id)?;
     }
+
+    self.remove_cached(idx);
+    if let Some(on_evict) = self.on_evict.as_mut() {
+      on_evict(page_id, flushed);
+    }
+    Ok(())
   }
 
-  fn get_cached_ptr(&self, page_id: PageId) -> Option<*const Page<'db>> {
-    let idx = self.page_index.borrow().get(&page_id).copied()?;
+  /// 把页移出缓存且不写回（页已被释放，或即将被整页覆盖）
+  fn discard(&mut self, page_id: PageId) -> Result<()> {
+    let Some(idx) = self.cached_index(page_id) else {
+      return Ok(());
+    };
+    if self.pages.get_mut()[idx].page.get_mut().pin_count() > 0 {
+      return Err(PagerError::Pinned(page_id));
+    }
+    self.remove_cached(idx);
+    Ok(())
+  }
+
+  /// 删除下标为 `idx` 的缓存槽位，并修正被换到该位置的页的下标
+  fn remove_cached(&mut self, idx: usize) {
+    let pages = self.pages.get_mut();
+    let page_id = pages.swap_remove(idx).page.get_mut().page_id;
+    let index = self.page_index.get_mut();
+    index.remove(&page_id);
+    if let Some(moved) = pages.get_mut(idx) {
+      index.insert(moved.page.get_mut().page_id, idx);
+    }
+  }
+
+  /// 返回页所在的 RefCell，未缓存时先从磁盘读入
+  fn cached_cell(&self, page_id: PageId) -> Result<&RefCell<Page<'db>>> {
+    let (idx, hit) = self.load(page_id)?;
+    self.record(|s| {
+      if hit {
+        s.cache_hits += 1;
+      } else {
+        s.cache_misses += 1;
+      }
+    });
+
     let pages = self.pages.borrow();
-    Some((&*pages[idx]) as *const Page<'db>)
+    let tick = self.clock.get() + 1;
+    self.clock.set(tick);
+    pages[idx].last_used.set(tick);
+    let ptr = &pages[idx].page as *const RefCell<Page<'db>>;
+    drop(pages);
+
+    // SAFETY: ptr 指向 Box 的堆内存，Vec 扩容不会移动它；页只会在 &mut self 下被移出缓存，
+    // 而返回值借用了 &self，因此在其存活期间不会失效
+    Ok(unsafe { &*ptr })
+  }
+
+  /// 确保页在缓存中，返回 `(下标, 是否原本就已缓存)`
+  fn load(&self, page_id: PageId) -> Result<(usize, bool)> {
+    if let Some(idx) = self.cached_index(page_id) {
+      let reloaded = self.buffer_pool.capacity() == 0 && self.reload_uncached(idx, page_id)?;
+      return Ok((idx, !reloaded));
+    }
+
+    Ok((self.cache_page(self.read_page(page_id)?), false))
+  }
+
+  /// 容量为 0 时重新从磁盘读入缓存槽位中的页，返回是否读了盘
+  ///
+  /// 脏页、被 pin 的页和当前有守卫的页保持不变。缓存页只能通过 `PageRef`/`PageRefMut`
+  /// 守卫（或 `&mut self` 下的 `get_page_mut`）借出，`try_borrow_mut` 成功即说明没有任何
+  /// 引用指向该槽位，原地覆盖是安全的。
+  fn reload_uncached(&self, idx: usize, page_id: PageId) -> Result<bool> {
+    let pages = self.pages.borrow();
+    let Ok(mut page) = pages[idx].page.try_borrow_mut() else {
+      return Ok(false);
+    };
+    if page.dirty || page.pin_count() > 0 {
+      return Ok(false);
+    }
+    *page = self.read_page(page_id)?;
+    Ok(true)
+  }
+
+  /// 从磁盘读入一页并解析（不放入缓存）
+  fn read_page(&self, page_id: PageId) -> Result<Page<'db>> {
+    let data = self.read_page_bytes(page_id)?;
+    let page = Page::from_bytes(page_id, data)?; // 这里会校验 page_type 字节
+    if self.strict_layout {
+      page.validate_layout().map_err(PageError::from)?;
+    }
+    Ok(page)
+  }
+
+  /// 把页放入缓存，返回其下标
+  fn cache_page(&self, page: Page<'db>) -> usize {
+    let page_id = page.page_id();
+    let mut pages = self.pages.borrow_mut();
+    let idx = pages.len();
+    pages.push(Box::new(CachedPage {
+      page: RefCell::new(page),
+      last_used: Cell::new(self.clock.get()),
+    }));
+    self.page_index.borrow_mut().insert(page_id, idx);
+    idx
   }
 
   fn read_page_bytes(&self, page_id: PageId) -> Result<[u8; 4096]> {
@@ -231,6 +1113,142 @@ impl<'db> Pager<'db> {
     let mut buf = [0u8; 4096];
     let off = (u64::from(id) - 1) * self.page_size as u64;
     read_exact_at(&self.file, &mut buf, off)?;
+    self.record(|s| s.page_reads += 1);
     Ok(buf)
   }
+
+  fn write_page_bytes(&self, page_id: PageId, data: &[u8; 4096]) -> Result<()> {
+    if let Some(barrier) = &self.flush_barrier {
+      barrier(page_id)?;
+    }
+    let off = (u64::from(page_id.into_inner()) - 1) * self.page_size as u64;
+    write_all_at(&self.file, data, off)?;
+    self.record(|s| s.page_writes += 1);
+    if let Some(on_write) = self.on_write.borrow_mut().as_mut() {
+      on_write(page_id, off);
+    }
+    Ok(())
+  }
+
+  fn record(&self, f: impl FnOnce(&mut PagerStats)) {
+    let mut stats = self.stats.get();
+    f(&mut stats);
+    self.stats.set(stats);
+  }
+}
+
+/// 脏页快照（见 `Pager::take_flush_batch`）
+///
+/// 拥有所有数据、不引用 Pager，可以移动到阻塞线程池（如 `spawn_blocking`）中写盘。
+#[derive(Debug, Clone)]
+pub struct FlushBatch {
+  page_size: usize,
+  pages: Vec<(PageId, Box<[u8; 4096]>)>,
+}
+
+impl FlushBatch {
+  /// 快照中的页，按 page id 升序
+  pub fn page_ids(&self) -> impl Iterator<Item = PageId> + '_ {
+    self.pages.iter().map(|(id, _)| *id)
+  }
+
+  /// 快照中的页数
+  pub fn len(&self) -> usize {
+    self.pages.len()
+  }
+
+  /// 是否没有脏页
+  pub fn is_empty(&self) -> bool {
+    self.pages.is_empty()
+  }
+}
+
+/// 把 `FlushBatch` 中的页写入数据库文件
+///
+/// 只做 I/O，不接触 Pager；`file` 通常是同一数据库文件的另一个句柄（`File::try_clone`）。
+pub fn write_flush_batch(file: &File, batch: &FlushBatch) -> io::Result<()> {
+  for (page_id, data) in &batch.pages {
+    let off = (u64::from(page_id.into_inner()) - 1) * batch.page_size as u64;
+    write_all_at(file, data.as_ref(), off)?;
+  }
+  Ok(())
+}
+
+/// 缓存中的一个页
+///
+/// - `page`：页本身，RefCell 跟踪守卫借用
+/// - `last_used`：最近一次访问时的 LRU 时钟
+pub(crate) struct CachedPage<'db> {
+  pub(crate) page: RefCell<Page<'db>>,
+  pub(crate) last_used: Cell<u64>,
+}
+
+/// 页 pin 守卫（见 `Pager::pin`）
+pub struct PinGuard {
+  page_id: PageId,
+  count: Arc<AtomicU32>,
+}
+
+impl PinGuard {
+  /// 被 pin 住的页
+  pub fn page_id(&self) -> PageId {
+    self.page_id
+  }
+}
+
+impl Drop for PinGuard {
+  fn drop(&mut self) {
+    self.count.fetch_sub(1, Ordering::Relaxed);
+  }
+}
+
+/// 只读页守卫（见 `Pager::page`）
+pub struct PageRef<'a, 'db> {
+  inner: Ref<'a, Page<'db>>,
+}
+
+impl<'db> Deref for PageRef<'_, 'db> {
+  type Target = Page<'db>;
+
+  fn deref(&self) -> &Self::Target {
+    &self.inner
+  }
+}
+
+/// 可写页守卫（见 `Pager::page_mut`）
+pub struct PageRefMut<'a, 'db> {
+  inner: RefMut<'a, Page<'db>>,
+}
+
+impl<'db> Deref for PageRefMut<'_, 'db> {
+  type Target = Page<'db>;
+
+  fn deref(&self) -> &Self::Target {
+    &self.inner
+  }
+}
+
+impl<'db> DerefMut for PageRefMut<'_, 'db> {
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    &mut self.inner
+  }
+}
+
+/// 某个事务可见的页版本（见 `Pager::get_page_as_of`）
+pub enum PageVersion<'a, 'db> {
+  /// 缓存中的当前页
+  Current(PageRef<'a, 'db>),
+  /// 内存中保留的历史版本
+  Snapshot(&'a Page<'db>),
+}
+
+impl<'db> Deref for PageVersion<'_, 'db> {
+  type Target = Page<'db>;
+
+  fn deref(&self) -> &Self::Target {
+    match self {
+      PageVersion::Current(page) => page,
+      PageVersion::Snapshot(page) => page,
+    }
+  }
 }
@@ -0,0 +1,350 @@
+//! 行记录编码（B+Tree 叶子 cell 的 payload）
+//!
+//! SQLite 风格的 record 格式：
+//!
+//! ```text
+//! [header_size varint][serial_type varint ...][value body ...]
+//! ```
+//!
+//! `header_size` 包含其自身的字节数。serial type：
+//! - 0：NULL
+//! - 1/2/3/4/5/6：1/2/3/4/6/8 字节大端有符号整数
+//! - 7：8 字节大端 IEEE 754 浮点数
+//! - 8/9：整数常量 0/1（无 body）
+//! - N >= 12 且为偶数：长度 (N-12)/2 的 BLOB
+//! - N >= 13 且为奇数：长度 (N-13)/2 的 UTF-8 TEXT
+//!
+//! rowid 存放在 cell 的 key 中，不进入 record。
+//!
+//! 字节序：record 内的 varint、整数和浮点 body 都是大端（与 SQLite 一致，见 `RECORD_BYTE_ORDER`），
+//! 而页头字段是小端（见 `page::PAGE_HEADER_BYTE_ORDER`）。两者混用不会报错，只会静默写坏文件，
+//! 因此 debug 构建下编码每个数值后都会解码回来核对。
+
+use std::borrow::Cow;
+use std::ops::Range;
+
+use rdb_domain::{DataType, Row, RowId, Value};
+
+use crate::compress::{self, Compressor};
+use crate::varint;
+
+/// 多字节数值的字节序
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteOrder {
+  BigEndian,
+  LittleEndian,
+}
+
+/// record 的字节序：serial type varint、整数和浮点 body 都按大端编码
+pub const RECORD_BYTE_ORDER: ByteOrder = ByteOrder::BigEndian;
+
+/// record 解码错误
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+  #[error("record truncated")]
+  Truncated,
+
+  #[error("invalid serial type {0}")]
+  InvalidSerialType(u64),
+
+  #[error("invalid header size {0}")]
+  InvalidHeaderSize(u64),
+
+  #[error("text column {index} is not valid UTF-8")]
+  InvalidUtf8 { index: usize },
+
+  #[error("record has {got} columns, expected {expected}")]
+  ColumnCountMismatch { expected: usize, got: usize },
+
+  #[error("column {index}: expected {expected:?}, got {got:?}")]
+  TypeDrift { index: usize, expected: DataType, got: DataType },
+
+  #[error("unsupported compression id {0}")]
+  UnsupportedCompression(u8),
+
+  #[error("compressed record is corrupt")]
+  CorruptCompressed,
+
+  #[error("record is compressed and cannot be accessed in place")]
+  CompressedRecord,
+}
+
+/// TEXT 值遇到非法 UTF-8 时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextDecoding {
+  /// 返回 `DecodeError::InvalidUtf8`，防止把损坏数据当成正常文本
+  #[default]
+  Strict,
+  /// 用 U+FFFD 替换非法字节，供数据恢复工具尽量读出内容
+  Lossy,
+}
+
+/// 把行的值编码为 record（不含 rowid）
+pub fn encode_row(row: &Row<'_>) -> Vec<u8> {
+  encode_values(&row.values)
+}
+
+/// 把行的值编码为 record，并用 `compressor` 尝试压缩（见 `compress` 模块）
+pub fn encode_row_compressed(row: &Row<'_>, compressor: &dyn Compressor) -> Vec<u8> {
+  compress::compress_payload(encode_row(row), compressor)
+}
+
+/// 把一组值编码为 record
+pub fn encode_values(values: &[Value<'_>]) -> Vec<u8> {
+  let mut types = Vec::with_capacity(values.len());
+  let mut body = Vec::new();
+  for value in values {
+    let t = serial_type(value);
+    let start = body.len();
+    encode_body(value, &mut body);
+    debug_assert_body_roundtrip(t, value, &body[start..]);
+    types.push(t);
+  }
+
+  let mut header = Vec::new();
+  for &t in &types {
+    varint::encode(t, &mut header);
+  }
+
+  // header_size 包含自身：先假设 1 字节，不够再加长
+  let mut size_len = 1;
+  while varint::encoded_len((size_len + header.len()) as u64) > size_len {
+    size_len += 1;
+  }
+
+  let mut out = Vec::with_capacity(size_len + header.len() + body.len());
+  varint::encode((size_len + header.len()) as u64, &mut out);
+  out.extend_from_slice(&header);
+  out.extend_from_slice(&body);
+  out
+}
+
+/// 解码 record 为行（TEXT 严格校验 UTF-8）
+pub fn decode_row(row_id: RowId, bytes: &[u8]) -> Result<Row<'static>, DecodeError> {
+  decode_row_with(row_id, bytes, TextDecoding::Strict)
+}
+
+/// 解码 record 为行，指定 TEXT 的 UTF-8 处理方式
+pub fn decode_row_with(
+  row_id: RowId,
+  bytes: &[u8],
+  text: TextDecoding,
+) -> Result<Row<'static>, DecodeError> {
+  Ok(Row::new(row_id, decode_values_with(bytes, text)?))
+}
+
+/// 按表的列类型严格解码 record
+///
+/// 列数必须与 `types` 一致；每个值（NULL 除外）必须符合对应的 `DataType`，
+/// 否则返回 `TypeDrift`，用于发现 schema 变更后与旧数据不一致的情况。
+pub fn decode_row_typed(
+  row_id: RowId,
+  bytes: &[u8],
+  types: &[DataType],
+) -> Result<Row<'static>, DecodeError> {
+  let values = decode_values(bytes)?;
+  if values.len() != types.len() {
+    return Err(DecodeError::ColumnCountMismatch { expected: types.len(), got: values.len() });
+  }
+
+  for (index, (value, &expected)) in values.iter().zip(types).enumerate() {
+    if !expected.matches(value) {
+      return Err(DecodeError::TypeDrift { index, expected, got: value.data_type() });
+    }
+  }
+
+  Ok(Row::new(row_id, values))
+}
+
+/// 解码 record 中的所有值（TEXT 严格校验 UTF-8）
+pub fn decode_values(bytes: &[u8]) -> Result<Vec<Value<'static>>, DecodeError> {
+  decode_values_with(bytes, TextDecoding::Strict)
+}
+
+/// 解码 record 中的所有值，指定 TEXT 的 UTF-8 处理方式
+///
+/// 压缩过的 payload 会先透明解压。
+pub fn decode_values_with(
+  bytes: &[u8],
+  text: TextDecoding,
+) -> Result<Vec<Value<'static>>, DecodeError> {
+  let raw = compress::decompress_payload(bytes)?;
+  let bytes = raw.as_ref();
+  let (header_size, mut pos) = varint::decode(bytes).ok_or(DecodeError::Truncated)?;
+  let header_end = usize::try_from(header_size)
+    .ok()
+    .filter(|&end| end >= pos && end <= bytes.len())
+    .ok_or(DecodeError::InvalidHeaderSize(header_size))?;
+
+  let mut types = Vec::new();
+  while pos < header_end {
+    let (t, n) = varint::decode(&bytes[pos..header_end]).ok_or(DecodeError::Truncated)?;
+    types.push(t);
+    pos += n;
+  }
+
+  let mut body = &bytes[header_end..];
+  let mut values = Vec::with_capacity(types.len());
+  for (index, &t) in types.iter().enumerate() {
+    let len = body_len(t)?;
+    let data = body.get(..len).ok_or(DecodeError::Truncated)?;
+    values.push(decode_body(index, t, data, text)?);
+    body = &body[len..];
+  }
+
+  Ok(values)
+}
+
+/// 第 `index` 列的 serial type 及其 body 在 record 中的字节范围，不解码任何值
+///
+/// record 的列数不足 `index + 1` 时返回 `None`（如新增列尚未回填）；
+/// 压缩过的 payload 没有可原地访问的范围，返回 `CompressedRecord`。
+pub(crate) fn column_span(
+  bytes: &[u8],
+  index: usize,
+) -> Result<Option<(u64, Range<usize>)>, DecodeError> {
+  if compress::is_compressed(bytes) {
+    return Err(DecodeError::CompressedRecord);
+  }
+  let (header_size, mut pos) = varint::decode(bytes).ok_or(DecodeError::Truncated)?;
+  let header_end = usize::try_from(header_size)
+    .ok()
+    .filter(|&end| end >= pos && end <= bytes.len())
+    .ok_or(DecodeError::InvalidHeaderSize(header_size))?;
+
+  let mut body_start = header_end;
+  for i in 0..=index {
+    if pos >= header_end {
+      return Ok(None);
+    }
+    let (t, n) = varint::decode(&bytes[pos..header_end]).ok_or(DecodeError::Truncated)?;
+    pos += n;
+    let len = body_len(t)?;
+    let body_end = body_start
+      .checked_add(len)
+      .filter(|&end| end <= bytes.len());
+    let body_end = body_end.ok_or(DecodeError::Truncated)?;
+    if i == index {
+      return Ok(Some((t, body_start..body_end)));
+    }
+    body_start = body_end;
+  }
+  Ok(None)
+}
+
+fn serial_type(value: &Value<'_>) -> u64 {
+  match value {
+    Value::Null => 0,
+    Value::Integer(0) => 8,
+    Value::Integer(1) => 9,
+    Value::Integer(i) => match int_len(*i) {
+      1 => 1,
+      2 => 2,
+      3 => 3,
+      4 => 4,
+      6 => 5,
+      _ => 6,
+    },
+    Value::Real(_) => 7,
+    Value::Blob(b) => 12 + 2 * b.len() as u64,
+    Value::Text(s) => 13 + 2 * s.len() as u64,
+    Value::SharedText(s) => 13 + 2 * s.len() as u64,
+  }
+}
+
+/// 能无损表示 `i` 的最小整数字节数（1/2/3/4/6/8）
+fn int_len(i: i64) -> usize {
+  [1, 2, 3, 4, 6]
+    .into_iter()
+    .find(|&len| {
+      let bits = 8 * len as u32;
+      let min = -(1i64 << (bits - 1));
+      let max = (1i64 << (bits - 1)) - 1;
+      (min..=max).contains(&i)
+    })
+    .unwrap_or(8)
+}
+
+fn body_len(serial_type: u64) -> Result<usize, DecodeError> {
+  let len = match serial_type {
+    0 | 8 | 9 => 0,
+    1 => 1,
+    2 => 2,
+    3 => 3,
+    4 => 4,
+    5 => 6,
+    6 | 7 => 8,
+    t if t >= 12 => (t - 12) / 2,
+    t => return Err(DecodeError::InvalidSerialType(t)),
+  };
+  usize::try_from(len).map_err(|_| DecodeError::Truncated)
+}
+
+fn encode_body(value: &Value<'_>, out: &mut Vec<u8>) {
+  match value {
+    Value::Null | Value::Integer(0) | Value::Integer(1) => {}
+    Value::Integer(i) => {
+      let len = int_len(*i);
+      out.extend_from_slice(&i.to_be_bytes()[8 - len..]);
+    }
+    Value::Real(r) => out.extend_from_slice(&r.to_be_bytes()),
+    Value::Blob(b) => out.extend_from_slice(b),
+    Value::Text(s) => out.extend_from_slice(s.as_bytes()),
+    Value::SharedText(s) => out.extend_from_slice(s.as_bytes()),
+  }
+}
+
+/// debug 构建下核对刚编码的 body：长度与 serial type 一致，数值按 `RECORD_BYTE_ORDER` 能解码回原值
+///
+/// 用来发现编码/解码两侧字节序不一致（例如误用 `to_le_bytes`）。
+fn debug_assert_body_roundtrip(serial_type: u64, value: &Value<'_>, body: &[u8]) {
+  if cfg!(debug_assertions) {
+    debug_assert_eq!(
+      body_len(serial_type),
+      Ok(body.len()),
+      "body length mismatch"
+    );
+    if matches!(value, Value::Integer(_) | Value::Real(_)) {
+      let decoded = decode_body(0, serial_type, body, TextDecoding::Strict);
+      debug_assert!(
+        matches!(&decoded, Ok(v) if v == value || matches!(v, Value::Real(r) if r.is_nan())),
+        "record body byte order mismatch: {value:?} decoded as {decoded:?}"
+      );
+    }
+  }
+}
+
+fn decode_body(
+  index: usize,
+  serial_type: u64,
+  data: &[u8],
+  text: TextDecoding,
+) -> Result<Value<'static>, DecodeError> {
+  Ok(match serial_type {
+    0 => Value::Null,
+    8 => Value::Integer(0),
+    9 => Value::Integer(1),
+    1..=6 => {
+      // 符号扩展到 8 字节
+      let fill = if data[0] & 0x80 != 0 { 0xFF } else { 0x00 };
+      let mut buf = [fill; 8];
+      buf[8 - data.len()..].copy_from_slice(data);
+      Value::Integer(i64::from_be_bytes(buf))
+    }
+    7 => {
+      let mut buf = [0u8; 8];
+      buf.copy_from_slice(data);
+      Value::Real(f64::from_be_bytes(buf))
+    }
+    t if t % 2 == 0 => Value::Blob(Cow::Owned(data.to_vec())),
+    _ => {
+      let s = match text {
+        TextDecoding::Strict => {
+          String::from_utf8(data.to_vec()).map_err(|_| DecodeError::InvalidUtf8 { index })?
+        }
+        TextDecoding::Lossy => String::from_utf8_lossy(data).into_owned(),
+      };
+      Value::Text(Cow::Owned(s))
+    }
+  })
+}
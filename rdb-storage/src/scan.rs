@@ -0,0 +1,108 @@
+//! 表扫描：按 rowid 顺序遍历表 B+Tree 并解码行
+
+use rdb_domain::{PageId, Row, Table, Value};
+
+use crate::btree;
+use crate::cell::decode_leaf_cell;
+use crate::pager::{Pager, PagerError, Result};
+use crate::record::decode_row;
+
+/// 按 rowid 升序逐行解码表中的数据，对每一行调用 `f`
+///
+/// 一次只解码一个叶子 cell，不会把整张表读入内存；`f` 返回错误时立即停止扫描。
+pub fn scan_rows(
+  pager: &Pager<'_>,
+  table: &Table,
+  mut f: impl FnMut(Row<'static>) -> Result<()>,
+) -> Result<()> {
+  for leaf in btree::leaf_pages(pager, table.root_page)? {
+    let page = pager.page(leaf)?;
+    for i in 0..page.num_cells()? {
+      f(decode_cell(leaf, page.cell(i)?)?)?;
+    }
+  }
+  Ok(())
+}
+
+/// 以迭代器的形式按 rowid 升序扫描表，见 `RowStream`
+pub fn stream_rows<'a>(pager: &'a Pager<'_>, table: &Table) -> Result<RowStream<'a>> {
+  let mut leaves = btree::leaf_pages(pager, table.root_page)?.into_iter();
+  let mut buffered = Vec::new().into_iter();
+
+  let rows = std::iter::from_fn(move || loop {
+    if let Some(row) = buffered.next() {
+      return Some(Ok(row));
+    }
+    let leaf = leaves.next()?;
+    match leaf_rows(pager, leaf) {
+      Ok(rows) => buffered = rows.into_iter(),
+      Err(e) => {
+        // 出错后结束迭代，不再读取后面的叶子页
+        leaves = Vec::new().into_iter();
+        return Some(Err(e));
+      }
+    }
+  });
+
+  Ok(RowStream { rows: Box::new(rows) })
+}
+
+/// 行迭代器：轻量的查询管道
+///
+/// 每次解码一个叶子页的行，不持有页守卫，迭代期间可以读取其他页。
+/// 元素为 `Result<Row>`：存储层错误原样传给调用方，之后迭代结束；
+/// `filter`/`map_values` 只作用于成功的行。
+///
+/// # Examples
+///
+/// let big: Vec<Row> = stream_rows(&pager, &table)?
+///   .filter(|row| matches!(row.get(0), Some(Value::Integer(n)) if *n > 50))
+///   .collect::<Result<_>>()?;
+///
+pub struct RowStream<'a> {
+  rows: Box<dyn Iterator<Item = Result<Row<'static>>> + 'a>,
+}
+
+impl<'a> RowStream<'a> {
+  /// 只保留满足 `predicate` 的行
+  pub fn filter(self, predicate: impl Fn(&Row<'static>) -> bool + 'a) -> RowStream<'a> {
+    let rows = self.rows.filter(move |row| match row {
+      Ok(row) => predicate(row),
+      Err(_) => true,
+    });
+    RowStream { rows: Box::new(rows) }
+  }
+
+  /// 变换每一行的值（rowid 不变）
+  pub fn map_values(
+    self,
+    mut f: impl FnMut(Vec<Value<'static>>) -> Vec<Value<'static>> + 'a,
+  ) -> RowStream<'a> {
+    let rows = self
+      .rows
+      .map(move |row| row.map(|row| Row::new(row.row_id, f(row.values))));
+    RowStream { rows: Box::new(rows) }
+  }
+}
+
+impl Iterator for RowStream<'_> {
+  type Item = Result<Row<'static>>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.rows.next()
+  }
+}
+
+/// 解码一个叶子页中的全部行
+fn leaf_rows(pager: &Pager<'_>, leaf: PageId) -> Result<Vec<Row<'static>>> {
+  let page = pager.page(leaf)?;
+  (0..page.num_cells()?)
+    .map(|i| decode_cell(leaf, page.cell(i)?))
+    .collect()
+}
+
+fn decode_cell(leaf: PageId, cell: &[u8]) -> Result<Row<'static>> {
+  let (row_id, payload) = decode_leaf_cell(cell)
+    .ok_or_else(|| PagerError::BTreeInvariant(format!("corrupt leaf cell in {leaf:?}")))?;
+  Ok(decode_row(row_id, payload)?)
+}
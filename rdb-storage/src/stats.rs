@@ -0,0 +1,127 @@
+//! 表统计信息（供基于代价的查询规划使用）
+
+use std::cmp::Ordering;
+
+use rdb_domain::sort::compare_keys;
+use rdb_domain::{PageId, Table, Value};
+
+use crate::btree;
+use crate::cell::decode_leaf_cell;
+use crate::page::CellScan;
+use crate::pager::{Pager, PagerError, Result};
+use crate::record::decode_values;
+
+/// 单表统计
+///
+/// - `row_count`：行数（叶子页中未软删除的 cell 数之和）
+/// - `leaf_pages`：叶子页数量
+/// - `depth`：树高，只有根叶子页时为 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TableStats {
+  pub row_count: u64,
+  pub leaf_pages: u32,
+  pub depth: u8,
+}
+
+/// 遍历表的 B+Tree 收集统计信息
+///
+/// 每个页（包括内部页）只读取一次；不解码 payload。
+pub fn analyze_table(pager: &Pager<'_>, table: &Table) -> Result<TableStats> {
+  btree::analyze(pager, table.root_page)
+}
+
+/// 默认直方图桶数
+pub const DEFAULT_HISTOGRAM_BUCKETS: usize = 10;
+
+/// 等深直方图的一个桶：`upper` 为桶内最大键（包含），`count` 为桶内键数
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistogramBucket {
+  pub upper: Value<'static>,
+  pub count: u64,
+}
+
+/// 索引列的值分布统计（用于估算谓词选择率）
+///
+/// - `entry_count`：非 NULL 键数
+/// - `null_count`：NULL 键数（NULL 不参与排序，单独计数）
+/// - `distinct_keys`：不同键数
+/// - `buckets`：按 `sql_compare` 排序的等深直方图
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct IndexStats {
+  pub entry_count: u64,
+  pub null_count: u64,
+  pub distinct_keys: u64,
+  pub buckets: Vec<HistogramBucket>,
+}
+
+impl IndexStats {
+  /// 由已按 `sql_compare` 升序排列的键构建统计
+  ///
+  /// 各桶键数之差不超过 1；键数少于 `bucket_count` 时每个键一个桶。
+  /// 相等的键可能跨越桶边界，此时相邻桶的 `upper` 相同——这正是倾斜的体现。
+  pub fn from_sorted_keys<I>(keys: I, bucket_count: usize) -> Self
+  where
+    I: IntoIterator<Item = Value<'static>>,
+  {
+    let mut null_count = 0u64;
+    let mut sorted = Vec::new();
+    for key in keys {
+      if matches!(key, Value::Null) {
+        null_count += 1;
+      } else {
+        sorted.push(key);
+      }
+    }
+
+    let distinct_keys = match sorted.first() {
+      None => 0,
+      Some(_) => {
+        1 + sorted
+          .windows(2)
+          .filter(|w| w[0].sql_compare(&w[1]) != Some(Ordering::Equal))
+          .count() as u64
+      }
+    };
+
+    let n = sorted.len();
+    let bucket_count = bucket_count.min(n);
+    let mut buckets = Vec::with_capacity(bucket_count);
+    let mut start = 0;
+    for i in 0..bucket_count {
+      let end = (i + 1) * n / bucket_count;
+      buckets.push(HistogramBucket { upper: sorted[end - 1].clone(), count: (end - start) as u64 });
+      start = end;
+    }
+
+    Self { entry_count: n as u64, null_count, distinct_keys, buckets }
+  }
+}
+
+/// 遍历索引 B+Tree，收集键的分布统计（`DEFAULT_HISTOGRAM_BUCKETS` 个桶）
+///
+/// 索引项与 SQLite 的索引记录相同：键在前、rowid 在后的记录 `[key, rowid]`，
+/// 只使用第一个值。索引类型实现前，项存放在以项序号为 key 的普通 B+Tree 中，
+/// 叶子页内不按键排序，因此读出全部键后用 `compare_keys` 排序再构建直方图。
+/// 已标记删除的 cell 不计入统计。
+pub fn analyze_index(pager: &Pager<'_>, index_root: PageId) -> Result<IndexStats> {
+  let mut keys = Vec::new();
+  for leaf in btree::leaf_pages(pager, index_root)? {
+    let page = pager.page(leaf)?;
+    for cell in page.iter_cells(CellScan::SkipTombstones)? {
+      let (_, cell) = cell?;
+      let (_, payload) = decode_leaf_cell(cell)
+        .ok_or_else(|| PagerError::BTreeInvariant("corrupt index cell".to_string()))?;
+      let key = decode_values(payload)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| PagerError::BTreeInvariant("index entry without key".to_string()))?;
+      keys.push(key);
+    }
+  }
+
+  keys.sort_by(compare_keys);
+  Ok(IndexStats::from_sorted_keys(
+    keys,
+    DEFAULT_HISTOGRAM_BUCKETS,
+  ))
+}
@@ -1,37 +1,111 @@
-use std::fs::File;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 
+use rdb_infrastructure::file_io::write_all_at;
 use rdb_infrastructure::BufferPool;
 
-pub use crate::pager::{Pager, PagerError, Result};
+use crate::page::PAGE_SIZE;
+
+pub use crate::pager::{Pager, PagerError, PagerStats, Result};
 pub use rdb_domain::PageId;
 
 pub fn new_pager_for_test(file: File) -> Result<Pager<'static>> {
-  Pager::new(file, 4096, Arc::new(BufferPool))
+  Pager::new(file, 4096, Arc::new(BufferPool::default()))
 }
 
-// ---- wrappers for integration tests (Pager<'static>) ----
+pub fn new_read_only_pager_for_test(file: File) -> Result<Pager<'static>> {
+  Pager::new_read_only(file, 4096, Arc::new(BufferPool::default()))
+}
 
-pub fn pager_get_page<'a>(
-  pager: &'a Pager<'static>,
-  page_id: PageId,
-) -> Result<&'a crate::page::Page<'static>> {
-  pager.get_page(page_id)
+pub fn new_pager_with_capacity_for_test(file: File, capacity: usize) -> Result<Pager<'static>> {
+  Pager::new(file, 4096, Arc::new(BufferPool::new(capacity)))
+}
+
+/// 集成测试用的临时数据库文件，drop 时删除
+///
+/// 文件名由前缀、进程 ID、时间戳和进程内计数器组成，同一进程内并行运行的测试也不会冲突。
+/// TempDb 只持有路径，不持有 Pager：`reopen` 前需要先 drop 之前的 Pager，否则文件锁返回 `Locked`。
+pub struct TempDb {
+  path: PathBuf,
 }
 
-pub fn pager_get_page_mut<'a>(
-  pager: &'a mut Pager<'static>,
+impl TempDb {
+  /// 创建新的临时数据库并打开（见 `Pager::create`）
+  pub fn new(prefix: &str) -> Result<(Self, Pager<'static>)> {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let nanos = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .unwrap_or_default()
+      .as_nanos();
+    let seq = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let path =
+      std::env::temp_dir().join(format!("{prefix}_{}_{nanos}_{seq}.db", std::process::id()));
+
+    // 先构造 TempDb，打开失败时也会清理文件
+    let db = Self { path };
+    let pager = Pager::create(&db.path)?;
+    Ok((db, pager))
+  }
+
+  /// 在同一路径上重新打开数据库（见 `Pager::open`）
+  pub fn reopen(&self) -> Result<Pager<'static>> {
+    Pager::open(&self.path)
+  }
+
+  /// 数据库文件路径
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+}
+
+impl Drop for TempDb {
+  fn drop(&mut self) {
+    let _ = std::fs::remove_file(&self.path);
+  }
+}
+
+/// 把文件中第 `page_id` 页、页内偏移 `offset` 处的字节改为 `value` 并刷盘
+///
+/// 用于确定性地制造损坏（校验和、完整性检查等测试）。文件需要足够长；
+/// 页 ID 为 0 或 `offset` 超出页大小时返回 `InvalidInput`。
+/// 打开着的 Pager 可能已缓存该页，需要重新打开后才能看到损坏。
+pub fn corrupt_byte(
+  path: impl AsRef<Path>,
   page_id: PageId,
-) -> Result<&'a mut crate::page::Page<'static>> {
-  pager.get_page_mut(page_id)
+  offset: usize,
+  value: u8,
+) -> io::Result<()> {
+  let id = page_id.into_inner();
+  if id == 0 || offset >= PAGE_SIZE {
+    return Err(io::Error::new(
+      io::ErrorKind::InvalidInput,
+      format!("invalid corruption target: page {id}, offset {offset}"),
+    ));
+  }
+
+  let file = OpenOptions::new().write(true).open(path)?;
+  let off = (u64::from(id) - 1) * PAGE_SIZE as u64 + offset as u64;
+  write_all_at(&file, &[value], off)?;
+  file.sync_all()
 }
 
+// ---- wrappers for integration tests (Pager<'static>) ----
+
 pub fn pager_allocate_page(pager: &mut Pager<'static>) -> Result<PageId> {
   pager.allocate_page()
 }
 
+pub fn pager_allocate_page_raw(pager: &mut Pager<'static>) -> Result<PageId> {
+  pager.allocate_page_raw()
+}
+
 pub fn pager_flush_page(pager: &mut Pager<'static>, page_id: PageId) -> Result<()> {
-  pager.flush_page(page_id)
+  pager.flush_page(page_id).map(|_| ())
 }
 
 pub fn pager_flush_all(pager: &mut Pager<'static>) -> Result<()> {
@@ -0,0 +1,75 @@
+//! 整库压缩（VACUUM）
+//!
+//! 大量删除之后，文件中会留下空闲页和碎片。`vacuum` 按 rowid 顺序扫描源库的系统目录
+//! 和每棵表 B+Tree，把所有行批量装入一个新文件：
+//!
+//! - 新文件从空库开始（新的数据库头，没有 freelist）
+//! - 行按 rowid 升序追加，配合 `SplitPolicy::RightBiased` 得到接近满载的页
+//! - payload 原样复制（压缩过的 record 保持压缩），目录行只改写 `rootpage`
+//! - 软删除（墓碑）的 cell 不会被复制，在新文件中被物理删除
+//!
+//! 源库只读访问，不做任何修改；替换原文件（rename 等）由调用方负责。
+
+use std::fs::OpenOptions;
+use std::path::Path;
+
+use rdb_domain::{PageId, Value};
+
+use crate::btree::{self, SplitPolicy};
+use crate::catalog::{self, COL_ROOTPAGE};
+use crate::cell::decode_leaf_cell;
+use crate::page::CellScan;
+use crate::pager::{Pager, PagerError, PagerOptions, Result};
+use crate::record::{decode_values, encode_values};
+
+/// 把 `src` 的全部内容紧凑地重建到 `dst_path` 指向的新文件
+///
+/// `dst_path` 必须不存在（已存在时返回 `Io`（`AlreadyExists`），不会覆盖任何文件）。
+/// 返回前所有页都已写盘并 fsync。源库中尚未刷盘的修改同样会被复制，因为读取经过缓存。
+pub fn vacuum(src: &Pager<'_>, dst_path: impl AsRef<Path>) -> Result<()> {
+  let file = OpenOptions::new()
+    .read(true)
+    .write(true)
+    .create_new(true)
+    .open(dst_path)?;
+  let mut dst = Pager::from_file(file, PagerOptions::default())?;
+
+  let catalog_root = src.db_header()?.catalog_root;
+  for leaf in btree::leaf_pages(src, catalog_root)? {
+    let page = src.page(leaf)?;
+    for cell in page.iter_cells(CellScan::SkipTombstones)? {
+      let (_, cell) = cell?;
+      let (row_id, payload) = decode_leaf_cell(cell)
+        .ok_or_else(|| PagerError::BTreeInvariant("corrupt catalog cell".to_string()))?;
+      let mut values = decode_values(payload)?;
+      let root = values
+        .get(COL_ROOTPAGE)
+        .and_then(Value::as_integer)
+        .and_then(|id| u32::try_from(id).ok())
+        .ok_or_else(|| PagerError::BTreeInvariant("catalog row without rootpage".to_string()))?;
+
+      let new_root = copy_tree(src, &mut dst, PageId::new(root))?;
+      values[COL_ROOTPAGE] = Value::Integer(i64::from(new_root.into_inner()));
+      catalog::insert_row(&mut dst, row_id, &encode_values(&values))?;
+    }
+  }
+
+  dst.flush_all()?;
+  dst.sync()
+}
+
+/// 把 `src` 中以 `root` 为根的树按 rowid 顺序装入 `dst` 的一棵新树，返回新根页
+fn copy_tree(src: &Pager<'_>, dst: &mut Pager<'_>, root: PageId) -> Result<PageId> {
+  let mut new_root = btree::create(dst)?;
+  for leaf in btree::leaf_pages(src, root)? {
+    let page = src.page(leaf)?;
+    for cell in page.iter_cells(CellScan::SkipTombstones)? {
+      let (_, cell) = cell?;
+      let (row_id, payload) = decode_leaf_cell(cell)
+        .ok_or_else(|| PagerError::BTreeInvariant("corrupt leaf cell".to_string()))?;
+      new_root =
+        btree::insert_with_policy(dst, new_root, row_id, payload, SplitPolicy::RightBiased)?;
+    }
+  }
+  Ok(new_root)
+}
@@ -0,0 +1,62 @@
+//! SQLite 风格变长整数（varint）
+//!
+//! 1~9 字节、大端序：前 8 个字节每字节存 7 bit（最高位为续位标志），
+//! 第 9 个字节（如果有）完整存 8 bit，因此 9 字节可表示全部 64 bit。
+
+/// varint 最大字节数
+pub const MAX_VARINT_LEN: usize = 9;
+
+/// 计算 `v` 编码后的字节数
+pub fn encoded_len(v: u64) -> usize {
+  if v > 0x00FF_FFFF_FFFF_FFFF {
+    return MAX_VARINT_LEN;
+  }
+
+  let mut len = 1;
+  let mut rest = v >> 7;
+  while rest != 0 {
+    len += 1;
+    rest >>= 7;
+  }
+  len
+}
+
+/// 把 `v` 以 varint 编码追加到 `out`，返回写入的字节数
+pub fn encode(v: u64, out: &mut Vec<u8>) -> usize {
+  // 9 字节形式：前 8 字节各 7 bit，最后 1 字节 8 bit
+  if v > 0x00FF_FFFF_FFFF_FFFF {
+    let mut buf = [0u8; MAX_VARINT_LEN];
+    buf[8] = v as u8;
+    let mut rest = v >> 8;
+    for b in buf[..8].iter_mut().rev() {
+      *b = (rest as u8 & 0x7F) | 0x80;
+      rest >>= 7;
+    }
+    out.extend_from_slice(&buf);
+    return MAX_VARINT_LEN;
+  }
+
+  let len = encoded_len(v);
+  for i in (0..len).rev() {
+    let byte = ((v >> (7 * i)) & 0x7F) as u8;
+    out.push(if i == 0 { byte } else { byte | 0x80 });
+  }
+  len
+}
+
+/// 从 `buf` 开头解码一个 varint，返回 `(值, 消耗的字节数)`
+///
+/// `buf` 在 varint 结束前耗尽时返回 `None`。
+pub fn decode(buf: &[u8]) -> Option<(u64, usize)> {
+  let mut v: u64 = 0;
+  for (i, &byte) in buf.iter().enumerate().take(MAX_VARINT_LEN) {
+    if i == MAX_VARINT_LEN - 1 {
+      return Some(((v << 8) | u64::from(byte), MAX_VARINT_LEN));
+    }
+    v = (v << 7) | u64::from(byte & 0x7F);
+    if byte & 0x80 == 0 {
+      return Some((v, i + 1));
+    }
+  }
+  None
+}
@@ -0,0 +1,122 @@
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rdb_domain::{
+  Column, ColumnConstraints, ColumnId, DataType, DomainError, Row, RowId, Table, TableId, Value,
+};
+use rdb_storage::alter::backfill_column;
+use rdb_storage::btree;
+use rdb_storage::record::encode_row;
+use rdb_storage::scan::scan_rows;
+use rdb_storage::test_support::{new_pager_for_test, PagerError};
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+struct TempFile {
+  path: PathBuf,
+}
+
+impl TempFile {
+  fn new(prefix: &str) -> io::Result<(Self, File)> {
+    let mut path = std::env::temp_dir();
+
+    let nanos = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .unwrap_or_default()
+      .as_nanos();
+
+    path.push(format!("{prefix}_{}_{}.db", std::process::id(), nanos));
+
+    let file = OpenOptions::new()
+      .read(true)
+      .write(true)
+      .create(true)
+      .truncate(true)
+      .open(&path)?;
+
+    Ok((Self { path }, file))
+  }
+}
+
+impl Drop for TempFile {
+  fn drop(&mut self) {
+    let _ = std::fs::remove_file(&self.path);
+  }
+}
+
+fn table_with_rows(
+  pager: &mut rdb_storage::pager::Pager<'static>,
+  n: i64,
+) -> Result<Table, PagerError> {
+  let mut root = btree::create(pager)?;
+  for i in 1..=n {
+    let row = Row::new(RowId::new(i), vec![Value::Integer(i)]);
+    root = btree::insert(pager, root, row.row_id, &encode_row(&row))?;
+  }
+
+  Ok(Table::new(
+    TableId::new(1),
+    "t".to_string(),
+    vec![Column::new(ColumnId::new(1), "id".to_string(), DataType::Integer)],
+    None,
+    root,
+  ))
+}
+
+fn status_column(default_value: Option<Value<'static>>) -> Column {
+  let mut column = Column::with_constraints(
+    ColumnId::new(2),
+    "status".to_string(),
+    DataType::Text,
+    ColumnConstraints { not_null: true, ..Default::default() },
+  );
+  column.default_value = default_value;
+  column
+}
+
+#[test]
+fn backfill_column_materializes_default() -> TestResult {
+  let (_tmp, file) = TempFile::new("rdb_alter_backfill")?;
+  let mut pager = new_pager_for_test(file)?;
+  let mut table = table_with_rows(&mut pager, 300)?;
+
+  let column = status_column(Some(Value::Text("new".into())));
+  table.columns.push(column.clone());
+
+  assert_eq!(backfill_column(&mut pager, &mut table, &column)?, 300);
+
+  let mut seen = 0;
+  scan_rows(&pager, &table, |row| {
+    assert_eq!(
+      row.values,
+      [Value::Integer(row.row_id.into_inner()), Value::Text("new".into())]
+    );
+    seen += 1;
+    Ok(())
+  })?;
+  assert_eq!(seen, 300);
+
+  // 已回填的行不再重写
+  assert_eq!(backfill_column(&mut pager, &mut table, &column)?, 0);
+
+  Ok(())
+}
+
+#[test]
+fn backfill_not_null_column_without_default_is_rejected() -> TestResult {
+  let (_tmp, file) = TempFile::new("rdb_alter_backfill_no_default")?;
+  let mut pager = new_pager_for_test(file)?;
+  let mut table = table_with_rows(&mut pager, 3)?;
+
+  let column = status_column(None);
+  table.columns.push(column.clone());
+
+  assert!(matches!(
+    backfill_column(&mut pager, &mut table, &column),
+    Err(PagerError::Domain(DomainError::NotNullViolation { .. }))
+  ));
+
+  Ok(())
+}
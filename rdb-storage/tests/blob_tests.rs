@@ -0,0 +1,92 @@
+use rdb_domain::{Column, ColumnId, DataType, DomainError, Row, RowId, Table, TableId, Value};
+use rdb_storage::blob::open_blob;
+use rdb_storage::btree;
+use rdb_storage::record::encode_row;
+use rdb_storage::test_support::{Pager, PagerError, TempDb};
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+fn blob_table(pager: &mut Pager<'_>, blob: Vec<u8>) -> Result<Table, PagerError> {
+  let mut root = btree::create(pager)?;
+  for i in 1..=3 {
+    let row = Row::new(
+      RowId::new(i),
+      vec![Value::Integer(i), Value::Blob(blob.clone().into())],
+    );
+    root = btree::insert(pager, root, row.row_id, &encode_row(&row))?;
+  }
+  Ok(Table::new(
+    TableId::new(1),
+    "t".to_string(),
+    vec![
+      Column::new(ColumnId::new(1), "n".to_string(), DataType::Integer),
+      Column::new(ColumnId::new(2), "data".to_string(), DataType::Blob),
+    ],
+    None,
+    root,
+  ))
+}
+
+#[test]
+fn blob_chunked_write_then_read() -> TestResult {
+  let (_db, mut pager) = TempDb::new("rdb_blob_chunks")?;
+  let table = blob_table(&mut pager, vec![0; 900])?;
+
+  let expected: Vec<u8> = (0..900u32).map(|i| (i * 7 % 251) as u8).collect();
+  {
+    let mut blob = open_blob(&mut pager, &table, RowId::new(2), "data")?;
+    assert_eq!(blob.len(), 900);
+    for (i, chunk) in expected.chunks(128).enumerate() {
+      blob.write_at(i * 128, chunk)?;
+    }
+    let mut read = vec![0; 900];
+    for (i, chunk) in read.chunks_mut(100).enumerate() {
+      blob.read_at(i * 100, chunk)?;
+    }
+    assert_eq!(read, expected);
+
+    let err = blob.read_at(850, &mut [0; 51]).unwrap_err();
+    assert!(matches!(
+      err,
+      PagerError::BlobOutOfBounds { offset: 850, len: 51, size: 900 }
+    ));
+  }
+
+  // 通过普通读取路径可见，且未影响相邻行
+  let payload = btree::get(&pager, table.root_page, RowId::new(2))?.ok_or("row 2 missing")?;
+  let row = rdb_storage::record::decode_row(RowId::new(2), &payload)?;
+  assert_eq!(row.get(1), Some(&Value::Blob(expected.into())));
+  let blob = open_blob(&mut pager, &table, RowId::new(3), "data")?;
+  let mut read = vec![1; 900];
+  blob.read_at(0, &mut read)?;
+  assert!(read.iter().all(|&b| b == 0));
+
+  Ok(())
+}
+
+#[test]
+fn open_blob_rejects_missing_row_and_non_blob_column() -> TestResult {
+  let (_db, mut pager) = TempDb::new("rdb_blob_errors")?;
+  let table = blob_table(&mut pager, vec![1, 2, 3])?;
+
+  let err = open_blob(&mut pager, &table, RowId::new(9), "data")
+    .err()
+    .ok_or("expected error")?;
+  assert!(matches!(err, PagerError::RowNotFound(id) if id == RowId::new(9)));
+  let err = open_blob(&mut pager, &table, RowId::new(1), "n")
+    .err()
+    .ok_or("expected error")?;
+  assert!(matches!(
+    err,
+    PagerError::Domain(DomainError::TypeMismatch { .. })
+  ));
+  let err = open_blob(&mut pager, &table, RowId::new(1), "nope")
+    .err()
+    .ok_or("expected error")?;
+  assert!(matches!(
+    err,
+    PagerError::Domain(DomainError::ColumnNotFound { .. })
+  ));
+
+  Ok(())
+}
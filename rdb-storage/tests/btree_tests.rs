@@ -0,0 +1,524 @@
+use std::borrow::Cow;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rdb_domain::{Column, ColumnId, DataType, RowId, Table, TableId, Value};
+use rdb_storage::btree::{self, SplitPolicy};
+use rdb_storage::cell::decode_leaf_cell;
+use rdb_storage::page::{PAGE_HEADER_SIZE, PAGE_SIZE};
+use rdb_storage::record::encode_values;
+use rdb_storage::stats::{
+  analyze_index, analyze_table, IndexStats, TableStats, DEFAULT_HISTOGRAM_BUCKETS,
+};
+use rdb_storage::test_support::{new_pager_for_test, PagerError, TempDb};
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+struct TempFile {
+  path: PathBuf,
+}
+
+impl TempFile {
+  fn new(prefix: &str) -> io::Result<(Self, File)> {
+    let mut path = std::env::temp_dir();
+
+    let nanos = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .unwrap_or_default()
+      .as_nanos();
+
+    path.push(format!("{prefix}_{}_{}.db", std::process::id(), nanos));
+
+    let file = OpenOptions::new()
+      .read(true)
+      .write(true)
+      .create(true)
+      .truncate(true)
+      .open(&path)?;
+
+    Ok((Self { path }, file))
+  }
+}
+
+impl Drop for TempFile {
+  fn drop(&mut self) {
+    let _ = std::fs::remove_file(&self.path);
+  }
+}
+
+fn payload_for(row_id: i64, len: usize) -> Vec<u8> {
+  (0..len).map(|i| (row_id as usize + i) as u8).collect()
+}
+
+#[test]
+fn btree_insert_and_get_across_splits() -> TestResult {
+  let (_tmp, file) = TempFile::new("rdb_btree_insert_get")?;
+  let mut pager = new_pager_for_test(file)?;
+
+  let mut root = btree::create(&mut pager)?;
+
+  // 伪随机顺序插入，覆盖叶子页和内部页的分裂
+  let n: i64 = 12_000;
+  for i in 1..=n {
+    let row_id = (i * 7_919) % n + 1;
+    root = btree::insert(
+      &mut pager,
+      root,
+      RowId::new(row_id),
+      &payload_for(row_id, 200),
+    )?;
+  }
+
+  for row_id in 1..=n {
+    let payload = btree::get(&pager, root, RowId::new(row_id))?;
+    assert_eq!(payload, Some(payload_for(row_id, 200)), "rowid {row_id}");
+  }
+  assert_eq!(btree::get(&pager, root, RowId::new(n + 1))?, None);
+
+  Ok(())
+}
+
+#[test]
+fn btree_insert_rejects_duplicate_rowid() -> TestResult {
+  let (_tmp, file) = TempFile::new("rdb_btree_duplicate")?;
+  let mut pager = new_pager_for_test(file)?;
+
+  let root = btree::create(&mut pager)?;
+  let root = btree::insert(&mut pager, root, RowId::new(1), b"a")?;
+
+  match btree::insert(&mut pager, root, RowId::new(1), b"b") {
+    Err(PagerError::DuplicateRowId(id)) => assert_eq!(id, RowId::new(1)),
+    other => return Err(format!("expected DuplicateRowId, got {other:?}").into()),
+  }
+
+  Ok(())
+}
+
+#[test]
+fn btree_rowids_yields_all_rowids_in_order() -> TestResult {
+  let (_tmp, file) = TempFile::new("rdb_btree_rowids")?;
+  let mut pager = new_pager_for_test(file)?;
+
+  let mut root = btree::create(&mut pager)?;
+  assert_eq!(btree::rowids(&pager, root).count(), 0);
+
+  // 乱序插入 3 的倍数，足以跨多个叶子页
+  let n: i64 = 2_000;
+  for i in 1..=n {
+    let row_id = ((i * 7_919) % n + 1) * 3;
+    root = btree::insert(
+      &mut pager,
+      root,
+      RowId::new(row_id),
+      &payload_for(row_id, 50),
+    )?;
+  }
+  assert!(btree::leaf_pages(&pager, root)?.len() > 1);
+
+  let ids = btree::rowids(&pager, root).collect::<Result<Vec<_>, _>>()?;
+  let expected: Vec<RowId> = (1..=n).map(|i| RowId::new(i * 3)).collect();
+  assert_eq!(ids, expected);
+
+  // 软删除的行不再出现
+  let leaf = btree::leaf_pages(&pager, root)?[0];
+  pager.page_mut(leaf)?.tombstone_cell(1)?;
+  let ids = btree::rowids(&pager, root).collect::<Result<Vec<_>, _>>()?;
+  assert_eq!(ids.len(), expected.len() - 1);
+  assert!(!ids.contains(&RowId::new(6)));
+
+  Ok(())
+}
+
+#[test]
+fn btree_upsert_inserts_or_replaces() -> TestResult {
+  let (_tmp, file) = TempFile::new("rdb_btree_upsert")?;
+  let mut pager = new_pager_for_test(file)?;
+
+  // 新 rowid：插入
+  let mut root = btree::create(&mut pager)?;
+  for row_id in 1..=200 {
+    let (new_root, replaced) = btree::upsert(
+      &mut pager,
+      root,
+      RowId::new(row_id),
+      &payload_for(row_id, 100),
+    )?;
+    assert!(!replaced);
+    root = new_root;
+  }
+  let leaves = btree::leaf_pages(&pager, root)?.len();
+  assert!(leaves > 1);
+
+  // 等长：原地覆盖
+  let (root, replaced) = btree::upsert(&mut pager, root, RowId::new(50), &[7; 100])?;
+  assert!(replaced);
+  assert_eq!(
+    btree::get(&pager, root, RowId::new(50))?,
+    Some(vec![7; 100])
+  );
+
+  // 更长：最左叶子已满，只能分裂后重新放置
+  let (root, replaced) = btree::upsert(&mut pager, root, RowId::new(1), &[9; 900])?;
+  assert!(replaced);
+  assert_eq!(btree::get(&pager, root, RowId::new(1))?, Some(vec![9; 900]));
+  assert_eq!(btree::leaf_pages(&pager, root)?.len(), leaves + 1);
+
+  // 更短：页内替换
+  let (root, replaced) = btree::upsert(&mut pager, root, RowId::new(2), b"x")?;
+  assert!(replaced);
+  assert_eq!(
+    btree::get(&pager, root, RowId::new(2))?,
+    Some(b"x".to_vec())
+  );
+
+  assert_eq!(btree::count(&pager, root)?, 200);
+  for row_id in 3..=200 {
+    if row_id != 50 {
+      let payload = btree::get(&pager, root, RowId::new(row_id))?;
+      assert_eq!(payload, Some(payload_for(row_id, 100)), "rowid {row_id}");
+    }
+  }
+
+  Ok(())
+}
+
+/// 除最右叶子外，各叶子页的平均填充率
+fn left_leaf_fill(policy: SplitPolicy) -> Result<f64, Box<dyn std::error::Error>> {
+  let (_tmp, file) = TempFile::new("rdb_btree_split_policy")?;
+  let mut pager = new_pager_for_test(file)?;
+
+  let mut root = btree::create(&mut pager)?;
+  for row_id in 1..=1000 {
+    let payload = payload_for(row_id, 100);
+    root = btree::insert_with_policy(&mut pager, root, RowId::new(row_id), &payload, policy)?;
+  }
+
+  let leaves = btree::leaf_pages(&pager, root)?;
+  assert!(leaves.len() > 2);
+
+  let usable = (PAGE_SIZE - PAGE_HEADER_SIZE) as f64;
+  let mut total = 0.0;
+  for &leaf in &leaves[..leaves.len() - 1] {
+    let free = pager.page(leaf)?.free_space()? as f64;
+    total += (usable - free) / usable;
+  }
+  Ok(total / (leaves.len() - 1) as f64)
+}
+
+#[test]
+fn btree_split_policy_right_biased_fills_left_leaves() -> TestResult {
+  let right_biased = left_leaf_fill(SplitPolicy::RightBiased)?;
+  let half_half = left_leaf_fill(SplitPolicy::HalfHalf)?;
+
+  assert!(right_biased > 0.9, "right-biased fill {right_biased}");
+  assert!(
+    half_half > 0.4 && half_half < 0.6,
+    "half-half fill {half_half}"
+  );
+
+  Ok(())
+}
+
+#[test]
+fn btree_split_balances_bytes_for_uneven_rows() -> TestResult {
+  let (_tmp, file) = TempFile::new("rdb_btree_split_size")?;
+  let mut pager = new_pager_for_test(file)?;
+
+  // 3 个大行在前，随后是小行，直到第一次分裂
+  let mut root = btree::create(&mut pager)?;
+  let mut row_id = 0;
+  while btree::leaf_pages(&pager, root)?.len() < 2 {
+    row_id += 1;
+    let len = if row_id <= 3 { 900 } else { 20 };
+    let payload = payload_for(row_id, len);
+    root = btree::insert_with_policy(
+      &mut pager,
+      root,
+      RowId::new(row_id),
+      &payload,
+      SplitPolicy::HalfHalf,
+    )?;
+  }
+
+  // 按个数对半会让左页保留大行和一半小行；按字节对半时左页只有 3 个大行
+  let leaves = btree::leaf_pages(&pager, root)?;
+  let left = pager.page(leaves[0])?;
+  let right = pager.page(leaves[1])?;
+  assert_eq!(left.num_cells()?, 3);
+  assert_eq!(
+    u64::from(left.num_cells()? + right.num_cells()?),
+    row_id as u64
+  );
+  let used = |free: usize| PAGE_SIZE - PAGE_HEADER_SIZE - free;
+  let (left_used, right_used) = (used(left.free_space()?), used(right.free_space()?));
+  assert!(
+    left_used < 2 * right_used,
+    "left {left_used} right {right_used}"
+  );
+
+  Ok(())
+}
+
+#[test]
+fn btree_count_sums_leaf_cells() -> TestResult {
+  let (_tmp, file) = TempFile::new("rdb_btree_count")?;
+  let mut pager = new_pager_for_test(file)?;
+
+  let mut root = btree::create(&mut pager)?;
+  assert_eq!(btree::count(&pager, root)?, 0);
+
+  for row_id in 1..=777 {
+    root = btree::insert(
+      &mut pager,
+      root,
+      RowId::new(row_id),
+      &payload_for(row_id, 64),
+    )?;
+  }
+
+  assert!(btree::leaf_pages(&pager, root)?.len() > 1);
+  assert_eq!(btree::count(&pager, root)?, 777);
+
+  // 软删除的行不计入
+  let leaf = btree::leaf_pages(&pager, root)?[1];
+  {
+    let mut page = pager.page_mut(leaf)?;
+    page.tombstone_cell(0)?;
+    page.tombstone_cell(2)?;
+  }
+  assert_eq!(btree::count(&pager, root)?, 775);
+
+  Ok(())
+}
+
+#[test]
+fn btree_delete_existing_and_missing_rowid() -> TestResult {
+  let (_tmp, file) = TempFile::new("rdb_btree_delete")?;
+  let mut pager = new_pager_for_test(file)?;
+
+  let mut root = btree::create(&mut pager)?;
+  for row_id in 1..=10 {
+    root = btree::insert(
+      &mut pager,
+      root,
+      RowId::new(row_id),
+      &payload_for(row_id, 8),
+    )?;
+  }
+
+  assert!(btree::delete(&mut pager, root, RowId::new(4))?);
+  assert_eq!(btree::get(&pager, root, RowId::new(4))?, None);
+  assert_eq!(btree::count(&pager, root)?, 9);
+
+  assert!(!btree::delete(&mut pager, root, RowId::new(4))?);
+  assert!(!btree::delete(&mut pager, root, RowId::new(42))?);
+  assert_eq!(btree::count(&pager, root)?, 9);
+
+  Ok(())
+}
+
+#[test]
+fn btree_delete_reclaims_emptied_leaf() -> TestResult {
+  let (_db, mut pager) = TempDb::new("rdb_btree_delete_leaf")?;
+
+  let mut root = btree::create(&mut pager)?;
+  for row_id in 1..=300 {
+    root = btree::insert(
+      &mut pager,
+      root,
+      RowId::new(row_id),
+      &payload_for(row_id, 100),
+    )?;
+  }
+
+  let leaves = btree::leaf_pages(&pager, root)?;
+  assert!(leaves.len() > 3);
+
+  // 删光第二个叶子页中的所有行
+  let victim = leaves[1];
+  let page = pager.page(victim)?;
+  let row_ids: Vec<RowId> = (0..page.num_cells()?)
+    .map(|i| Ok(decode_leaf_cell(page.cell(i)?).ok_or("corrupt cell")?.0))
+    .collect::<Result<_, Box<dyn std::error::Error>>>()?;
+  drop(page);
+  for &row_id in &row_ids {
+    assert!(btree::delete(&mut pager, root, row_id)?);
+  }
+
+  let after = btree::leaf_pages(&pager, root)?;
+  assert_eq!(after.len(), leaves.len() - 1);
+  assert!(!after.contains(&victim));
+  assert_eq!(btree::count(&pager, root)?, 300 - row_ids.len() as u64);
+
+  // 被摘除的叶子页进入 freelist（freelist 原本为空，它自己成为链表头）
+  assert_eq!(pager.db_header()?.freelist_head, Some(victim));
+
+  // 其余行仍可查到，被删的行查不到
+  for row_id in 1..=300 {
+    let found = btree::get(&pager, root, RowId::new(row_id))?;
+    assert_eq!(
+      found.is_some(),
+      !row_ids.contains(&RowId::new(row_id)),
+      "rowid {row_id}"
+    );
+  }
+
+  // 删光全部行后根退化为空叶子页，仍可继续插入
+  for row_id in 1..=300 {
+    btree::delete(&mut pager, root, RowId::new(row_id))?;
+  }
+  assert_eq!(btree::count(&pager, root)?, 0);
+  let root = btree::insert(&mut pager, root, RowId::new(1), b"again")?;
+  assert_eq!(
+    btree::get(&pager, root, RowId::new(1))?,
+    Some(b"again".to_vec())
+  );
+
+  // 之后的分配复用被释放的页，文件不再增长
+  let pages = pager.describe()?.page_count;
+  let reused = btree::create(&mut pager)?;
+  assert_eq!(pager.describe()?.page_count, pages);
+  assert_eq!(btree::count(&pager, reused)?, 0);
+
+  Ok(())
+}
+
+#[test]
+fn analyze_table_gathers_tree_stats() -> TestResult {
+  let (_tmp, file) = TempFile::new("rdb_btree_analyze")?;
+  let mut pager = new_pager_for_test(file)?;
+
+  let root = btree::create(&mut pager)?;
+  let mut table = Table::new(
+    TableId::new(1),
+    "t".to_string(),
+    vec![Column::new(ColumnId::new(1), "v".to_string(), DataType::Blob)],
+    None,
+    root,
+  );
+
+  // 空树：只有一个根叶子页
+  assert_eq!(
+    analyze_table(&pager, &table)?,
+    TableStats { row_count: 0, leaf_pages: 1, depth: 1 }
+  );
+
+  let mut root = root;
+  for row_id in 1..=300 {
+    root = btree::insert(
+      &mut pager,
+      root,
+      RowId::new(row_id),
+      &payload_for(row_id, 100),
+    )?;
+  }
+  table.root_page = root;
+
+  let stats = analyze_table(&pager, &table)?;
+  assert_eq!(stats.row_count, 300);
+  assert_eq!(
+    stats.leaf_pages as usize,
+    btree::leaf_pages(&pager, root)?.len()
+  );
+  assert_eq!(stats.depth, 2);
+
+  Ok(())
+}
+
+#[test]
+fn index_stats_histogram_reflects_skew() {
+  // 80 个 1，其余 20 个为 2..=21，外加 3 个 NULL
+  let keys = std::iter::repeat(Value::Integer(1))
+    .take(80)
+    .chain((2..=21).map(Value::Integer))
+    .chain(std::iter::repeat(Value::Null).take(3));
+
+  let stats = IndexStats::from_sorted_keys(keys, DEFAULT_HISTOGRAM_BUCKETS);
+
+  assert_eq!(stats.entry_count, 100);
+  assert_eq!(stats.null_count, 3);
+  assert_eq!(stats.distinct_keys, 21);
+  assert_eq!(stats.buckets.len(), 10);
+  assert!(stats.buckets.iter().all(|b| b.count == 10));
+
+  // 前 8 个桶都被热点值 1 占满，剩下两个桶覆盖长尾
+  let uppers: Vec<_> = stats.buckets.iter().map(|b| b.upper.clone()).collect();
+  assert!(uppers[..8].iter().all(|v| *v == Value::Integer(1)));
+  assert_eq!(uppers[8], Value::Integer(11));
+  assert_eq!(uppers[9], Value::Integer(21));
+}
+
+#[test]
+fn index_stats_with_fewer_keys_than_buckets() {
+  let stats = IndexStats::from_sorted_keys([Value::Integer(5), Value::Integer(7)], 10);
+  assert_eq!(stats.buckets.len(), 2);
+  assert_eq!(stats.distinct_keys, 2);
+
+  assert_eq!(IndexStats::from_sorted_keys([], 10), IndexStats::default());
+}
+
+#[test]
+fn analyze_index_histogram_reflects_skew() -> TestResult {
+  let (_db, mut pager) = TempDb::new("rdb_btree_analyze_index")?;
+
+  // 2000 个索引项：1000 个 'ca'，其余 'a000'..'a999' 各一个，外加 5 个 NULL；
+  // 乱序插入，检验按键排序而不是按项序号
+  let mut keys: Vec<Value<'static>> = (0..1000)
+    .map(|i| Value::Text(Cow::Owned(format!("a{i:03}"))))
+    .chain(std::iter::repeat(Value::Text(Cow::Borrowed("ca"))).take(1000))
+    .chain(std::iter::repeat(Value::Null).take(5))
+    .collect();
+  keys.reverse();
+  keys.rotate_left(37);
+
+  let mut root = btree::create(&mut pager)?;
+  for (i, key) in keys.into_iter().enumerate() {
+    let entry = encode_values(&[key, Value::Integer(1000 + i as i64)]);
+    root = btree::insert(&mut pager, root, RowId::new(i as i64 + 1), &entry)?;
+  }
+  assert!(btree::leaf_pages(&pager, root)?.len() > 1);
+
+  let stats = analyze_index(&pager, root)?;
+  assert_eq!(stats.entry_count, 2000);
+  assert_eq!(stats.null_count, 5);
+  assert_eq!(stats.distinct_keys, 1001);
+  assert_eq!(stats.buckets.len(), DEFAULT_HISTOGRAM_BUCKETS);
+  assert!(stats.buckets.iter().all(|b| b.count == 200));
+
+  // 长尾的 1000 个键均匀落在前 5 个桶，热点值 'ca' 独占后 5 个桶
+  let uppers: Vec<_> = stats.buckets.iter().map(|b| b.upper.clone()).collect();
+  for (i, upper) in uppers[..5].iter().enumerate() {
+    assert_eq!(
+      upper.as_text(),
+      Some(format!("a{:03}", i * 200 + 199).as_str())
+    );
+  }
+  assert!(uppers[5..].iter().all(|v| v.as_text() == Some("ca")));
+
+  // 标记删除的索引项不计入
+  let leaf = btree::leaf_pages(&pager, root)?[0];
+  pager.page_mut(leaf)?.tombstone_cell(0)?;
+  let stats = analyze_index(&pager, root)?;
+  assert_eq!(stats.entry_count + stats.null_count, 2004);
+
+  Ok(())
+}
+
+#[test]
+fn btree_insert_rejects_payload_needing_overflow() -> TestResult {
+  let (_tmp, file) = TempFile::new("rdb_btree_overflow_boundary")?;
+  let mut pager = new_pager_for_test(file)?;
+  let root = btree::create(&mut pager)?;
+
+  let max = btree::MAX_PAYLOAD;
+  let root = btree::insert(&mut pager, root, RowId::new(1), &vec![1u8; max])?;
+  assert!(matches!(
+    btree::insert(&mut pager, root, RowId::new(2), &vec![1u8; max + 1]),
+    Err(PagerError::PayloadTooLarge { len, max: m }) if len == max + 1 && m == max
+  ));
+  assert_eq!(btree::count(&pager, root)?, 1);
+
+  Ok(())
+}
@@ -0,0 +1,190 @@
+use std::borrow::Cow;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rdb_domain::{
+  Column, ColumnConstraints, ColumnId, DataType, Row, RowId, Table, TableId, Value,
+};
+use rdb_storage::btree;
+use rdb_storage::csv::{export_csv, import_csv};
+use rdb_storage::record::encode_row;
+use rdb_storage::scan::scan_rows;
+use rdb_storage::test_support::{new_pager_for_test, PagerError};
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+struct TempFile {
+  path: PathBuf,
+}
+
+impl TempFile {
+  fn new(prefix: &str) -> io::Result<(Self, File)> {
+    let mut path = std::env::temp_dir();
+
+    let nanos = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .unwrap_or_default()
+      .as_nanos();
+
+    path.push(format!("{prefix}_{}_{}.db", std::process::id(), nanos));
+
+    let file = OpenOptions::new()
+      .read(true)
+      .write(true)
+      .create(true)
+      .truncate(true)
+      .open(&path)?;
+
+    Ok((Self { path }, file))
+  }
+}
+
+impl Drop for TempFile {
+  fn drop(&mut self) {
+    let _ = std::fs::remove_file(&self.path);
+  }
+}
+
+#[test]
+fn export_csv_writes_header_and_rows() -> TestResult {
+  let (_tmp, file) = TempFile::new("rdb_csv_export")?;
+  let mut pager = new_pager_for_test(file)?;
+
+  let mut root = btree::create(&mut pager)?;
+  let rows = [
+    Row::new(
+      RowId::new(2),
+      vec![
+        Value::Integer(2),
+        Value::Text(Cow::Borrowed("say \"hi\", bob")),
+        Value::Null,
+        Value::Real(1.5),
+      ],
+    ),
+    Row::new(
+      RowId::new(1),
+      vec![
+        Value::Integer(1),
+        Value::Text(Cow::Borrowed("plain")),
+        Value::Blob(Cow::Borrowed(&[0xDE, 0xAD])),
+        Value::Null,
+      ],
+    ),
+  ];
+  for row in &rows {
+    root = btree::insert(&mut pager, root, row.row_id, &encode_row(row))?;
+  }
+
+  let table = Table::new(
+    TableId::new(1),
+    "t".to_string(),
+    vec![
+      Column::new(ColumnId::new(1), "id".to_string(), DataType::Integer),
+      Column::new(ColumnId::new(2), "note, text".to_string(), DataType::Text),
+      Column::new(ColumnId::new(3), "data".to_string(), DataType::Blob),
+      Column::new(ColumnId::new(4), "score".to_string(), DataType::Real),
+    ],
+    None,
+    root,
+  );
+
+  let mut out = Vec::new();
+  export_csv(&pager, &table, &mut out)?;
+
+  // 按 rowid 顺序输出；NULL 为空字段，BLOB 为十六进制
+  assert_eq!(
+    String::from_utf8(out)?,
+    "id,\"note, text\",data,score\n\
+     1,plain,DEAD,\n\
+     2,\"say \"\"hi\"\", bob\",,1.5\n"
+  );
+
+  Ok(())
+}
+
+#[test]
+fn import_csv_inserts_rows_and_reports_bad_line() -> TestResult {
+  let (_tmp, file) = TempFile::new("rdb_csv_import")?;
+  let mut pager = new_pager_for_test(file)?;
+
+  let pk = ColumnConstraints { primary_key: true, ..Default::default() };
+  let not_null = ColumnConstraints { not_null: true, ..Default::default() };
+  let mut table = Table::new(
+    TableId::new(1),
+    "t".to_string(),
+    vec![
+      Column::with_constraints(ColumnId::new(1), "id".to_string(), DataType::Integer, pk),
+      Column::with_constraints(
+        ColumnId::new(2),
+        "name".to_string(),
+        DataType::Text,
+        not_null,
+      ),
+      Column::new(ColumnId::new(3), "score".to_string(), DataType::Real),
+      Column::new(ColumnId::new(4), "data".to_string(), DataType::Blob),
+    ],
+    Some(ColumnId::new(1)),
+    btree::create(&mut pager)?,
+  );
+
+  // 列顺序与表不同；引号内含换行和逗号
+  let csv = "name,id,score,data\n\
+             alice,10,1.5,CAFE\n\
+             \"multi\nline, \"\"q\"\"\",3,,\n\
+             \"\",7,2,\n";
+  assert_eq!(import_csv(&mut pager, &mut table, csv.as_bytes())?, 3);
+
+  let mut rows = Vec::new();
+  scan_rows(&pager, &table, |row| {
+    rows.push((row.row_id, row.values));
+    Ok(())
+  })?;
+  assert_eq!(
+    rows,
+    vec![
+      (
+        RowId::new(3),
+        vec![
+          Value::Integer(3),
+          Value::Text(Cow::Borrowed("multi\nline, \"q\"")),
+          Value::Null,
+          Value::Null,
+        ],
+      ),
+      (
+        RowId::new(7),
+        vec![Value::Integer(7), Value::Text(Cow::Borrowed("")), Value::Real(2.0), Value::Null],
+      ),
+      (
+        RowId::new(10),
+        vec![
+          Value::Integer(10),
+          Value::Text(Cow::Borrowed("alice")),
+          Value::Real(1.5),
+          Value::Blob(Cow::Borrowed(&[0xCA, 0xFE])),
+        ],
+      ),
+    ]
+  );
+
+  // 第 3 行的 id 不是整数
+  let bad = "id,name\n11,bob\nx,carol\n";
+  match import_csv(&mut pager, &mut table, bad.as_bytes()) {
+    Err(PagerError::Csv { line, message }) => {
+      assert_eq!(line, 3);
+      assert!(message.contains("'id'"), "{message}");
+    }
+    other => panic!("expected csv error, got {other:?}"),
+  }
+
+  // NOT NULL 校验失败同样带行号
+  let missing = "id,name\n12,\n";
+  assert!(matches!(
+    import_csv(&mut pager, &mut table, missing.as_bytes()),
+    Err(PagerError::Csv { line: 2, .. })
+  ));
+
+  Ok(())
+}
@@ -0,0 +1,101 @@
+use rdb_domain::{Column, ColumnId, DataType, PageId, RowId, SortDirection, Table, TableId};
+use rdb_storage::btree;
+use rdb_storage::cursor::Cursor;
+use rdb_storage::test_support::{Pager, PagerError, TempDb};
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+/// 插入 rowid 1..=n，payload 足够大使树有多个叶子页和内部页
+fn build_tree(pager: &mut Pager<'_>, n: i64) -> Result<PageId, PagerError> {
+  let mut root = btree::create(pager)?;
+  for i in 1..=n {
+    root = btree::insert(pager, root, RowId::new(i), &[i as u8; 300])?;
+  }
+  Ok(root)
+}
+
+fn collect(
+  cursor: &mut Cursor<'_, '_>,
+  mut valid: bool,
+  forward: bool,
+) -> Result<Vec<i64>, PagerError> {
+  let mut ids = Vec::new();
+  while valid {
+    ids.push(cursor.row_id()?.map_or(0, |id| id.into_inner()));
+    valid = if forward {
+      cursor.next()?
+    } else {
+      cursor.prev()?
+    };
+  }
+  Ok(ids)
+}
+
+#[test]
+fn cursor_reverse_iteration() -> TestResult {
+  let (_db, mut pager) = TempDb::new("rdb_cursor_reverse")?;
+  let root = build_tree(&mut pager, 30)?;
+  assert!(btree::leaf_pages(&pager, root)?.len() > 2);
+
+  let mut cursor = Cursor::new(&pager, root);
+  let valid = cursor.last()?;
+  assert_eq!(
+    collect(&mut cursor, valid, false)?,
+    (1..=30).rev().collect::<Vec<_>>()
+  );
+  assert!(!cursor.is_valid());
+  assert_eq!(cursor.row_id()?, None);
+
+  let valid = cursor.first()?;
+  assert_eq!(
+    collect(&mut cursor, valid, true)?,
+    (1..=30).collect::<Vec<_>>()
+  );
+
+  // 来回移动，跨越叶子页边界
+  cursor.first()?;
+  for _ in 0..20 {
+    cursor.next()?;
+  }
+  assert_eq!(cursor.row_id()?, Some(RowId::new(21)));
+  for _ in 0..15 {
+    cursor.prev()?;
+  }
+  assert_eq!(cursor.row_id()?, Some(RowId::new(6)));
+  assert_eq!(cursor.payload()?, Some(vec![6; 300]));
+
+  Ok(())
+}
+
+#[test]
+fn cursor_follows_table_order_and_skips_empty_leaves() -> TestResult {
+  let (_db, mut pager) = TempDb::new("rdb_cursor_order")?;
+  let root = build_tree(&mut pager, 1000)?;
+  // 删空中间的若干叶子页
+  for i in 300..=700 {
+    btree::delete(&mut pager, root, RowId::new(i))?;
+  }
+
+  let columns = vec![Column::new(ColumnId::new(1), "n".to_string(), DataType::Integer)];
+  let table = Table::new(TableId::new(1), "t".to_string(), columns, None, root)
+    .with_rowid_order(SortDirection::Descending);
+
+  let expected: Vec<i64> = (1..300).chain(701..=1000).rev().collect();
+  let mut cursor = Cursor::for_table(&pager, &table);
+  let mut valid = cursor.rewind()?;
+  let mut ids = Vec::new();
+  while valid {
+    ids.push(cursor.row_id()?.map_or(0, |id| id.into_inner()));
+    valid = cursor.step()?;
+  }
+  assert_eq!(ids, expected);
+
+  // 空树
+  let empty = btree::create(&mut pager)?;
+  let mut cursor = Cursor::new(&pager, empty);
+  assert!(!cursor.first()?);
+  assert!(!cursor.last()?);
+  assert!(!cursor.next()?);
+
+  Ok(())
+}
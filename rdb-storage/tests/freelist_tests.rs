@@ -0,0 +1,67 @@
+use rdb_domain::PageId;
+use rdb_storage::freelist::{Freelist, FREELIST_CAPACITY};
+use rdb_storage::page::{Page, PageError, PageType};
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+#[test]
+fn freelist_new_page_is_empty() -> TestResult {
+  let mut page = Page::new(PageId::new(2), PageType::Freelist);
+
+  let list = Freelist::read(&page)?;
+  assert_eq!(list.next, None);
+  assert!(list.ids.is_empty());
+  assert_eq!(Freelist::pop(&mut page)?, None);
+
+  Ok(())
+}
+
+#[test]
+fn freelist_rejects_non_freelist_page() {
+  let mut page = Page::new(PageId::new(2), PageType::Leaf);
+
+  assert_eq!(
+    Freelist::push(&mut page, PageId::new(9)),
+    Err(PageError::UnsupportedPageType(PageType::Leaf))
+  );
+}
+
+#[test]
+fn freelist_push_chains_and_pops_lifo() -> TestResult {
+  let mut first = Page::new(PageId::new(2), PageType::Freelist);
+  let mut second = Page::new(PageId::new(3), PageType::Freelist);
+
+  // 压到第一页写满，剩余的写入第二页，并由第二页指向第一页（新页作为链表头）
+  let total = FREELIST_CAPACITY + 10;
+  for id in 0..total as u32 {
+    let id = PageId::new(100 + id);
+    if !Freelist::is_full(&first)? {
+      Freelist::push(&mut first, id)?;
+    } else {
+      Freelist::push(&mut second, id)?;
+    }
+  }
+  assert!(matches!(
+    Freelist::push(&mut first, PageId::new(1)),
+    Err(PageError::PageFull { .. })
+  ));
+  Freelist::set_next(&mut second, Some(first.page_id()))?;
+
+  let list = Freelist::read(&second)?;
+  assert_eq!(list.next, Some(PageId::new(2)));
+  assert_eq!(list.ids.len(), 10);
+  assert_eq!(Freelist::read(&first)?.ids.len(), FREELIST_CAPACITY);
+
+  // 从链表头开始弹出，顺序与压入相反
+  let mut popped = Vec::new();
+  for page in [&mut second, &mut first] {
+    while let Some(id) = Freelist::pop(page)? {
+      popped.push(id.into_inner());
+    }
+  }
+  let expected: Vec<u32> = (0..total as u32).rev().map(|id| 100 + id).collect();
+  assert_eq!(popped, expected);
+  assert!(Freelist::read(&first)?.ids.is_empty());
+
+  Ok(())
+}
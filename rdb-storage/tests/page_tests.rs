@@ -1,5 +1,9 @@
-use rdb_domain::PageId;
-use rdb_storage::page::{Page, PageHeader, PageType, OFF_PAGE_TYPE, PAGE_HEADER_SIZE};
+use rdb_domain::{DomainError, PageId, RowId, TransactionId};
+use rdb_storage::cell::{decode_leaf_cell, encode_internal_cell, encode_leaf_cell};
+use rdb_storage::page::{
+  decode_header, CellScan, HeaderError, LayoutError, Page, PageError, PageHeader, PageType,
+  MAX_LOCAL_PAYLOAD, OFF_PAGE_TYPE, PAGE_HEADER_SIZE, PAGE_HEADER_VERSION, PAGE_SIZE,
+};
 
 type TestResult = Result<(), Box<dyn std::error::Error>>;
 
@@ -33,6 +37,29 @@ fn page_from_bytes_rejects_invalid_page_type() {
   assert!(r.is_err());
 }
 
+#[test]
+fn page_from_bytes_unchecked_accepts_unknown_type() -> TestResult {
+  let mut data = [0u8; 4096];
+  data[OFF_PAGE_TYPE] = 0xFF;
+  data[100] = 0xAB;
+
+  // 严格路径仍然拒绝，绕过路径保留原始字节
+  assert!(Page::from_bytes(PageId::new(1), data).is_err());
+  let page = Page::from_bytes_unchecked(PageId::new(1), data);
+  assert_eq!(page.data(), &data);
+  assert!(!page.is_dirty());
+  assert!(matches!(
+    page.page_type_checked(),
+    Err(HeaderError::InvalidPageType(_))
+  ));
+
+  let valid = Page::new(PageId::new(2), PageType::Leaf);
+  let page = Page::from_bytes_unchecked(PageId::new(2), *valid.data());
+  assert_eq!(page.page_type_checked()?, PageType::Leaf);
+
+  Ok(())
+}
+
 #[test]
 fn page_write_header_roundtrip() -> TestResult {
   let mut page = Page::new(PageId::new(1), PageType::Internal);
@@ -83,3 +110,622 @@ fn page_header_bytes_are_written_to_first_32_bytes() -> TestResult {
 
   Ok(())
 }
+
+#[test]
+fn page_write_identical_header_does_not_mark_dirty() -> TestResult {
+  let mut page = Page::new(PageId::new(1), PageType::Leaf);
+  assert!(!page.is_dirty());
+
+  let header = page.try_parse_header()?;
+  page.write_header(&header);
+  assert!(!page.is_dirty());
+
+  let mut changed = header;
+  changed.num_cells = 1;
+  page.write_header(&changed);
+  assert!(page.is_dirty());
+
+  page.mark_clean();
+  assert!(!page.is_dirty());
+
+  // 只读访问不影响脏标记
+  let _ = page.data();
+  assert!(!page.is_dirty());
+
+  Ok(())
+}
+
+#[test]
+fn page_insert_cell_ordered_returns_index() -> TestResult {
+  let mut page = Page::new(PageId::new(1), PageType::Leaf);
+
+  let mut indices = Vec::new();
+  for rowid in [30, 10, 50, 20, 40, 5] {
+    indices.push(page.insert_cell_ordered(&encode_leaf_cell(RowId::new(rowid), b"payload"))?);
+  }
+  // 每次返回的是插入时刻的位置
+  assert_eq!(indices, [0, 0, 2, 1, 3, 0]);
+
+  let mut rowids = Vec::new();
+  for cell in page.iter_cells(CellScan::All)? {
+    let (_, cell) = cell?;
+    rowids.push(decode_leaf_cell(cell).ok_or("corrupt cell")?.0.into_inner());
+  }
+  assert_eq!(rowids, [5, 10, 20, 30, 40, 50]);
+
+  assert!(matches!(
+    page.insert_cell_ordered(&encode_leaf_cell(RowId::new(20), b"dup")),
+    Err(PageError::DuplicateRowId(id)) if id == RowId::new(20)
+  ));
+  assert_eq!(page.num_cells()?, 6);
+
+  // 内部页按 key 排序，跳过子页指针
+  let mut internal = Page::new(PageId::new(2), PageType::Internal);
+  internal.insert_cell_ordered(&encode_internal_cell(PageId::new(7), RowId::new(200)))?;
+  let index =
+    internal.insert_cell_ordered(&encode_internal_cell(PageId::new(8), RowId::new(100)))?;
+  assert_eq!(index, 0);
+  assert_eq!(internal.find_cell_by_rowid(RowId::new(200))?, Ok(1));
+
+  Ok(())
+}
+
+/// rowid 1、2、3 的叶子页，再用底层的 `insert_cell` 把 rowid 10 插到最前面
+fn page_with_out_of_order_cell() -> Result<Page<'static>, PageError> {
+  let mut page = Page::new(PageId::new(1), PageType::Leaf);
+  for rowid in 1..=3 {
+    page.insert_cell_ordered(&encode_leaf_cell(RowId::new(rowid), b"v"))?;
+  }
+  page.insert_cell(0, &encode_leaf_cell(RowId::new(10), b"v"))?;
+  Ok(page)
+}
+
+#[test]
+fn page_check_rowid_order_detects_out_of_order_cell() -> TestResult {
+  let mut page = Page::new(PageId::new(1), PageType::Leaf);
+  assert_eq!(page.check_rowid_order(), Ok(()));
+  for rowid in [3, 1, 2] {
+    page.insert_cell_ordered(&encode_leaf_cell(RowId::new(rowid), b"v"))?;
+  }
+  assert_eq!(page.check_rowid_order(), Ok(()));
+
+  let page = page_with_out_of_order_cell()?;
+  assert!(matches!(
+    page.check_rowid_order(),
+    Err(DomainError::InvariantViolation { message }) if message.contains("cell 1")
+  ));
+
+  // 不存放 cell 的页类型不检查
+  assert_eq!(
+    Page::new(PageId::new(2), PageType::Overflow).check_rowid_order(),
+    Ok(())
+  );
+
+  Ok(())
+}
+
+#[cfg(feature = "strict")]
+#[test]
+fn page_insert_cell_ordered_rejects_broken_order_in_strict_mode() -> TestResult {
+  let mut page = page_with_out_of_order_cell()?;
+  let before = *page.data();
+  assert!(matches!(
+    page.insert_cell_ordered(&encode_leaf_cell(RowId::new(5), b"v")),
+    Err(PageError::Invariant(DomainError::InvariantViolation { .. }))
+  ));
+
+  // 在修改页之前就拒绝：字节（包括 cell 数和 content area）保持不变
+  assert_eq!(page.data(), &before);
+  assert_eq!(page.num_cells()?, 4);
+  Ok(())
+}
+
+#[cfg(all(debug_assertions, not(feature = "strict")))]
+#[test]
+#[should_panic(expected = "out of rowid order")]
+fn page_insert_cell_ordered_asserts_order_in_debug_builds() {
+  if let Ok(mut page) = page_with_out_of_order_cell() {
+    let _ = page.insert_cell_ordered(&encode_leaf_cell(RowId::new(5), b"v"));
+  }
+}
+
+#[test]
+fn page_insert_cell_reuses_best_fit_freeblock() -> TestResult {
+  let mut page = Page::new(PageId::new(1), PageType::Leaf);
+  let small = encode_leaf_cell(RowId::new(1), &[1; 20]);
+  let large = encode_leaf_cell(RowId::new(2), &[2; 60]);
+  for (i, cell) in [&small, &large, &small, &small].into_iter().enumerate() {
+    page.insert_cell(i as u16, cell)?;
+  }
+  assert_eq!(page.free_blocks()?.count(), 0);
+
+  // 删除中间的两个 cell（不在 content area 头部），各自变成 freeblock
+  let large_offset = PAGE_SIZE - small.len() - large.len();
+  let small_offset = large_offset - small.len();
+  page.remove_cell(2)?;
+  page.remove_cell(1)?;
+  let blocks: Vec<(u16, u16)> = page.free_blocks()?.collect();
+  assert_eq!(
+    blocks,
+    [(large_offset as u16, large.len() as u16), (small_offset as u16, small.len() as u16)]
+  );
+  let area = page.try_parse_header()?.cell_content_area;
+  let free = page.free_space()?;
+
+  // 小 cell 选择大小恰好的块（best fit），而不是链表头部更大的块
+  page.insert_cell(1, &encode_leaf_cell(RowId::new(3), &[3; 20]))?;
+  assert_eq!(
+    page.free_blocks()?.collect::<Vec<_>>(),
+    [(large_offset as u16, large.len() as u16)]
+  );
+  assert_eq!(
+    page.cell(1)?,
+    encode_leaf_cell(RowId::new(3), &[3; 20]).as_slice()
+  );
+
+  // 更小的 cell 从大块尾部切出，剩余部分仍是 freeblock
+  let tiny = encode_leaf_cell(RowId::new(4), &[4; 10]);
+  page.insert_cell(2, &tiny)?;
+  let rest = large.len() - tiny.len();
+  assert_eq!(
+    page.free_blocks()?.collect::<Vec<_>>(),
+    [(large_offset as u16, rest as u16)]
+  );
+  assert_eq!(page.cell(2)?, tiny.as_slice());
+
+  // 复用 freeblock 不消耗 content area，只多占一个 cell pointer
+  let header = page.try_parse_header()?;
+  assert_eq!(header.cell_content_area, area);
+  assert_eq!(page.free_space()?, free - small.len() - tiny.len() - 4);
+
+  Ok(())
+}
+
+#[test]
+fn page_remove_cell_defragments_instead_of_overflowing_fragment_count() -> TestResult {
+  let mut page = Page::new(PageId::new(1), PageType::Leaf);
+  // 空 payload 的 cell 只有 2 字节，删除后只能计入碎片
+  let cells: Vec<Vec<u8>> = (1..=40)
+    .map(|rowid| encode_leaf_cell(RowId::new(rowid), &[]))
+    .collect();
+  for (i, cell) in cells.iter().enumerate() {
+    page.insert_cell(i as u16, cell)?;
+  }
+  let free = page.free_space()?;
+
+  // 删除第 0 个 cell（位于页尾，不在 content area 头部），每次产生 2 字节碎片
+  for removed in 1..=30 {
+    page.remove_cell(0)?;
+    assert_eq!(
+      page.try_parse_header()?.fragmented_bytes as usize,
+      removed * 2
+    );
+  }
+
+  // 第 31 次会越过 60 字节上限：整理整页而不是截断计数
+  page.remove_cell(0)?;
+  let header = page.try_parse_header()?;
+  assert_eq!(header.fragmented_bytes, 0);
+  assert_eq!(header.first_freeblock, 0);
+  assert_eq!(page.free_space()?, free + 31 * (cells[0].len() + 2));
+  for (i, original) in cells[31..].iter().enumerate() {
+    assert_eq!(page.cell(i as u16)?, original.as_slice());
+  }
+  page.validate_layout()?;
+
+  Ok(())
+}
+
+#[test]
+fn page_insert_cell_skips_freeblock_that_would_overflow_fragment_count() -> TestResult {
+  let mut page = Page::new(PageId::new(1), PageType::Leaf);
+  let small = encode_leaf_cell(RowId::new(1), &[1; 4]);
+  for (i, rowid) in [1, 2, 3].into_iter().enumerate() {
+    page.insert_cell(i as u16, &encode_leaf_cell(RowId::new(rowid), &[1; 4]))?;
+  }
+  // 中间的 cell 变成 6 字节的 freeblock
+  page.remove_cell(1)?;
+  let block_offset = (PAGE_SIZE - 2 * small.len()) as u16;
+  assert_eq!(
+    page.free_blocks()?.collect::<Vec<_>>(),
+    [(block_offset, small.len() as u16)]
+  );
+
+  // 碎片计数已接近上限：4 字节 cell 会留下 2 字节零头，使计数达到 61，不能复用该块
+  let mut header = page.try_parse_header()?;
+  header.fragmented_bytes = 59;
+  page.write_header(&header);
+  let tiny = encode_leaf_cell(RowId::new(2), &[2; 2]);
+  page.insert_cell(1, &tiny)?;
+  let after = page.try_parse_header()?;
+  assert_eq!(after.fragmented_bytes, 59);
+  assert_eq!(
+    after.cell_content_area as usize,
+    header.cell_content_area as usize - tiny.len()
+  );
+  assert_eq!(
+    page.free_blocks()?.collect::<Vec<_>>(),
+    [(block_offset, small.len() as u16)]
+  );
+
+  // 计数留有余量时照常复用，零头计入碎片
+  page.remove_cell(1)?;
+  let mut header = page.try_parse_header()?;
+  header.fragmented_bytes = 58;
+  page.write_header(&header);
+  page.insert_cell(1, &tiny)?;
+  let after = page.try_parse_header()?;
+  assert_eq!(after.fragmented_bytes, 60);
+  assert_eq!(after.first_freeblock, 0);
+  assert_eq!(page.cell(1)?, tiny.as_slice());
+
+  Ok(())
+}
+
+#[test]
+fn page_split_point_by_size_balances_bytes() -> TestResult {
+  let mut page = Page::new(PageId::new(1), PageType::Leaf);
+  // 一个大 cell 后跟 9 个小 cell：按个数对半会把几乎全部字节留在左页
+  page.insert_cell(0, &encode_leaf_cell(RowId::new(1), &[0; 900]))?;
+  for i in 1..10u16 {
+    page.insert_cell(i, &encode_leaf_cell(RowId::new(i64::from(i) + 1), &[0; 20]))?;
+  }
+  let count_midpoint = page.num_cells()? / 2;
+  let split = page.split_point_by_size()?;
+  assert_eq!(count_midpoint, 5);
+  assert_eq!(split, 1);
+
+  // 大小相同时与按个数对半一致
+  let mut uniform = Page::new(PageId::new(2), PageType::Leaf);
+  for i in 0..8u16 {
+    uniform.insert_cell(i, &encode_leaf_cell(RowId::new(i64::from(i)), &[0; 50]))?;
+  }
+  assert_eq!(uniform.split_point_by_size()?, 4);
+
+  // 无法分裂的页
+  assert_eq!(
+    Page::new(PageId::new(3), PageType::Leaf).split_point_by_size()?,
+    0
+  );
+
+  Ok(())
+}
+
+#[test]
+fn page_validate_layout_detects_corruption() -> TestResult {
+  let mut valid = Page::new(PageId::new(1), PageType::Leaf);
+  for i in 0..3u16 {
+    valid.insert_cell(i, &encode_leaf_cell(RowId::new(i64::from(i)), b"payload"))?;
+  }
+  valid.tombstone_cell(1)?;
+  assert_eq!(valid.validate_layout(), Ok(()));
+  assert_eq!(
+    Page::new(PageId::new(2), PageType::Leaf).validate_layout(),
+    Ok(())
+  );
+  let area = valid.try_parse_header()?.cell_content_area as usize;
+
+  // content area 伸进 pointer array
+  let mut page = Page::from_bytes(PageId::new(1), *valid.data())?;
+  let mut h = page.try_parse_header()?;
+  h.cell_content_area = (PAGE_HEADER_SIZE + 2) as u16;
+  page.write_header(&h);
+  assert_eq!(
+    page.validate_layout(),
+    Err(LayoutError::ContentAreaOverlapsPointers {
+      cell_content_area: PAGE_HEADER_SIZE + 2,
+      pointer_array_end: PAGE_HEADER_SIZE + 6,
+    })
+  );
+
+  // cell 数过大，pointer array 越过 content area
+  let mut page = Page::from_bytes(PageId::new(1), *valid.data())?;
+  let mut h = page.try_parse_header()?;
+  h.num_cells = 2040;
+  page.write_header(&h);
+  assert!(matches!(
+    page.validate_layout(),
+    Err(LayoutError::ContentAreaOverlapsPointers { .. })
+  ));
+
+  // content area 越过页尾
+  let mut page = Page::from_bytes(PageId::new(1), *valid.data())?;
+  let mut h = page.try_parse_header()?;
+  h.cell_content_area = 5000;
+  page.write_header(&h);
+  assert_eq!(
+    page.validate_layout(),
+    Err(LayoutError::ContentAreaPastEnd { cell_content_area: 5000 })
+  );
+
+  // cell pointer 指向 pointer array / 页尾之外
+  for (index, offset) in [(0u16, PAGE_HEADER_SIZE + 4), (2, PAGE_SIZE), (1, area - 1)] {
+    let mut page = Page::from_bytes(PageId::new(1), *valid.data())?;
+    let ptr = PAGE_HEADER_SIZE + usize::from(index) * 2;
+    page.data_mut()[ptr..ptr + 2].copy_from_slice(&(offset as u16).to_le_bytes());
+    assert_eq!(
+      page.validate_layout(),
+      Err(LayoutError::CellPointerOutOfRange { index, offset, cell_content_area: area })
+    );
+  }
+
+  Ok(())
+}
+
+#[test]
+fn page_find_cell_by_rowid_binary_search() -> TestResult {
+  let mut page = Page::new(PageId::new(1), PageType::Leaf);
+
+  // rowid 有序：10, 20, ..., 70
+  for (i, rowid) in (1..=7).map(|k| k * 10).enumerate() {
+    page.insert_cell(i as u16, &encode_leaf_cell(RowId::new(rowid), b"payload"))?;
+  }
+
+  assert_eq!(page.find_cell_by_rowid(RowId::new(10))?, Ok(0));
+  assert_eq!(page.find_cell_by_rowid(RowId::new(40))?, Ok(3));
+  assert_eq!(page.find_cell_by_rowid(RowId::new(70))?, Ok(6));
+
+  // 不存在的 key 返回插入位置
+  assert_eq!(page.find_cell_by_rowid(RowId::new(5))?, Err(0));
+  assert_eq!(page.find_cell_by_rowid(RowId::new(35))?, Err(3));
+  assert_eq!(page.find_cell_by_rowid(RowId::new(99))?, Err(7));
+
+  // 空页
+  let empty = Page::new(PageId::new(2), PageType::Leaf);
+  assert_eq!(empty.find_cell_by_rowid(RowId::new(1))?, Err(0));
+
+  Ok(())
+}
+
+#[test]
+fn page_find_cell_by_rowid_on_internal_page() -> TestResult {
+  let mut page = Page::new(PageId::new(1), PageType::Internal);
+  for (i, rowid) in [100, 200, 300].into_iter().enumerate() {
+    let cell = encode_internal_cell(PageId::new(i as u32 + 2), RowId::new(rowid));
+    page.insert_cell(i as u16, &cell)?;
+  }
+
+  assert_eq!(page.find_cell_by_rowid(RowId::new(200))?, Ok(1));
+  assert_eq!(page.find_cell_by_rowid(RowId::new(250))?, Err(2));
+
+  Ok(())
+}
+
+#[test]
+fn page_defragment_reports_reclaimed_space() -> TestResult {
+  let mut page = Page::new(PageId::new(1), PageType::Leaf);
+
+  let cells: Vec<Vec<u8>> = (1..=6)
+    .map(|rowid| encode_leaf_cell(RowId::new(rowid), &[rowid as u8; 40]))
+    .collect();
+  for (i, cell) in cells.iter().enumerate() {
+    page.insert_cell(i as u16, cell)?;
+  }
+
+  // 删除第 2、4 个 cell，在内容区中间留下空洞
+  page.remove_cell(3)?;
+  page.remove_cell(1)?;
+  let freed = cells[1].len() + cells[3].len();
+
+  page.mark_clean();
+  let stats = page.defragment()?;
+  assert_eq!(stats.reclaimed_bytes, freed);
+  // 位于空洞下方的 cell 3、5、6 需要上移
+  assert_eq!(stats.cells_moved, 3);
+  assert!(page.is_dirty());
+
+  // 剩余 cell 内容不变
+  for (i, original) in [&cells[0], &cells[2], &cells[4], &cells[5]]
+    .into_iter()
+    .enumerate()
+  {
+    assert_eq!(page.cell(i as u16)?, original.as_slice());
+  }
+
+  // 再次整理没有可回收的空间，也不会产生脏页
+  page.mark_clean();
+  let again = page.defragment()?;
+  assert_eq!(again.reclaimed_bytes, 0);
+  assert_eq!(again.cells_moved, 0);
+  assert!(!page.is_dirty());
+
+  Ok(())
+}
+
+#[test]
+fn page_reserved_bytes_roundtrip() -> TestResult {
+  let mut page = Page::new(PageId::new(1), PageType::Leaf);
+  page.set_reserved([1, 2, 3, 4, 5, 6, 7, 8]);
+  assert!(page.is_dirty());
+
+  // 经过字节层面的往返（等同于刷盘后重新读入）
+  let reloaded = Page::from_bytes(PageId::new(1), *page.data())?;
+  let header = reloaded.try_parse_header()?;
+  assert_eq!(header.reserved_bytes(), [1, 2, 3, 4, 5, 6, 7, 8]);
+  assert_eq!(header.node_id(), 0x0403_0201);
+  assert_eq!(header.epoch(), 0x0807_0605);
+
+  // 事务 stamp 与集群元数据共用 reserved 字段
+  assert_eq!(
+    reloaded.txn(),
+    TransactionId::new(u64::from_le_bytes([1, 2, 3, 4, 5, 6, 7, 8]))
+  );
+
+  // 结构化访问器只改各自的 4 字节
+  let mut header = header;
+  header.set_node_id(7);
+  header.set_epoch(3);
+  assert_eq!(header.reserved_bytes(), [7, 0, 0, 0, 3, 0, 0, 0]);
+
+  Ok(())
+}
+
+#[test]
+fn page_tombstone_hides_cell_until_vacuum() -> TestResult {
+  let mut page = Page::new(PageId::new(1), PageType::Leaf);
+  let cells: Vec<Vec<u8>> = (1..=4)
+    .map(|rowid| encode_leaf_cell(RowId::new(rowid), &[rowid as u8; 16]))
+    .collect();
+  for (i, cell) in cells.iter().enumerate() {
+    page.insert_cell(i as u16, cell)?;
+  }
+
+  page.mark_clean();
+  page.tombstone_cell(1)?;
+  page.tombstone_cell(3)?;
+  assert!(page.is_dirty());
+  assert!(page.is_tombstoned(1)?);
+  assert!(!page.is_tombstoned(0)?);
+
+  // 软删除不改变 cell 数量，默认遍历仍能看到墓碑
+  assert_eq!(page.num_cells()?, 4);
+  assert_eq!(page.iter_cells(CellScan::All)?.count(), 4);
+
+  let visible = page
+    .iter_cells(CellScan::SkipTombstones)?
+    .collect::<Result<Vec<_>, _>>()?;
+  assert_eq!(
+    visible,
+    vec![(0, cells[0].as_slice()), (2, cells[2].as_slice())]
+  );
+
+  // 整理碎片后墓碑标记保留
+  page.remove_cell(0)?;
+  page.defragment()?;
+  assert!(page.is_tombstoned(0)?);
+  assert!(page.is_tombstoned(2)?);
+  assert_eq!(page.cell(0)?, cells[1].as_slice());
+
+  assert_eq!(page.vacuum()?, 2);
+  assert_eq!(page.num_cells()?, 1);
+  assert_eq!(page.cell(0)?, cells[2].as_slice());
+  assert!(!page.is_tombstoned(0)?);
+
+  Ok(())
+}
+
+#[test]
+fn page_tombstone_rejects_internal_page() -> TestResult {
+  let mut page = Page::new(PageId::new(1), PageType::Internal);
+  page.insert_cell(0, &encode_internal_cell(PageId::new(2), RowId::new(10)))?;
+
+  assert_eq!(
+    page.tombstone_cell(0),
+    Err(PageError::UnsupportedPageType(PageType::Internal))
+  );
+  Ok(())
+}
+
+#[test]
+fn page_decode_header_from_slice() -> TestResult {
+  let mut page = Page::new(PageId::new(3), PageType::Leaf);
+  page.insert_cell(0, &encode_leaf_cell(RowId::new(1), b"abc"))?;
+
+  // 只取页头加少量数据，不需要完整的 4KB
+  let bytes: Vec<u8> = page.data()[..PAGE_HEADER_SIZE + 8].to_vec();
+  let header = decode_header(&bytes)?;
+  assert_eq!(header, page.try_parse_header()?);
+  assert_eq!(header.num_cells, 1);
+
+  assert_eq!(
+    decode_header(&bytes[..PAGE_HEADER_SIZE - 1]),
+    Err(PageError::HeaderTooShort { len: PAGE_HEADER_SIZE - 1 })
+  );
+
+  let mut bad = bytes.clone();
+  bad[OFF_PAGE_TYPE] = 0xEE;
+  assert!(matches!(
+    decode_header(&bad),
+    Err(PageError::InvalidPageType(_))
+  ));
+
+  Ok(())
+}
+
+#[test]
+fn page_header_version_is_written_and_validated() -> TestResult {
+  let page = Page::new(PageId::new(1), PageType::Leaf);
+  assert_eq!(page.header_version(), PAGE_HEADER_VERSION);
+  assert_eq!(page.data()[OFF_PAGE_TYPE], 0x20 | 0x0D);
+
+  // 引入版本号之前写入的页（高 3 位为 0）照常解码
+  let mut legacy = *page.data();
+  legacy[OFF_PAGE_TYPE] = 0x0D;
+  let legacy = Page::from_bytes(PageId::new(1), legacy)?;
+  assert_eq!(legacy.header_version(), 0);
+  assert_eq!(legacy.page_type(), PageType::Leaf);
+  assert_eq!(legacy.try_parse_header()?.page_type, PageType::Leaf);
+
+  // 未知（更新的）版本
+  let mut future = *page.data();
+  future[OFF_PAGE_TYPE] = (2 << 5) | 0x0D;
+  assert_eq!(
+    decode_header(&future),
+    Err(PageError::UnsupportedHeaderVersion(2))
+  );
+  assert_eq!(
+    Page::from_bytes(PageId::new(1), future).err(),
+    Some(HeaderError::UnsupportedHeaderVersion(2))
+  );
+
+  Ok(())
+}
+
+#[test]
+fn page_max_local_payload_boundaries() -> TestResult {
+  let mut leaf = Page::new(PageId::new(1), PageType::Leaf);
+  assert_eq!(leaf.max_local_payload(), MAX_LOCAL_PAYLOAD);
+  assert_eq!(MAX_LOCAL_PAYLOAD, (4096 - 32) / 4 - 2 - 2 * 9);
+
+  assert!(!leaf.needs_overflow(0));
+  assert!(!leaf.needs_overflow(MAX_LOCAL_PAYLOAD));
+  assert!(leaf.needs_overflow(MAX_LOCAL_PAYLOAD + 1));
+
+  // 最大 payload（rowid 取最长的 varint）的 cell 至少能放下 4 个
+  let cell = encode_leaf_cell(RowId::new(i64::MIN), &[0u8; MAX_LOCAL_PAYLOAD]);
+  for i in 0..4 {
+    leaf.insert_cell(i, &cell)?;
+  }
+
+  // 内部页不存放 payload
+  let internal = Page::new(PageId::new(2), PageType::Internal);
+  assert_eq!(internal.max_local_payload(), 0);
+  assert!(internal.needs_overflow(1));
+
+  Ok(())
+}
+
+#[test]
+fn page_insert_rejects_oversized_cell_and_corrupt_layout() -> TestResult {
+  let mut page = Page::new(PageId::new(1), PageType::Leaf);
+
+  // 超过整页的 payload：返回 PageFull 而不是下溢
+  let huge = vec![0u8; PAGE_SIZE];
+  assert!(matches!(
+    page.insert_cell(0, &huge),
+    Err(PageError::PageFull { .. })
+  ));
+  assert_eq!(page.num_cells()?, 0);
+
+  // 页头中的 cell_content_area 落在 pointer array 之前
+  let mut header = page.try_parse_header()?;
+  header.num_cells = 4;
+  header.cell_content_area = 36;
+  page.write_header(&header);
+  assert_eq!(
+    page.insert_cell(0, b"x"),
+    Err(PageError::InvalidLayout { cell_content_area: 36, num_cells: 4 })
+  );
+  assert!(matches!(page.cell(0), Err(PageError::InvalidLayout { .. })));
+
+  // 超出页尾同样视为损坏
+  header.num_cells = 0;
+  header.cell_content_area = u16::MAX;
+  page.write_header(&header);
+  assert!(matches!(
+    page.free_space(),
+    Err(PageError::InvalidLayout { .. })
+  ));
+
+  Ok(())
+}
@@ -1,15 +1,27 @@
+use std::cell::RefCell;
 use std::fs::{File, OpenOptions};
 use std::io;
 use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use rdb_domain::PageId;
+use rdb_domain::{Column, ColumnId, DataType, DomainError, PageId, Table, TableId, TransactionId};
 use rdb_infrastructure::file_io::{read_exact_at, write_all_at};
-use rdb_storage::page::{Page, PageHeader, PageType, PAGE_HEADER_SIZE};
+use rdb_infrastructure::BufferPool;
+use rdb_storage::db_header::FORMAT_VERSION;
+use rdb_storage::page::{
+  LayoutError, Page, PageError, PageHeader, PageType, OFF_PAGE_TYPE, PAGE_HEADER_SIZE, PAGE_SIZE,
+};
+use rdb_storage::pager::{
+  write_flush_batch, CheckpointResult, Durability, Pager, PagerInfo, PagerOptions,
+};
 use rdb_storage::test_support::{
-  new_pager_for_test, pager_allocate_page, pager_flush_all, pager_flush_page, pager_get_page,
-  pager_get_page_mut, PagerError,
+  corrupt_byte, new_pager_for_test, new_pager_with_capacity_for_test, new_read_only_pager_for_test,
+  pager_allocate_page, pager_allocate_page_raw, pager_flush_all, pager_flush_page, PagerError,
+  TempDb,
 };
+use rdb_storage::{btree, catalog};
 
 type TestResult = Result<(), Box<dyn std::error::Error>>;
 
@@ -38,6 +50,16 @@ impl TempFile {
     Ok((Self { path }, file))
   }
 
+  /// 只生成唯一路径，不创建文件
+  fn missing(prefix: &str) -> Self {
+    let nanos = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .unwrap_or_default()
+      .as_nanos();
+    let path = std::env::temp_dir().join(format!("{prefix}_{}_{}.db", std::process::id(), nanos));
+    Self { path }
+  }
+
   fn reopen_rw(&self) -> io::Result<File> {
     OpenOptions::new().read(true).write(true).open(&self.path)
   }
@@ -60,7 +82,7 @@ fn read_header(path: &TempFile, page_id: u32) -> io::Result<PageHeader> {
   let mut buf = [0u8; PAGE_HEADER_SIZE];
   let off = (page_id as u64 - 1) * 4096;
   read_exact_at(&file, &mut buf, off)?;
-  Ok(PageHeader::decode(&buf).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?)
+  PageHeader::decode(&buf).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
 }
 
 #[test]
@@ -81,7 +103,7 @@ fn pager_get_page_rejects_page_id_0() -> TestResult {
   let (_tmp, file) = TempFile::new("rdb_pager_pageid0")?;
   let pager = new_pager_for_test(file)?;
 
-  let r = pager_get_page(&pager, PageId::new(0));
+  let r = pager.page(PageId::new(0));
   match r {
     Err(PagerError::PageNotFound(id)) => {
       assert_eq!(id, PageId::new(0));
@@ -103,7 +125,7 @@ fn pager_get_page_rejects_page_id_greater_than_page_count() -> TestResult {
 
   let pager = new_pager_for_test(file)?;
 
-  let r = pager_get_page(&pager, PageId::new(2));
+  let r = pager.page(PageId::new(2));
   match r {
     Err(PagerError::PageNotFound(id)) => {
       assert_eq!(id, PageId::new(2));
@@ -124,14 +146,14 @@ fn pager_get_page_reads_from_disk_and_is_cached() -> TestResult {
 
   let pager = new_pager_for_test(file)?;
 
-  let a = pager_get_page(&pager, PageId::new(1))?;
-  let b = pager_get_page(&pager, PageId::new(1))?;
+  let a = pager.page(PageId::new(1))?;
+  let b = pager.page(PageId::new(1))?;
 
   assert_eq!(a.page_id(), PageId::new(1));
   assert_eq!(a.page_type(), PageType::Internal);
 
   // 同一页应命中缓存（同一地址）
-  assert_eq!((a as *const _), (b as *const _));
+  assert_eq!(&*a as *const Page, &*b as *const Page);
 
   Ok(())
 }
@@ -168,9 +190,9 @@ fn pager_get_page_mut_then_flush_page_persists_changes() -> TestResult {
   let mut pager = new_pager_for_test(file)?;
 
   {
-    let page = pager_get_page_mut(&mut pager, PageId::new(1))?;
+    let mut page = pager.page_mut(PageId::new(1))?;
     let mut h = page.try_parse_header()?;
-    h.num_cells = 7;
+    h.lsn = 7;
     h.right_child = 42;
     page.write_header(&h); // 这会标记 dirty
   }
@@ -179,12 +201,120 @@ fn pager_get_page_mut_then_flush_page_persists_changes() -> TestResult {
 
   let h2 = read_header(&tmp, 1)?;
   assert_eq!(h2.page_type, PageType::Leaf);
-  assert_eq!(h2.num_cells, 7);
+  assert_eq!(h2.lsn, 7);
   assert_eq!(h2.right_child, 42);
 
   Ok(())
 }
 
+#[test]
+fn pager_flush_page_refuses_inconsistent_header() -> TestResult {
+  let (tmp, file) = TempFile::new("rdb_pager_flush_invalid")?;
+  write_page(&tmp, 1, &Page::new(PageId::new(1), PageType::Leaf))?;
+  let mut pager = new_pager_for_test(file)?;
+  let id = PageId::new(1);
+
+  // content area 伸进页头
+  {
+    let mut page = pager.page_mut(id)?;
+    let mut h = page.try_parse_header()?;
+    h.cell_content_area = 10;
+    page.write_header(&h);
+  }
+  let writes = pager.stats().page_writes;
+  match pager_flush_page(&mut pager, id) {
+    Err(PagerError::InvalidPageState {
+      page_id,
+      source: LayoutError::ContentAreaOverlapsPointers { cell_content_area: 10, .. },
+    }) => assert_eq!(page_id, id),
+    other => return Err(format!("expected InvalidPageState, got {other:?}").into()),
+  }
+  assert_eq!(pager.stats().page_writes, writes);
+  assert_eq!(read_header(&tmp, 1)?.cell_content_area as usize, PAGE_SIZE);
+  assert!(pager.page(id)?.is_dirty());
+
+  // content area 越过页尾
+  {
+    let mut page = pager.page_mut(id)?;
+    let mut h = page.try_parse_header()?;
+    h.cell_content_area = PAGE_SIZE as u16 + 1;
+    page.write_header(&h);
+  }
+  assert!(matches!(
+    pager_flush_page(&mut pager, id),
+    Err(PagerError::InvalidPageState { source: LayoutError::ContentAreaPastEnd { .. }, .. })
+  ));
+
+  // 页头自洽，但 cell pointer 指向页头
+  {
+    let mut page = pager.page_mut(id)?;
+    let mut h = page.try_parse_header()?;
+    h.num_cells = 1;
+    h.cell_content_area = PAGE_SIZE as u16 - 8;
+    page.write_header(&h);
+    page.data_mut()[PAGE_HEADER_SIZE..PAGE_HEADER_SIZE + 2].copy_from_slice(&4u16.to_le_bytes());
+  }
+  assert!(matches!(
+    pager_flush_page(&mut pager, id),
+    Err(PagerError::InvalidPageState {
+      source: LayoutError::CellPointerOutOfRange { index: 0, offset: 4, .. },
+      ..
+    })
+  ));
+
+  // 修好之后可以正常刷盘
+  {
+    let mut page = pager.page_mut(id)?;
+    let mut h = page.try_parse_header()?;
+    h.num_cells = 0;
+    h.cell_content_area = PAGE_SIZE as u16;
+    h.lsn = 3;
+    page.write_header(&h);
+  }
+  pager_flush_page(&mut pager, id)?;
+  assert_eq!(read_header(&tmp, 1)?.lsn, 3);
+
+  Ok(())
+}
+
+#[test]
+fn pager_take_flush_batch_refuses_inconsistent_page() -> TestResult {
+  let (tmp, file) = TempFile::new("rdb_pager_flush_batch_invalid")?;
+  for id in 1..=2 {
+    write_page(&tmp, id, &Page::new(PageId::new(id), PageType::Leaf))?;
+  }
+  let mut pager = new_pager_for_test(file)?;
+
+  pager
+    .page_mut(PageId::new(1))?
+    .set_txn(TransactionId::new(4));
+  {
+    let mut page = pager.page_mut(PageId::new(2))?;
+    let mut h = page.try_parse_header()?;
+    h.cell_content_area = 10;
+    page.write_header(&h);
+  }
+
+  // 任何一页不一致时不生成 batch，所有脏页保持脏标记
+  match pager.take_flush_batch() {
+    Err(PagerError::InvalidPageState { page_id, .. }) => assert_eq!(page_id, PageId::new(2)),
+    other => return Err(format!("expected InvalidPageState, got {other:?}").into()),
+  }
+  assert!(pager.page(PageId::new(1))?.is_dirty());
+  assert!(pager.page(PageId::new(2))?.is_dirty());
+
+  // 修好之后正常生成
+  {
+    let mut page = pager.page_mut(PageId::new(2))?;
+    let mut h = page.try_parse_header()?;
+    h.cell_content_area = PAGE_SIZE as u16;
+    page.write_header(&h);
+  }
+  assert_eq!(pager.take_flush_batch()?.len(), 2);
+
+  Ok(())
+}
+
 #[test]
 fn pager_flush_all_flushes_multiple_dirty_pages() -> TestResult {
   let (tmp, file) = TempFile::new("rdb_pager_flush_all")?;
@@ -199,16 +329,16 @@ fn pager_flush_all_flushes_multiple_dirty_pages() -> TestResult {
   let mut pager = new_pager_for_test(file)?;
 
   {
-    let page1 = pager_get_page_mut(&mut pager, PageId::new(1))?;
+    let mut page1 = pager.page_mut(PageId::new(1))?;
     let mut h1 = page1.try_parse_header()?;
-    h1.num_cells = 11;
+    h1.lsn = 11;
     page1.write_header(&h1);
   }
 
   {
-    let page2 = pager_get_page_mut(&mut pager, PageId::new(2))?;
+    let mut page2 = pager.page_mut(PageId::new(2))?;
     let mut h2 = page2.try_parse_header()?;
-    h2.num_cells = 22;
+    h2.lsn = 22;
     page2.write_header(&h2);
   }
 
@@ -218,10 +348,441 @@ fn pager_flush_all_flushes_multiple_dirty_pages() -> TestResult {
   let h2 = read_header(&tmp, 2)?;
 
   assert_eq!(h1.page_type, PageType::Leaf);
-  assert_eq!(h1.num_cells, 11);
+  assert_eq!(h1.lsn, 11);
 
   assert_eq!(h2.page_type, PageType::Internal);
-  assert_eq!(h2.num_cells, 22);
+  assert_eq!(h2.lsn, 22);
+
+  Ok(())
+}
+
+#[test]
+fn pager_flush_all_writes_in_page_id_order() -> TestResult {
+  let (tmp, file) = TempFile::new("rdb_pager_flush_order")?;
+  for id in 1..=3 {
+    write_page(&tmp, id, &Page::new(PageId::new(id), PageType::Leaf))?;
+  }
+
+  let mut pager = new_pager_for_test(file)?;
+  let writes = Rc::new(RefCell::new(Vec::new()));
+  let log = Rc::clone(&writes);
+  pager.set_on_write(move |id, offset| log.borrow_mut().push((id.into_inner(), offset)));
+
+  for id in [3, 1, 2] {
+    let mut page = pager.page_mut(PageId::new(id))?;
+    let mut h = page.try_parse_header()?;
+    h.lsn = id as u64;
+    page.write_header(&h);
+  }
+
+  pager_flush_all(&mut pager)?;
+  assert_eq!(writes.borrow().as_slice(), &[(1, 0), (2, 4096), (3, 8192)]);
+
+  Ok(())
+}
+
+#[test]
+fn pager_flush_barrier_runs_before_each_write() -> TestResult {
+  let (tmp, file) = TempFile::new("rdb_pager_flush_barrier")?;
+  for id in 1..=3 {
+    write_page(&tmp, id, &Page::new(PageId::new(id), PageType::Leaf))?;
+  }
+
+  let mut pager = new_pager_for_test(file)?;
+  let events = Rc::new(RefCell::new(Vec::new()));
+  let log = Rc::clone(&events);
+  pager.set_flush_barrier(move |id| {
+    log.borrow_mut().push(("barrier", id.into_inner()));
+    Ok(())
+  });
+  let log = Rc::clone(&events);
+  pager.set_on_write(move |id, _| log.borrow_mut().push(("write", id.into_inner())));
+
+  for id in [3, 1] {
+    let mut page = pager.page_mut(PageId::new(id))?;
+    let mut h = page.try_parse_header()?;
+    h.lsn = 1;
+    page.write_header(&h);
+  }
+  pager_flush_all(&mut pager)?;
+  assert_eq!(
+    events.borrow().as_slice(),
+    &[("barrier", 1), ("write", 1), ("barrier", 3), ("write", 3)]
+  );
+
+  // 屏障失败时不写盘，页保持为脏，之后可以重试
+  pager.set_flush_barrier(|_| Err(io::Error::other("log not durable")));
+  let mut page = pager.page_mut(PageId::new(2))?;
+  let mut h = page.try_parse_header()?;
+  h.lsn = 2;
+  page.write_header(&h);
+  drop(page);
+  events.borrow_mut().clear();
+  assert!(matches!(
+    pager_flush_page(&mut pager, PageId::new(2)),
+    Err(PagerError::Io(_))
+  ));
+  assert!(events.borrow().is_empty());
+
+  pager.set_flush_barrier(|_| Ok(()));
+  pager_flush_all(&mut pager)?;
+  assert_eq!(events.borrow().as_slice(), &[("write", 2)]);
+
+  Ok(())
+}
+
+#[test]
+fn pager_get_page_as_of_sees_prior_version() -> TestResult {
+  let (tmp, file) = TempFile::new("rdb_pager_mvcc")?;
+  write_page(&tmp, 1, &Page::new(PageId::new(1), PageType::Leaf))?;
+
+  let mut pager = new_pager_for_test(file)?;
+  pager.set_versioning(true);
+  let id = PageId::new(1);
+  let (t1, t2, t3) = (
+    TransactionId::new(1),
+    TransactionId::new(2),
+    TransactionId::new(3),
+  );
+
+  let mut page = pager.begin_page_write(id, t1)?;
+  page.data_mut()[100] = 1;
+  drop(page);
+  let mut page = pager.begin_page_write(id, t3)?;
+  page.data_mut()[100] = 3;
+  drop(page);
+
+  // 在 t3 之前开始的读取方仍然看到 t1 写入的字节
+  let old = pager.get_page_as_of(id, t2)?;
+  assert_eq!((old.txn(), old.data()[100]), (t1, 1));
+  drop(old);
+  let new = pager.get_page_as_of(id, t3)?;
+  assert_eq!((new.txn(), new.data()[100]), (t3, 3));
+  drop(new);
+  // 旧事务不能覆盖新版本
+  assert!(matches!(
+    pager.begin_page_write(id, t2),
+    Err(PagerError::WriteConflict { stamp, .. }) if stamp == t3
+  ));
+
+  // 最老的活跃事务是 t2 时，t1 的版本仍需保留；到 t3 时全部可回收
+  assert_eq!(pager.prune_versions(t2), 1);
+  assert_eq!(pager.get_page_as_of(id, t2)?.data()[100], 1);
+  assert_eq!(pager.prune_versions(t3), 1);
+  assert!(matches!(
+    pager.get_page_as_of(id, t2),
+    Err(PagerError::VersionNotFound { .. })
+  ));
+
+  Ok(())
+}
+
+#[test]
+fn temp_db_reopen_reads_back_written_page() -> TestResult {
+  let (db, mut pager) = TempDb::new("rdb_temp_db")?;
+  let path = db.path().to_path_buf();
+  assert!(path.exists());
+
+  let id = btree::create(&mut pager)?;
+  pager.page_mut(id)?.data_mut()[PAGE_SIZE - 1] = 0xAB;
+  pager_flush_all(&mut pager)?;
+
+  // 旧 Pager 持有文件锁，重新打开前必须先释放
+  assert!(matches!(db.reopen(), Err(PagerError::Locked)));
+  drop(pager);
+
+  let pager = db.reopen()?;
+  assert_eq!(pager.page(id)?.data()[PAGE_SIZE - 1], 0xAB);
+  drop(pager);
+
+  // 两个 TempDb 的路径不同；drop 后文件被删除
+  let (other, _pager) = TempDb::new("rdb_temp_db")?;
+  assert_ne!(other.path(), path);
+  drop(db);
+  assert!(!path.exists());
+
+  Ok(())
+}
+
+#[test]
+fn corrupt_byte_makes_page_unreadable() -> TestResult {
+  let (db, mut pager) = TempDb::new("rdb_corrupt_byte")?;
+  let id = btree::create(&mut pager)?;
+  pager_flush_all(&mut pager)?;
+  drop(pager);
+
+  corrupt_byte(db.path(), id, OFF_PAGE_TYPE, 0xFF)?;
+  let pager = db.reopen()?;
+  assert!(matches!(
+    pager.page(id),
+    Err(PagerError::InvalidPageType(_))
+  ));
+  drop(pager);
+
+  // 目标不合法时不改动文件
+  let len = std::fs::metadata(db.path())?.len();
+  assert!(corrupt_byte(db.path(), id, PAGE_SIZE, 0).is_err());
+  assert!(corrupt_byte(db.path(), PageId::new(0), 0, 0).is_err());
+  assert_eq!(std::fs::metadata(db.path())?.len(), len);
+
+  Ok(())
+}
+
+#[test]
+fn pager_describe_reports_geometry() -> TestResult {
+  let (tmp, file) = TempFile::new("rdb_pager_describe")?;
+  for id in 1..=3 {
+    write_page(&tmp, id, &Page::new(PageId::new(id), PageType::Leaf))?;
+  }
+
+  let mut pager = new_pager_for_test(file)?;
+  let info = pager.describe()?;
+  assert_eq!(
+    info,
+    PagerInfo { page_size: 4096, page_count: 3, file_len: 3 * 4096, read_only: false }
+  );
+
+  pager_allocate_page(&mut pager)?;
+  assert_eq!(pager.describe()?.page_count, 4);
+  assert_eq!(pager.describe()?.file_len, 4 * 4096);
+  drop(pager);
+
+  let pager = new_read_only_pager_for_test(File::open(&tmp.path)?)?;
+  let info = pager.describe()?;
+  assert!(info.read_only);
+  assert_eq!((info.page_count, info.file_len), (4, 4 * 4096));
+
+  Ok(())
+}
+
+#[test]
+fn pager_strict_layout_rejects_corrupt_page_on_load() -> TestResult {
+  let (tmp, file) = TempFile::new("rdb_pager_strict_layout")?;
+  let mut bad = Page::new(PageId::new(2), PageType::Leaf);
+  let mut h = bad.try_parse_header()?;
+  h.num_cells = 1;
+  h.cell_content_area = PAGE_SIZE as u16 - 8;
+  bad.write_header(&h);
+  // cell pointer 指向页头
+  bad.data_mut()[PAGE_HEADER_SIZE..PAGE_HEADER_SIZE + 2].copy_from_slice(&4u16.to_le_bytes());
+  write_page(&tmp, 1, &Page::new(PageId::new(1), PageType::Leaf))?;
+  write_page(&tmp, 2, &bad)?;
+
+  // 默认不校验
+  let pager = new_pager_for_test(file)?;
+  pager.page(PageId::new(2))?;
+  drop(pager);
+
+  let mut pager = new_pager_for_test(File::options().read(true).write(true).open(&tmp.path)?)?;
+  pager.set_strict_layout(true);
+  pager.page(PageId::new(1))?;
+  assert!(matches!(
+    pager.page(PageId::new(2)),
+    Err(PagerError::Page(PageError::Layout(
+      LayoutError::CellPointerOutOfRange { offset: 4, .. }
+    )))
+  ));
+
+  Ok(())
+}
+
+#[test]
+fn pager_checkpoint_counts_only_dirty_pages() -> TestResult {
+  let (tmp, file) = TempFile::new("rdb_pager_checkpoint")?;
+  for id in 1..=5 {
+    write_page(&tmp, id, &Page::new(PageId::new(id), PageType::Leaf))?;
+  }
+
+  let mut pager = new_pager_for_test(file)?;
+  for id in 1..=5 {
+    pager.page(PageId::new(id))?;
+  }
+  for id in [1, 3, 5] {
+    let mut page = pager.page_mut(PageId::new(id))?;
+    let mut h = page.try_parse_header()?;
+    h.lsn = 1;
+    page.write_header(&h);
+  }
+
+  let result = pager.checkpoint()?;
+  assert_eq!(result.pages_written, 3);
+  assert_eq!(result.bytes_written, 3 * 4096);
+
+  // 再次 checkpoint 没有脏页
+  assert_eq!(pager.checkpoint()?, CheckpointResult::default());
+
+  Ok(())
+}
+
+#[test]
+fn pager_deferred_durability_syncs_only_on_sync() -> TestResult {
+  let (tmp, file) = TempFile::new("rdb_pager_durability")?;
+  for id in 1..=2 {
+    write_page(&tmp, id, &Page::new(PageId::new(id), PageType::Leaf))?;
+  }
+
+  let mut pager = new_pager_for_test(file)?;
+  let syncs = Rc::new(RefCell::new(0));
+  let counter = Rc::clone(&syncs);
+  pager.set_on_sync(move || *counter.borrow_mut() += 1);
+
+  let dirty = |pager: &mut Pager<'static>, id: u32| -> TestResult {
+    let mut page = pager.page_mut(PageId::new(id))?;
+    let mut h = page.try_parse_header()?;
+    h.lsn += 1;
+    page.write_header(&h);
+    Ok(())
+  };
+
+  // 默认 Full：checkpoint 写完后 fsync 一次，单页刷盘也 fsync
+  assert_eq!(pager.durability(), Durability::Full);
+  dirty(&mut pager, 1)?;
+  dirty(&mut pager, 2)?;
+  pager.checkpoint()?;
+  assert_eq!(*syncs.borrow(), 1);
+  dirty(&mut pager, 1)?;
+  pager_flush_page(&mut pager, PageId::new(1))?;
+  assert_eq!(*syncs.borrow(), 2);
+
+  pager.set_durability(Durability::Deferred);
+  dirty(&mut pager, 1)?;
+  dirty(&mut pager, 2)?;
+  pager_flush_all(&mut pager)?;
+  dirty(&mut pager, 2)?;
+  pager_flush_page(&mut pager, PageId::new(2))?;
+  assert_eq!(*syncs.borrow(), 2);
+  assert_eq!(read_header(&tmp, 2)?.lsn, 3);
+
+  pager.sync()?;
+  assert_eq!(*syncs.borrow(), 3);
+
+  Ok(())
+}
+
+#[test]
+fn pager_read_raw_reads_unaligned_offsets() -> TestResult {
+  let (tmp, file) = TempFile::new("rdb_pager_read_raw")?;
+  let mut leaf = Page::new(PageId::new(2), PageType::Leaf);
+  leaf.insert_cell(0, &[0xAB; 300])?;
+  write_page(&tmp, 1, &Page::new(PageId::new(1), PageType::Leaf))?;
+  write_page(&tmp, 2, &leaf)?;
+
+  let pager = new_pager_for_test(file)?;
+
+  // 跨越 cell 内容区，偏移不按页对齐
+  let offset = 4096 + 4096 - 150;
+  let mut buf = [0u8; 100];
+  pager.read_raw(offset, &mut buf)?;
+  assert_eq!(&buf[..], &leaf.data()[4096 - 150..4096 - 50]);
+  assert_eq!(pager.stats().page_reads, 0);
+
+  // 越过文件末尾
+  let mut buf = [0u8; 100];
+  assert!(matches!(
+    pager.read_raw(2 * 4096 - 50, &mut buf),
+    Err(PagerError::OutOfBounds { file_len: 8192, .. })
+  ));
+  assert!(matches!(
+    pager.read_raw(u64::MAX, &mut buf),
+    Err(PagerError::OutOfBounds { .. })
+  ));
+
+  Ok(())
+}
+
+#[test]
+fn pager_diff_page_lists_unflushed_bytes() -> TestResult {
+  let (tmp, file) = TempFile::new("rdb_pager_diff")?;
+  write_page(&tmp, 1, &Page::new(PageId::new(1), PageType::Leaf))?;
+
+  let mut pager = new_pager_for_test(file)?;
+  assert!(matches!(
+    pager.diff_page(PageId::new(1)),
+    Err(PagerError::PageNotFound(_))
+  ));
+
+  {
+    let mut page = pager.page_mut(PageId::new(1))?;
+    let mut h = page.try_parse_header()?;
+    h.lsn = 0x0102;
+    page.write_header(&h);
+  }
+
+  // lsn 位于页头 offset 12..20（小端序），磁盘上仍为 0
+  assert_eq!(
+    pager.diff_page(PageId::new(1))?,
+    vec![(12, 0x02, 0), (13, 0x01, 0)]
+  );
+
+  pager_flush_page(&mut pager, PageId::new(1))?;
+  assert!(pager.diff_page(PageId::new(1))?.is_empty());
+
+  Ok(())
+}
+
+#[test]
+fn pager_flush_batch_is_written_outside_the_pager() -> TestResult {
+  let (tmp, file) = TempFile::new("rdb_pager_flush_batch")?;
+  for id in 1..=3 {
+    write_page(&tmp, id, &Page::new(PageId::new(id), PageType::Leaf))?;
+  }
+
+  let mut pager = new_pager_for_test(file)?;
+  pager.page(PageId::new(2))?;
+  for id in [3, 1] {
+    let mut page = pager.page_mut(PageId::new(id))?;
+    let mut h = page.try_parse_header()?;
+    h.lsn = 40 + id as u64;
+    page.write_header(&h);
+  }
+
+  let batch = pager.take_flush_batch()?;
+  assert_eq!(
+    batch.page_ids().collect::<Vec<_>>(),
+    [PageId::new(1), PageId::new(3)]
+  );
+  assert!(!pager.page(PageId::new(1))?.is_dirty());
+  assert!(pager.take_flush_batch()?.is_empty());
+
+  // 写盘前磁盘上仍是旧内容
+  assert_eq!(read_header(&tmp, 1)?.lsn, 0);
+
+  // 在另一个线程用独立的文件句柄写盘
+  let handle = tmp.reopen_rw()?;
+  std::thread::spawn(move || write_flush_batch(&handle, &batch))
+    .join()
+    .map_err(|_| "panicked")??;
+
+  assert_eq!(read_header(&tmp, 1)?.lsn, 41);
+  assert_eq!(read_header(&tmp, 3)?.lsn, 43);
+  assert_eq!(pager.stats().page_writes, 0);
+
+  Ok(())
+}
+
+#[test]
+fn pager_relocate_page_moves_bytes_to_new_offset() -> TestResult {
+  let (tmp, file) = TempFile::new("rdb_pager_relocate")?;
+  let mut leaf = Page::new(PageId::new(1), PageType::Leaf);
+  leaf.insert_cell(0, b"moved")?;
+  write_page(&tmp, 1, &leaf)?;
+
+  let mut pager = new_pager_for_test(file)?;
+  let to = pager_allocate_page(&mut pager)?;
+
+  assert_eq!(pager.relocate_page(PageId::new(1), to)?, PageType::Leaf);
+  assert!(pager.page(to)?.is_dirty());
+  pager_flush_all(&mut pager)?;
+
+  let mut buf = [0u8; 4096];
+  read_exact_at(&tmp.reopen_rw()?, &mut buf, 4096)?;
+  assert_eq!(&buf, leaf.data());
+
+  // 目标页必须已分配
+  assert!(matches!(
+    pager.relocate_page(PageId::new(1), PageId::new(9)),
+    Err(PagerError::PageNotFound(_))
+  ));
 
   Ok(())
 }
@@ -244,3 +805,487 @@ fn pager_allocate_page_extends_file_and_is_zero_filled() -> TestResult {
 
   Ok(())
 }
+
+#[cfg(target_os = "linux")]
+#[test]
+fn pager_sparse_allocation_skips_zero_write() -> TestResult {
+  let (tmp, file) = TempFile::new("rdb_pager_sparse")?;
+  let mut pager = new_pager_for_test(file)?;
+  pager.set_sparse_allocation(true);
+
+  let id = pager_allocate_page(&mut pager)?;
+  let id2 = pager_allocate_page(&mut pager)?;
+  assert_eq!((id, id2), (PageId::new(1), PageId::new(2)));
+  assert_eq!(pager.stats().page_writes, 0);
+
+  // 从未写过的空洞读出来全是 0
+  let file = tmp.reopen_rw()?;
+  assert_eq!(file.metadata()?.len(), 4096 * 2);
+  let mut buf = [0xFFu8; 4096];
+  read_exact_at(&file, &mut buf, 4096)?;
+  assert!(buf.iter().all(|&b| b == 0));
+
+  Ok(())
+}
+
+#[test]
+fn pager_error_converts_to_domain_storage_error() {
+  let e = PagerError::PageNotFound(PageId::new(7));
+  let domain: DomainError = e.into();
+
+  match domain {
+    DomainError::Storage { message } => {
+      assert!(message.contains("page not found"), "message: {message}");
+      assert!(message.contains('7'), "message: {message}");
+    }
+    other => panic!("expected DomainError::Storage, got {other:?}"),
+  }
+}
+
+#[test]
+fn pager_page_guards_track_borrows() -> TestResult {
+  let (tmp, file) = TempFile::new("rdb_pager_guards")?;
+
+  file.set_len(4096 * 2)?;
+  write_page(&tmp, 1, &Page::new(PageId::new(1), PageType::Leaf))?;
+  write_page(&tmp, 2, &Page::new(PageId::new(2), PageType::Leaf))?;
+
+  let mut pager = new_pager_for_test(file)?;
+
+  // 多个只读守卫可以共存，且指向同一份缓存页
+  {
+    let a = pager.page(PageId::new(1))?;
+    let b = pager.page(PageId::new(1))?;
+    let c = pager.page(PageId::new(2))?;
+    assert_eq!(a.page_id(), b.page_id());
+    assert_eq!(c.page_id(), PageId::new(2));
+  }
+
+  // 可写守卫借用整个 pager，持有期间无法再拿到任何守卫（编译期保证）
+  {
+    let mut w = pager.page_mut(PageId::new(1))?;
+    let mut h = w.try_parse_header()?;
+    h.num_cells = 3;
+    w.write_header(&h);
+  }
+
+  // 守卫释放后恢复可借用，且修改可见
+  let r = pager.page(PageId::new(1))?;
+  assert!(r.is_dirty());
+  assert_eq!(r.try_parse_header()?.num_cells, 3);
+
+  Ok(())
+}
+
+#[test]
+fn pager_page_txn_stamp_survives_flush() -> TestResult {
+  let (tmp, file) = TempFile::new("rdb_pager_txn_stamp")?;
+  write_page(&tmp, 1, &Page::new(PageId::new(1), PageType::Leaf))?;
+
+  let mut pager = new_pager_for_test(file)?;
+  {
+    let mut page = pager.page_mut(PageId::new(1))?;
+    assert_eq!(page.txn(), TransactionId::new(0));
+    page.set_txn(TransactionId::new(0xABCD_0123_4567));
+    assert!(page.is_dirty());
+  }
+  pager_flush_page(&mut pager, PageId::new(1))?;
+  drop(pager);
+
+  // 新 Pager 从磁盘重新读入
+  let pager = new_pager_for_test(tmp.reopen_rw()?)?;
+  let page = pager.page(PageId::new(1))?;
+  assert_eq!(page.txn(), TransactionId::new(0xABCD_0123_4567));
+  assert_eq!(page.page_type(), PageType::Leaf);
+
+  Ok(())
+}
+
+#[test]
+fn pager_page_txn_stamp_leaves_lsn_alone() -> TestResult {
+  let (tmp, file) = TempFile::new("rdb_pager_txn_lsn")?;
+  let mut page = Page::new(PageId::new(1), PageType::Leaf);
+  let mut h = page.try_parse_header()?;
+  h.lsn = 42;
+  page.write_header(&h);
+  write_page(&tmp, 1, &page)?;
+
+  let mut pager = new_pager_for_test(file)?;
+  {
+    let mut page = pager.page_mut(PageId::new(1))?;
+    page.set_txn(TransactionId::new(7));
+    assert_eq!(page.lsn(), 42);
+  }
+  pager_flush_page(&mut pager, PageId::new(1))?;
+
+  // 磁盘上的 lsn 保持不变，flush barrier 看到的仍是原来的日志序号
+  let header = read_header(&tmp, 1)?;
+  assert_eq!(header.lsn, 42);
+  assert_eq!(header.reserved, 7);
+  assert_eq!(pager.page(PageId::new(1))?.txn(), TransactionId::new(7));
+
+  Ok(())
+}
+
+#[test]
+fn pager_allocate_page_raw_skips_zero_fill_write() -> TestResult {
+  let (_tmp, file) = TempFile::new("rdb_pager_alloc_raw")?;
+  let mut pager = new_pager_for_test(file)?;
+
+  // 默认分配：一次清零写
+  let id1 = pager_allocate_page(&mut pager)?;
+  assert_eq!(pager.stats().page_writes, 1);
+
+  // raw 分配：只扩展文件，不写页内容
+  let id2 = pager_allocate_page_raw(&mut pager)?;
+  assert_eq!(id2, PageId::new(id1.into_inner() + 1));
+  assert_eq!(pager.stats().page_writes, 1);
+  assert_eq!(pager.stats().page_reads, 0);
+
+  Ok(())
+}
+
+#[test]
+fn pager_eviction_flushes_dirty_page_first() -> TestResult {
+  let (tmp, file) = TempFile::new("rdb_pager_evict")?;
+  for id in 1..=4 {
+    write_page(&tmp, id, &Page::new(PageId::new(id), PageType::Leaf))?;
+  }
+
+  let mut pager = new_pager_with_capacity_for_test(file, 2)?;
+  let evicted = Rc::new(RefCell::new(Vec::new()));
+  let log = Rc::clone(&evicted);
+  pager.set_on_evict(move |id, flushed| log.borrow_mut().push((id, flushed)));
+
+  // 修改页 1 但不显式刷盘
+  {
+    let mut page = pager.page_mut(PageId::new(1))?;
+    let mut h = page.try_parse_header()?;
+    h.lsn = 7;
+    page.write_header(&h);
+  }
+
+  // 继续访问其他页，迫使最久未用的页 1 被淘汰
+  pager.page_mut(PageId::new(2))?;
+  pager.page_mut(PageId::new(3))?;
+  assert_eq!(evicted.borrow().as_slice(), &[(PageId::new(1), true)]);
+
+  // 淘汰前已写回磁盘
+  assert_eq!(read_header(&tmp, 1)?.lsn, 7);
+
+  // 再次访问会从磁盘读回修改后的内容
+  let reads = pager.stats().page_reads;
+  let page = pager.page(PageId::new(1))?;
+  assert_eq!(page.try_parse_header()?.lsn, 7);
+  assert_eq!(pager.stats().page_reads, reads + 1);
+
+  Ok(())
+}
+
+#[test]
+fn pager_pin_guard_blocks_eviction_until_dropped() -> TestResult {
+  let (tmp, file) = TempFile::new("rdb_pager_pin")?;
+  for id in 1..=3 {
+    write_page(&tmp, id, &Page::new(PageId::new(id), PageType::Leaf))?;
+  }
+
+  let mut pager = new_pager_with_capacity_for_test(file, 1)?;
+  let evicted = Rc::new(RefCell::new(Vec::new()));
+  let log = Rc::clone(&evicted);
+  pager.set_on_evict(move |id, _| log.borrow_mut().push(id));
+
+  {
+    let guard = pager.pin(PageId::new(1))?;
+    assert_eq!(guard.page_id(), PageId::new(1));
+    assert_eq!(pager.page(PageId::new(1))?.pin_count(), 1);
+
+    // 超出容量，但唯一可淘汰的候选页 1 被 pin 住
+    pager.page_mut(PageId::new(2))?;
+    assert!(evicted.borrow().is_empty());
+    assert_eq!(pager.page(PageId::new(1))?.pin_count(), 1);
+  }
+
+  assert_eq!(pager.page(PageId::new(1))?.pin_count(), 0);
+  pager.page_mut(PageId::new(3))?;
+  assert!(evicted.borrow().contains(&PageId::new(1)));
+
+  Ok(())
+}
+
+#[test]
+fn pager_evict_removes_page_from_cache() -> TestResult {
+  let (tmp, file) = TempFile::new("rdb_pager_evict")?;
+  for id in 1..=2 {
+    write_page(&tmp, id, &Page::new(PageId::new(id), PageType::Leaf))?;
+  }
+
+  let mut pager = new_pager_for_test(file)?;
+  let evicted = Rc::new(RefCell::new(Vec::new()));
+  let log = Rc::clone(&evicted);
+  pager.set_on_evict(move |id, flushed| log.borrow_mut().push((id, flushed)));
+
+  // 干净页：直接移出，再次访问需要读盘
+  let clean = PageId::new(1);
+  pager.page(clean)?;
+  assert!(pager.evict(clean)?);
+  assert!(!pager.evict(clean)?);
+  let reads = pager.stats().page_reads;
+  pager.page(clean)?;
+  assert_eq!(pager.stats().page_reads, reads + 1);
+
+  // 脏页：先写回磁盘
+  let dirty = PageId::new(2);
+  pager.page_mut(dirty)?.set_txn(TransactionId::new(5));
+  let writes = pager.stats().page_writes;
+  assert!(pager.evict(dirty)?);
+  assert_eq!(pager.stats().page_writes, writes + 1);
+  assert_eq!(read_header(&tmp, 2)?.reserved, 5);
+  assert_eq!(*evicted.borrow(), [(clean, false), (dirty, true)]);
+
+  // 被 pin 的页拒绝淘汰，守卫释放后可以淘汰
+  let guard = pager.pin(clean)?;
+  assert!(matches!(pager.evict(clean), Err(PagerError::Pinned(id)) if id == clean));
+  drop(guard);
+  assert!(pager.evict(clean)?);
+
+  // 从未缓存的页
+  assert!(!pager.evict(PageId::new(99))?);
+
+  Ok(())
+}
+
+#[test]
+fn pager_zero_capacity_reads_from_disk_every_time() -> TestResult {
+  let (tmp, file) = TempFile::new("rdb_pager_zero_capacity")?;
+  for id in 1..=2 {
+    write_page(&tmp, id, &Page::new(PageId::new(id), PageType::Leaf))?;
+  }
+
+  let mut pager = new_pager_with_capacity_for_test(file, 0)?;
+  let page_id = PageId::new(1);
+
+  // 同一页读两次，两次都读盘
+  let before = pager.stats();
+  assert_eq!(pager.page(page_id)?.page_type(), PageType::Leaf);
+  assert_eq!(pager.page(page_id)?.page_type(), PageType::Leaf);
+  let after = pager.stats();
+  assert_eq!(after.page_reads - before.page_reads, 2);
+  assert_eq!(after.cache_misses - before.cache_misses, 2);
+  assert_eq!(after.cache_hits, before.cache_hits);
+
+  // 守卫存活期间该页保留在缓存中，即使磁盘上的内容已经变了也不会被原地覆盖
+  {
+    let guard = pager.page(page_id)?;
+    write_page(&tmp, 1, &Page::new(page_id, PageType::Internal))?;
+    let before = pager.stats();
+    assert_eq!(pager.page(page_id)?.page_type(), PageType::Leaf);
+    assert_eq!(pager.stats().page_reads, before.page_reads);
+    assert_eq!(guard.page_type(), PageType::Leaf);
+  }
+  // 守卫释放后重新读盘，看到新内容
+  assert_eq!(pager.page(page_id)?.page_type(), PageType::Internal);
+
+  // 写路径不会因为腾不出空间而卡住；脏页先写回再移出缓存，修改不会丢
+  pager.page_mut(page_id)?.set_txn(TransactionId::new(9));
+  pager.page_mut(PageId::new(2))?;
+  assert_eq!(pager.page(page_id)?.txn(), TransactionId::new(9));
+
+  Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn pager_second_writer_is_locked_out() -> TestResult {
+  let (tmp, file) = TempFile::new("rdb_pager_lock")?;
+  let first = new_pager_for_test(file)?;
+
+  match new_pager_for_test(tmp.reopen_rw()?) {
+    Err(PagerError::Locked) => {}
+    Err(e) => return Err(format!("expected Locked, got Err({e})").into()),
+    Ok(_) => return Err("expected Locked, got Ok(Pager)".into()),
+  }
+  assert!(matches!(
+    new_read_only_pager_for_test(tmp.reopen_rw()?),
+    Err(PagerError::Locked)
+  ));
+
+  // 释放后可以重新打开
+  drop(first);
+  let reader_a = new_read_only_pager_for_test(tmp.reopen_rw()?)?;
+  let _reader_b = new_read_only_pager_for_test(tmp.reopen_rw()?)?;
+  assert!(matches!(
+    new_pager_for_test(tmp.reopen_rw()?),
+    Err(PagerError::Locked)
+  ));
+  drop(reader_a);
+
+  Ok(())
+}
+
+#[test]
+fn pager_create_initializes_new_database() -> TestResult {
+  let tmp = TempFile::missing("rdb_pager_create");
+  assert!(!tmp.path.exists());
+
+  let pager = Pager::create(&tmp.path)?;
+  let header = pager.db_header()?;
+  assert_eq!(header.page_size, 4096);
+  assert_eq!(header.format_version, FORMAT_VERSION);
+  assert_eq!(header.catalog_root, PageId::new(2));
+  assert_eq!(header.freelist_head, None);
+
+  // 系统目录是一个空叶子页
+  let catalog = pager.page(header.catalog_root)?;
+  assert_eq!(catalog.page_type(), PageType::Leaf);
+  assert_eq!(catalog.num_cells()?, 0);
+  drop(catalog);
+  drop(pager);
+
+  // 文件已经落盘：两页，第 1 页是 Meta 页
+  assert_eq!(std::fs::metadata(&tmp.path)?.len(), 2 * 4096);
+  assert_eq!(read_header(&tmp, 1)?.page_type, PageType::Meta);
+
+  Ok(())
+}
+
+#[test]
+fn pager_create_opens_existing_database() -> TestResult {
+  let tmp = TempFile::missing("rdb_pager_create_existing");
+
+  let header = Pager::create(&tmp.path)?.db_header()?;
+
+  // 再次打开不会重新初始化
+  let pager = Pager::create(&tmp.path)?;
+  assert_eq!(pager.db_header()?, header);
+  drop(pager);
+  assert_eq!(std::fs::metadata(&tmp.path)?.len(), 2 * 4096);
+
+  // 第 1 页不是数据库头时拒绝打开
+  write_page(&tmp, 1, &Page::new(PageId::new(1), PageType::Leaf))?;
+  assert!(matches!(
+    Pager::create(&tmp.path),
+    Err(PagerError::InvalidDbHeader(_))
+  ));
+
+  Ok(())
+}
+
+#[test]
+fn pager_from_file_opens_caller_provided_handle() -> TestResult {
+  let tmp = TempFile::missing("rdb_pager_from_file");
+  let header = Pager::create(&tmp.path)?.db_header()?;
+
+  // 调用方自己打开文件，再交给 Pager
+  let file = tmp.reopen_rw()?;
+  let pager = Pager::from_file(file, PagerOptions::default())?;
+  assert_eq!(pager.db_header()?, header);
+  assert_eq!(pager.page(header.catalog_root)?.page_type(), PageType::Leaf);
+  drop(pager);
+
+  // 只读模式，使用调用方的缓存配置
+  let file = File::open(&tmp.path)?;
+  let options = PagerOptions {
+    buffer_pool: Arc::new(BufferPool::new(4)),
+    read_only: true,
+    ..Default::default()
+  };
+  let pager = Pager::from_file(file, options)?;
+  assert_eq!(pager.db_header()?, header);
+  drop(pager);
+
+  // 空文件：读写模式下初始化，只读模式下拒绝
+  let (empty, file) = TempFile::new("rdb_pager_from_file_empty")?;
+  assert!(matches!(
+    Pager::from_file(
+      File::open(&empty.path)?,
+      PagerOptions { read_only: true, ..Default::default() }
+    ),
+    Err(PagerError::EmptyFile)
+  ));
+  Pager::from_file(file, PagerOptions::default())?;
+  assert_eq!(std::fs::metadata(&empty.path)?.len(), 2 * 4096);
+
+  Ok(())
+}
+
+#[test]
+fn pager_empty_file_is_initialized_by_create_and_rejected_by_open() -> TestResult {
+  // open：不初始化，明确报告空文件
+  let (tmp, file) = TempFile::new("rdb_pager_open_empty")?;
+  drop(file);
+  assert!(matches!(Pager::open(&tmp.path), Err(PagerError::EmptyFile)));
+  assert_eq!(std::fs::metadata(&tmp.path)?.len(), 0);
+
+  // 文件不存在时 open 不创建
+  let missing = TempFile::missing("rdb_pager_open_missing");
+  assert!(matches!(Pager::open(&missing.path), Err(PagerError::Io(_))));
+  assert!(!missing.path.exists());
+
+  // create：自动初始化第 1 页
+  let header = Pager::create(&tmp.path)?.db_header()?;
+  assert_eq!(header.catalog_root, PageId::new(2));
+
+  // 初始化之后 open 可以正常打开
+  assert_eq!(Pager::open(&tmp.path)?.db_header()?, header);
+
+  Ok(())
+}
+
+#[test]
+fn pager_open_warms_catalog_and_table_roots() -> TestResult {
+  let tmp = TempFile::missing("rdb_pager_warm");
+
+  let mut roots = Vec::new();
+  {
+    let mut pager = Pager::create(&tmp.path)?;
+    for (id, name) in [(1, "users"), (2, "orders")] {
+      let root = btree::create(&mut pager)?;
+      let table = Table::new(
+        TableId::new(id),
+        name.to_string(),
+        vec![Column::new(ColumnId::new(1), "id".to_string(), DataType::Integer)],
+        None,
+        root,
+      );
+      catalog::add_table(&mut pager, &table)?;
+      roots.push(root);
+    }
+    pager_flush_all(&mut pager)?;
+  }
+
+  let pager = Pager::create(&tmp.path)?;
+  let header = pager.db_header()?;
+  assert_eq!(catalog::table_roots(&pager, header.catalog_root)?, roots);
+
+  // 打开时已预加载，首次访问全部命中缓存
+  let before = pager.stats();
+  for &root in &roots {
+    pager.page(root)?;
+  }
+  let after = pager.stats();
+  assert_eq!(after.cache_misses, before.cache_misses);
+  assert_eq!(after.cache_hits, before.cache_hits + roots.len() as u64);
+  assert_eq!(after.page_reads, before.page_reads);
+
+  Ok(())
+}
+
+#[test]
+fn pager_warm_loads_pages_without_counting_misses() -> TestResult {
+  let (tmp, file) = TempFile::new("rdb_pager_warm_explicit")?;
+  for id in 1..=3 {
+    write_page(&tmp, id, &Page::new(PageId::new(id), PageType::Leaf))?;
+  }
+
+  let pager = new_pager_for_test(file)?;
+  pager.warm(&[PageId::new(1), PageId::new(3)])?;
+  assert_eq!(pager.stats().page_reads, 2);
+  assert_eq!(pager.stats().cache_misses, 0);
+
+  pager.page(PageId::new(1))?;
+  pager.page(PageId::new(2))?;
+  assert_eq!(pager.stats().cache_hits, 1);
+  assert_eq!(pager.stats().cache_misses, 1);
+
+  Ok(())
+}
@@ -9,6 +9,7 @@ fn arb_page_type() -> impl Strategy<Value = PageType> {
     Just(PageType::Leaf),
     Just(PageType::Overflow),
     Just(PageType::Freelist),
+    Just(PageType::Meta),
   ]
 }
 
@@ -0,0 +1,195 @@
+use std::borrow::Cow;
+
+use rdb_domain::{DataType, PageId, Row, RowId, Value};
+use rdb_storage::compress::{is_compressed, NoCompression};
+use rdb_storage::page::{Page, PageType, PAGE_HEADER_BYTE_ORDER};
+use rdb_storage::record::{
+  decode_row, decode_row_typed, decode_row_with, decode_values, encode_row, encode_row_compressed,
+  encode_values, ByteOrder, DecodeError, TextDecoding, RECORD_BYTE_ORDER,
+};
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+fn sample_row() -> Row<'static> {
+  Row::new(
+    RowId::new(7),
+    vec![
+      Value::Integer(42),
+      Value::Text(Cow::Borrowed("alice")),
+      Value::Real(1.5),
+      Value::Null,
+      Value::Blob(Cow::Borrowed(&[0xDE, 0xAD])),
+    ],
+  )
+}
+
+#[test]
+fn record_roundtrip_all_types() -> TestResult {
+  let row = sample_row();
+  let decoded = decode_row(row.row_id, &encode_row(&row))?;
+
+  assert_eq!(decoded.row_id, RowId::new(7));
+  assert_eq!(decoded.values, row.values);
+
+  Ok(())
+}
+
+#[test]
+fn record_roundtrip_integer_widths() -> TestResult {
+  let ints = [0, 1, -1, 127, -128, 300, -70_000, 1 << 40, i64::MIN, i64::MAX];
+  let row = Row::new(
+    RowId::new(1),
+    ints.iter().map(|&i| Value::Integer(i)).collect(),
+  );
+
+  let decoded = decode_row(row.row_id, &encode_row(&row))?;
+  assert_eq!(decoded.values, row.values);
+
+  Ok(())
+}
+
+#[test]
+fn record_decode_typed_accepts_matching_types() -> TestResult {
+  let row = sample_row();
+  let types = [
+    DataType::Integer,
+    DataType::Text,
+    DataType::Real,
+    DataType::Integer, // NULL 可以出现在任何列
+    DataType::Blob,
+  ];
+
+  let decoded = decode_row_typed(row.row_id, &encode_row(&row), &types)?;
+  assert_eq!(decoded.values, row.values);
+
+  Ok(())
+}
+
+#[test]
+fn record_decode_typed_detects_type_drift() {
+  let row = sample_row();
+  // schema 里第 2 列被改成了 INTEGER
+  let types =
+    [DataType::Integer, DataType::Text, DataType::Integer, DataType::Text, DataType::Blob];
+
+  assert_eq!(
+    decode_row_typed(row.row_id, &encode_row(&row), &types).map(|r| r.values),
+    Err(DecodeError::TypeDrift { index: 2, expected: DataType::Integer, got: DataType::Real })
+  );
+
+  assert!(matches!(
+    decode_row_typed(row.row_id, &encode_row(&row), &types[..3]),
+    Err(DecodeError::ColumnCountMismatch { expected: 3, got: 5 })
+  ));
+}
+
+#[test]
+fn record_decode_rejects_truncated_body() {
+  let bytes = encode_row(&sample_row());
+  assert_eq!(
+    decode_row(RowId::new(1), &bytes[..bytes.len() - 1]).map(|r| r.values),
+    Err(DecodeError::Truncated)
+  );
+}
+
+/// 手工构造一个只有一列 TEXT 的 record
+fn text_record(bytes: &[u8]) -> Vec<u8> {
+  let serial_type = 13 + 2 * bytes.len() as u8;
+  let mut out = vec![2, serial_type];
+  out.extend_from_slice(bytes);
+  out
+}
+
+#[test]
+fn record_text_decoding_modes() -> TestResult {
+  let valid = text_record("héllo".as_bytes());
+  for mode in [TextDecoding::Strict, TextDecoding::Lossy] {
+    let row = decode_row_with(RowId::new(1), &valid, mode)?;
+    assert_eq!(row.values, vec![Value::Text(Cow::Borrowed("héllo"))]);
+  }
+
+  let invalid = text_record(&[b'a', 0xFF, b'b']);
+  assert_eq!(
+    decode_row_with(RowId::new(1), &invalid, TextDecoding::Strict).map(|r| r.values),
+    Err(DecodeError::InvalidUtf8 { index: 0 })
+  );
+  // 默认严格
+  assert!(decode_row(RowId::new(1), &invalid).is_err());
+
+  let lossy = decode_row_with(RowId::new(1), &invalid, TextDecoding::Lossy)?;
+  assert_eq!(lossy.values, vec![Value::Text(Cow::Borrowed("a\u{FFFD}b"))]);
+
+  Ok(())
+}
+
+#[test]
+fn record_integer_body_is_big_endian() -> TestResult {
+  assert_eq!(RECORD_BYTE_ORDER, ByteOrder::BigEndian);
+  assert_eq!(PAGE_HEADER_BYTE_ORDER, ByteOrder::LittleEndian);
+
+  // header: [size=3][serial 3][serial 7]，body: 3 字节整数 + 8 字节浮点
+  let bytes = encode_values(&[Value::Integer(0x01_02_03), Value::Real(1.0)]);
+  assert_eq!(&bytes[..3], &[3, 3, 7]);
+  assert_eq!(&bytes[3..6], &[0x01, 0x02, 0x03]);
+  assert_eq!(&bytes[6..], &1.0f64.to_be_bytes());
+
+  // 负数按大端补码截断，最高字节在前
+  let bytes = encode_values(&[Value::Integer(-2)]);
+  assert_eq!(&bytes[2..], &[0xFE]);
+  assert_eq!(decode_values(&bytes)?, [Value::Integer(-2)]);
+
+  // 对照：页头的 num_cells 是小端
+  let mut page = Page::new(PageId::new(1), PageType::Leaf);
+  let mut header = page.try_parse_header()?;
+  header.num_cells = 0x0102;
+  page.write_header(&header);
+  assert_eq!(&page.data()[3..5], &[0x02, 0x01]);
+
+  Ok(())
+}
+
+#[cfg(feature = "lz4")]
+#[test]
+fn record_lz4_roundtrip_shrinks_compressible_text() -> TestResult {
+  use rdb_storage::compress::Lz4;
+
+  let text = "the quick brown fox jumps over the lazy dog. ".repeat(200);
+  let row = Row::new(
+    RowId::new(3),
+    vec![Value::Integer(1), Value::Text(Cow::Owned(text))],
+  );
+
+  let plain = encode_row(&row);
+  let stored = encode_row_compressed(&row, &Lz4);
+  assert!(is_compressed(&stored));
+  assert!(stored.len() < plain.len() / 4);
+
+  let decoded = decode_row(row.row_id, &stored)?;
+  assert_eq!(decoded.values, row.values);
+
+  Ok(())
+}
+
+#[test]
+fn record_compression_keeps_plain_records_readable() -> TestResult {
+  // NoCompression 与不划算的压缩都保留原 record
+  let row = sample_row();
+  let stored = encode_row_compressed(&row, &NoCompression);
+  assert_eq!(stored, encode_row(&row));
+  assert!(!is_compressed(&stored));
+  assert_eq!(decode_row(row.row_id, &stored)?.values, row.values);
+
+  #[cfg(feature = "lz4")]
+  {
+    let stored = encode_row_compressed(&row, &rdb_storage::compress::Lz4);
+    assert_eq!(stored, encode_row(&row));
+  }
+
+  // 未知算法 id
+  assert_eq!(
+    decode_values(&[0x00, 0xEE, 0x01, 0x00]),
+    Err(DecodeError::UnsupportedCompression(0xEE))
+  );
+
+  Ok(())
+}
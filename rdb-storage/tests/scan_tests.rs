@@ -0,0 +1,62 @@
+use rdb_domain::{Column, ColumnId, DataType, Row, RowId, Table, TableId, Value};
+use rdb_storage::btree;
+use rdb_storage::record::encode_row;
+use rdb_storage::scan::{scan_rows, stream_rows};
+use rdb_storage::test_support::{PagerError, TempDb};
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+#[test]
+fn stream_rows_filters_and_maps() -> TestResult {
+  let (_db, mut pager) = TempDb::new("rdb_scan_stream")?;
+
+  // 足够多的行，跨越多个叶子页
+  let mut root = btree::create(&mut pager)?;
+  for i in 1..=400 {
+    let row = Row::new(
+      RowId::new(i),
+      vec![Value::Integer(i % 100), Value::Text("x".repeat(20).into())],
+    );
+    root = btree::insert(&mut pager, root, row.row_id, &encode_row(&row))?;
+  }
+  let table = Table::new(
+    TableId::new(1),
+    "t".to_string(),
+    vec![
+      Column::new(ColumnId::new(1), "n".to_string(), DataType::Integer),
+      Column::new(ColumnId::new(2), "s".to_string(), DataType::Text),
+    ],
+    None,
+    root,
+  );
+  assert!(btree::leaf_pages(&pager, root)?.len() > 1);
+
+  let rows: Vec<Row> = stream_rows(&pager, &table)?
+    .filter(|row| matches!(row.get(0), Some(Value::Integer(n)) if *n > 50))
+    .collect::<Result<_, PagerError>>()?;
+  assert_eq!(rows.len(), 4 * 49);
+  assert!(rows.windows(2).all(|w| w[0].row_id < w[1].row_id));
+  assert_eq!(rows[0].row_id, RowId::new(51));
+
+  // 与回调式扫描结果一致
+  let mut expected = Vec::new();
+  scan_rows(&pager, &table, |row| {
+    if matches!(row.get(0), Some(Value::Integer(n)) if *n > 50) {
+      expected.push(row.row_id);
+    }
+    Ok(())
+  })?;
+  assert_eq!(rows.iter().map(|r| r.row_id).collect::<Vec<_>>(), expected);
+
+  // map_values 只保留第一列
+  let first = stream_rows(&pager, &table)?
+    .map_values(|mut values| {
+      values.truncate(1);
+      values
+    })
+    .next()
+    .transpose()?;
+  assert_eq!(first.map(|r| r.values), Some(vec![Value::Integer(1)]));
+
+  Ok(())
+}
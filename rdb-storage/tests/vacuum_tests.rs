@@ -0,0 +1,132 @@
+use rdb_domain::{Column, ColumnId, DataType, PageId, RowId, Table, TableId};
+use rdb_storage::pager::Pager;
+use rdb_storage::test_support::{pager_flush_all, TempDb};
+use rdb_storage::vacuum::vacuum;
+use rdb_storage::{btree, catalog};
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+fn payload_for(row_id: i64, len: usize) -> Vec<u8> {
+  (0..len).map(|i| (row_id as usize + i) as u8).collect()
+}
+
+fn add_table(pager: &mut Pager<'static>, id: u32, name: &str, rows: i64) -> TestResult {
+  let mut root = btree::create(pager)?;
+  for row_id in 1..=rows {
+    root = btree::insert(pager, root, RowId::new(row_id), &payload_for(row_id, 120))?;
+  }
+  let table = Table::new(
+    TableId::new(id),
+    name.to_string(),
+    vec![Column::new(ColumnId::new(1), "id".to_string(), DataType::Integer)],
+    None,
+    root,
+  );
+  catalog::add_table(pager, &table)?;
+  Ok(())
+}
+
+fn roots(pager: &Pager<'_>) -> Result<Vec<PageId>, Box<dyn std::error::Error>> {
+  Ok(catalog::table_roots(
+    pager,
+    pager.db_header()?.catalog_root,
+  )?)
+}
+
+#[test]
+fn vacuum_after_deleting_half_the_rows_shrinks_the_file() -> TestResult {
+  let (src_db, mut src) = TempDb::new("rdb_vacuum_src")?;
+  add_table(&mut src, 1, "users", 400)?;
+  add_table(&mut src, 2, "orders", 30)?;
+
+  // 删掉 users 中所有偶数 rowid 的行
+  let users = roots(&src)?[0];
+  for row_id in (2..=400).step_by(2) {
+    assert!(btree::delete(&mut src, users, RowId::new(row_id))?);
+  }
+  pager_flush_all(&mut src)?;
+
+  // 目标路径必须不存在
+  let (dst_db, dst) = TempDb::new("rdb_vacuum_dst")?;
+  drop(dst);
+  std::fs::remove_file(dst_db.path())?;
+  vacuum(&src, dst_db.path())?;
+
+  let src_len = std::fs::metadata(src_db.path())?.len();
+  let dst_len = std::fs::metadata(dst_db.path())?.len();
+  assert!(
+    dst_len < src_len,
+    "vacuumed {dst_len} bytes, source {src_len} bytes"
+  );
+
+  let dst = dst_db.reopen()?;
+  assert_eq!(dst.db_header()?.freelist_head, None);
+  let new_roots = roots(&dst)?;
+  assert_eq!(new_roots.len(), 2);
+
+  // 剩下的行全部保留，payload 原样复制
+  assert_eq!(btree::count(&dst, new_roots[0])?, 200);
+  for row_id in 1..=400 {
+    let expected = (row_id % 2 == 1).then(|| payload_for(row_id, 120));
+    assert_eq!(
+      btree::get(&dst, new_roots[0], RowId::new(row_id))?,
+      expected
+    );
+  }
+  assert_eq!(btree::count(&dst, new_roots[1])?, 30);
+  for row_id in 1..=30 {
+    assert_eq!(
+      btree::get(&dst, new_roots[1], RowId::new(row_id))?,
+      Some(payload_for(row_id, 120))
+    );
+  }
+
+  Ok(())
+}
+
+#[test]
+fn vacuum_drops_tombstoned_rows() -> TestResult {
+  let (_src_db, mut src) = TempDb::new("rdb_vacuum_tombstone_src")?;
+  add_table(&mut src, 1, "users", 50)?;
+  let users = roots(&src)?[0];
+
+  // 软删除 rowid 7：cell 仍在页上，只打了墓碑标记
+  let victim = RowId::new(7);
+  for leaf in btree::leaf_pages(&src, users)? {
+    let mut page = src.page_mut(leaf)?;
+    if let Ok(index) = page.find_cell_by_rowid(victim)? {
+      page.tombstone_cell(index as u16)?;
+    }
+  }
+
+  let (dst_db, dst) = TempDb::new("rdb_vacuum_tombstone_dst")?;
+  drop(dst);
+  std::fs::remove_file(dst_db.path())?;
+  vacuum(&src, dst_db.path())?;
+
+  let dst = dst_db.reopen()?;
+  let new_root = roots(&dst)?[0];
+  assert_eq!(btree::get(&dst, new_root, victim)?, None);
+  assert_eq!(btree::count(&dst, new_root)?, 49);
+  for row_id in (1..=50).filter(|&id| id != 7) {
+    assert_eq!(
+      btree::get(&dst, new_root, RowId::new(row_id))?,
+      Some(payload_for(row_id, 120))
+    );
+  }
+
+  Ok(())
+}
+
+#[test]
+fn vacuum_refuses_to_overwrite_an_existing_file() -> TestResult {
+  let (_src_db, src) = TempDb::new("rdb_vacuum_src_exists")?;
+  let (dst_db, dst) = TempDb::new("rdb_vacuum_dst_exists")?;
+  drop(dst);
+  let before = std::fs::read(dst_db.path())?;
+
+  assert!(vacuum(&src, dst_db.path()).is_err());
+  assert_eq!(std::fs::read(dst_db.path())?, before);
+
+  Ok(())
+}